@@ -5,4 +5,16 @@ fn main() {
     {
         println!("cargo::rustc-link-arg-cdylib=/DEF:src\\exports.def");
     }
+
+    // shown in "About modtide", builds outside a git checkout leave it unset
+    println!("cargo::rerun-if-changed=.git/HEAD");
+    println!("cargo::rerun-if-changed=.git/refs/heads");
+    if let Ok(out) = std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        && out.status.success()
+    {
+        let hash = String::from_utf8_lossy(&out.stdout);
+        println!("cargo::rustc-env=MODTIDE_GIT_HASH={}", hash.trim());
+    }
 }
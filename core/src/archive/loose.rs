@@ -0,0 +1,119 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use super::ArchiveReader;
+use super::DirEntry;
+use super::FileType;
+use super::Monitor;
+use super::Result;
+
+// loose files dropped next to a `.mod` file, listed as `mods/<NAME>/...`
+pub struct Loose {
+    name: String,
+    paths: Vec<PathBuf>,
+}
+
+impl Loose {
+    // returns the `.mod` file when `paths` hold exactly one
+    pub fn mod_file(paths: &[PathBuf]) -> Option<&Path> {
+        let mut mods = paths.iter()
+            .filter(|p| p.is_file())
+            .filter(|p| p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("mod")));
+        let path = mods.next()?;
+        mods.next().is_none().then_some(path)
+    }
+
+    pub fn new(mod_file: &Path, paths: &[PathBuf]) -> Result<Self> {
+        let name = mod_file.file_stem()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| io::Error::other("invalid mod file name"))?;
+
+        let mut canonical = Vec::with_capacity(paths.len());
+        for path in paths {
+            canonical.push(path.canonicalize()?);
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            paths: canonical,
+        })
+    }
+
+    fn iter_all(
+        &self,
+        mut cb: impl FnMut(&Path, &Path, FileType) -> Result<()>,
+    ) -> Result<()> {
+        let root = Path::new("mods").join(&self.name);
+        cb(Path::new(""), Path::new("mods"), FileType::Dir)?;
+        cb(Path::new(""), &root, FileType::Dir)?;
+
+        let mut iter = Vec::new();
+        for path in &self.paths {
+            iter.push((path.clone(), root.clone()));
+        }
+
+        while let Some((path, parent)) = iter.pop() {
+            let suffix = parent.join(path.file_name().unwrap());
+
+            let type_ = match fs::metadata(&path)? {
+                meta if meta.is_file() => FileType::File,
+                meta if meta.is_dir() => FileType::Dir,
+                _ => continue,
+            };
+            cb(&path, &suffix, type_)?;
+
+            if type_.is_dir() {
+                for fd in fs::read_dir(&path)? {
+                    iter.push((fd?.path(), suffix.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ArchiveReader for Loose {
+    fn entries(
+        &self,
+        monitor: &Monitor,
+        cb: &mut dyn FnMut(DirEntry) -> Result<()>,
+    ) -> Result<()> {
+        self.iter_all(|path, suffix, type_| {
+            monitor.stopped()?;
+
+            let size = if type_.is_file() {
+                fs::metadata(path)?.len()
+            } else {
+                0
+            };
+            cb(DirEntry::from_path(suffix, type_, size))
+        })
+    }
+
+    fn copy(
+        &self,
+        monitor: &Monitor,
+        dest: &Path,
+        filter: &dyn Fn(&str) -> bool,
+        progress: &dyn Fn(u64),
+    ) -> Result<()> {
+        let dest = &super::long_path(dest);
+        self.iter_all(|path, suffix, type_| {
+            monitor.stopped()?;
+
+            if !filter(&super::raw::entry_name(suffix)) {
+                return Ok(());
+            }
+
+            if type_.is_dir() {
+                // parents may have been filtered out
+                fs::create_dir_all(dest.join(suffix))?;
+            } else if type_.is_file() {
+                progress(super::raw::copy_file(path, &dest.join(suffix))?);
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,1481 @@
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Result;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::thread;
+
+mod loose;
+use loose::Loose;
+mod raw;
+use raw::RawDir;
+mod sevenzip;
+use sevenzip::SevenZip;
+mod zip;
+use zip::Zip;
+
+use crate::package;
+use crate::package::Package;
+
+trait ArchiveReader: Send + Sync {
+    fn entries(
+        &self,
+        monitor: &Monitor,
+        cb: &mut dyn FnMut(DirEntry) -> Result<()>,
+    ) -> Result<()>;
+    // `filter` receives paths as listed by `entries` and skips entries returning false
+    // `progress` receives the size of each file once it's copied
+    fn copy(
+        &self,
+        monitor: &Monitor,
+        dest: &Path,
+        filter: &dyn Fn(&str) -> bool,
+        progress: &dyn Fn(u64),
+    ) -> Result<()>;
+
+    // copies out a single file, only meant for small files like `.dtmod` manifests
+    //
+    // `os_path` is `path` as it is on disk, see `ArchiveList::os_path`
+    fn read(&self, monitor: &Monitor, path: &str, os_path: &Path) -> Result<Vec<u8>> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let temp = std::env::temp_dir().join(format!(
+            "modtide-read-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed),
+        ));
+        fs::create_dir_all(&temp)?;
+        let res = self.copy(monitor, &temp, &|p| {
+            p == path || path.strip_prefix(p).is_some_and(|rest| rest.starts_with('/'))
+        }, &|_| ()).and_then(|()| fs::read(temp.join(os_path)));
+        let _ = fs::remove_dir_all(&temp);
+        res
+    }
+}
+
+fn open_archive(path: &Path) -> Result<Option<Box<dyn ArchiveReader>>> {
+    let meta = fs::metadata(path)?;
+    if meta.is_dir() {
+        return Ok(Some(Box::new(RawDir::new(path)?)));
+    } else if !meta.is_file() {
+        return Ok(None);
+    }
+
+    match detect_format(path)? {
+        Some(Format::Zip) => Ok(Some(Box::new(Zip::new(path)?))),
+        Some(Format::SevenZip) => match SevenZip::new(path)? {
+            Some(archive) => Ok(Some(Box::new(archive))),
+            None => Ok(None),
+        },
+        None => Ok(None),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Zip,
+    // anything handed to an installed 7-Zip
+    SevenZip,
+}
+
+// check magic bytes first so renamed downloads still open
+fn detect_format(path: &Path) -> Result<Option<Format>> {
+    let mut header = Vec::with_capacity(262);
+    File::open(path)?.take(262).read_to_end(&mut header)?;
+    Ok(format_from_magic(&header).or_else(|| {
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip")) {
+            Some(Format::Zip)
+        } else if SevenZip::supports(path) {
+            Some(Format::SevenZip)
+        } else {
+            None
+        }
+    }))
+}
+
+fn format_from_magic(data: &[u8]) -> Option<Format> {
+    const SEVEN_ZIP: [&[u8]; 8] = [
+        b"7z\xbc\xaf\x27\x1c",
+        b"Rar!\x1a\x07",
+        b"\x1f\x8b",
+        b"\xfd7zXZ\x00",
+        b"BZh",
+        b"\x28\xb5\x2f\xfd",
+        b"MSCF",
+        b"\x5d\x00\x00",
+    ];
+
+    if data.starts_with(b"PK\x03\x04") || data.starts_with(b"PK\x05\x06") {
+        Some(Format::Zip)
+    } else if SEVEN_ZIP.iter().any(|magic| data.starts_with(magic))
+        || data.get(257..262) == Some(b"ustar")
+    {
+        Some(Format::SevenZip)
+    } else {
+        None
+    }
+}
+
+fn extract_limit_error() -> io::Error {
+    io::Error::other("archive output larger than extract limit")
+}
+
+struct Monitor {
+    canceled: AtomicBool,
+    // sanity limit in bytes on the total extracted size of an archive
+    limit: u64,
+}
+
+impl Monitor {
+    fn new(limit: u64) -> Self {
+        Self {
+            canceled: AtomicBool::new(false),
+            limit,
+        }
+    }
+
+    fn cancel(&self) {
+        self.canceled.store(true, Ordering::SeqCst);
+    }
+
+    fn check_size(&self, total: u64) -> Result<()> {
+        if total > self.limit {
+            Err(extract_limit_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn stopped(&self) -> Result<()> {
+        if self.canceled.load(Ordering::SeqCst) {
+            Err(io::Error::new(io::ErrorKind::WouldBlock, "operation canceled"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn entry_cmp_(
+    ap: &str,
+    ak: FileType,
+    bp: &str,
+    bk: FileType,
+) -> std::cmp::Ordering {
+    let ac = ap.split('/').count();
+    let bc = bp.split('/').count();
+    entry_cmp_counted((ap, ac, ak), (bp, bc, bk))
+}
+
+// `entry_cmp_` with the number of path components already known
+fn entry_cmp_counted(
+    (ap, ac, ak): (&str, usize, FileType),
+    (bp, bc, bk): (&str, usize, FileType),
+) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let mut ap = ap.split('/');
+    let mut bp = bp.split('/');
+
+    let mut ord = std::cmp::Ordering::Equal;
+    let mut prefix_match = true;
+    let mut checked = 0;
+    while let (Some(a), Some(b)) = (ap.next(), bp.next()) {
+        let mut a = a.as_bytes().iter();
+        let mut b = b.as_bytes().iter();
+        while let (Some(a), Some(b)) = (a.next(), b.next()) {
+            let a = a.to_ascii_lowercase();
+            let b = b.to_ascii_lowercase();
+            ord = a.cmp(&b);
+            if ord.is_ne() {
+                break;
+            }
+        }
+
+        let shorter = a.next().cmp(&b.next());
+        ord = ord.then(shorter);
+
+        checked += 1;
+        if ord.is_ne() {
+            prefix_match = false;
+            break;
+        }
+    }
+
+    let count = ac.cmp(&bc);
+    let kind = ak.cmp(&bk);
+
+    if count.is_eq() && checked == ac {
+        kind.then(ord)
+    } else if prefix_match {
+        count
+    } else if checked == ac || checked == bc {
+        let prio = match count {
+            Ordering::Less => ak.cmp(&FileType::Dir),
+            Ordering::Equal => ak.cmp(&bk),
+            Ordering::Greater => FileType::Dir.cmp(&bk),
+        };
+        prio.then(ord)
+    } else {
+        ord.then(count)
+    }
+}
+
+fn entry_cmp(a: &DirEntry, b: &DirEntry) -> std::cmp::Ordering {
+    entry_cmp_counted(
+        (&a.path, a.depth as usize, a.kind),
+        (&b.path, b.depth as usize, b.kind),
+    )
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FileType {
+    Dir,
+    File,
+}
+
+impl FileType {
+    pub fn is_file(&self) -> bool {
+        matches!(*self, FileType::File)
+    }
+
+    pub fn is_dir(&self) -> bool {
+        matches!(*self, FileType::Dir)
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct DirEntry {
+    kind: FileType,
+    // number of components in `path`, cached for sorting
+    depth: u32,
+    path: Box<str>,
+    size: u64,
+    // the path relative to the archive when `path` is lossy, it isn't changed
+    // by `Prefix::prepend`
+    raw: Option<PathBuf>,
+}
+
+impl DirEntry {
+    fn new(path: &str, kind: FileType, size: u64) -> Self {
+        assert!(!path.contains(".."));
+        let mut entry = Self {
+            kind,
+            depth: 0,
+            path: Box::default(),
+            size,
+            raw: None,
+        };
+        entry.set_path(path.replace('\\', "/"));
+        entry
+    }
+
+    // for entries read from disk, names that aren't valid unicode are listed
+    // lossy and keep their real path for copying
+    fn from_path(path: &Path, kind: FileType, size: u64) -> Self {
+        let mut entry = Self::new(&path.to_string_lossy(), kind, size);
+        if path.to_str().is_none() {
+            entry.raw = Some(path.to_path_buf());
+        }
+        entry
+    }
+
+    fn set_path(&mut self, path: String) {
+        self.depth = path.split('/').count() as u32;
+        self.path = path.into_boxed_str();
+    }
+}
+
+pub struct ArchiveList<T = Vec<DirEntry>> {
+    entries: T,
+    offset: usize,
+}
+
+impl ArchiveList {
+    fn new(mut entries: Vec<DirEntry>) -> Self {
+        entries.sort_by(entry_cmp);
+        Self {
+            entries,
+            offset: 0,
+        }
+    }
+
+    // `path` as it is on disk relative to the archive
+    fn os_path(&self, path: &str) -> PathBuf {
+        self.entries.iter()
+            .find(|entry| *entry.path == *path)
+            .and_then(|entry| entry.raw.clone())
+            .unwrap_or_else(|| PathBuf::from(path))
+    }
+
+    // merge sorted lists, keeping the first of entries with the same path
+    //
+    // fails when a file and a folder have the same path ignoring case
+    fn compose(lists: Vec<ArchiveList>) -> Result<Self> {
+        let mut lists = lists.into_iter();
+        let mut entries = lists.next().map(|l| l.entries).unwrap_or_default();
+        for list in lists {
+            let mut merged: Vec<DirEntry> = Vec::with_capacity(entries.len() + list.entries.len());
+            let mut a = entries.into_iter().peekable();
+            let mut b = list.entries.into_iter().peekable();
+            loop {
+                let next = match (a.peek(), b.peek()) {
+                    (Some(x), Some(y)) => match entry_cmp(x, y) {
+                        std::cmp::Ordering::Less => a.next(),
+                        std::cmp::Ordering::Greater => b.next(),
+                        std::cmp::Ordering::Equal => {
+                            b.next();
+                            a.next()
+                        }
+                    },
+                    (Some(_), None) => a.next(),
+                    (None, _) => b.next(),
+                };
+                let Some(entry) = next else {
+                    break;
+                };
+
+                if let Some(prev) = merged.last()
+                    && entry.path.eq_ignore_ascii_case(&prev.path)
+                {
+                    if entry.kind != prev.kind {
+                        return Err(io::Error::new(io::ErrorKind::AlreadyExists,
+                            format!("\"{}\" is both a file and a folder", entry.path)));
+                    }
+                    continue;
+                }
+                merged.push(entry);
+            }
+            entries = merged;
+        }
+
+        Ok(Self {
+            entries,
+            offset: 0,
+        })
+    }
+}
+
+fn sublist<'a>(
+    e: &'a [DirEntry],
+    o: usize,
+    key: &str,
+) -> Option<ArchiveList<&'a [DirEntry]>> {
+    if let Ok(start) = e.binary_search_by(|p| entry_cmp_(&p.path[o..], p.kind, key, FileType::Dir))
+        && e[start].kind.is_dir()
+    {
+        let end = start + e[start..].partition_point(|p| {
+            let path = &p.path.as_bytes()[o..];
+            path.len() >= key.len()
+                && path[..key.len()].eq_ignore_ascii_case(key.as_bytes())
+                && (path.len() == key.len() || path[key.len()] == b'/')
+        });
+        let start = end.min(start + 1);
+        Some(ArchiveList {
+            entries: &e[start..end],
+            offset: o + key.len() + 1,
+        })
+    } else {
+        None
+    }
+}
+
+impl<T: AsRef<[DirEntry]>> ArchiveList<T> {
+    pub fn list(&self, key: &str) -> Option<ArchiveList<&[DirEntry]>> {
+        sublist(self.entries.as_ref(), self.offset, key)
+    }
+
+    // like `list` but accepts multiple components, e.g. `mods/name`
+    pub fn get(&self, path: &str) -> Option<ArchiveList<&[DirEntry]>> {
+        let mut list = ArchiveList {
+            entries: self.entries.as_ref(),
+            offset: self.offset,
+        };
+        for part in path.split('/').filter(|p| !p.is_empty()) {
+            list = sublist(list.entries, list.offset, part)?;
+        }
+        Some(list)
+    }
+
+    // all entries below `prefix` with paths relative to `prefix`
+    pub fn walk(&self, prefix: &str) -> impl Iterator<Item = (&str, FileType)> {
+        let list = self.get(prefix);
+        let (e, o) = list.map(|l| (l.entries, l.offset)).unwrap_or((&[], 0));
+        e.iter().map(move |entry| (&entry.path[o..], entry.kind))
+    }
+
+    pub fn size(&self) -> u64 {
+        self.entries.as_ref()
+            .iter()
+            .map(|entry| entry.size)
+            .sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, FileType, usize, u64)> {
+        let e = self.entries.as_ref();
+        e.iter()
+            .map(|entry| {
+                let path = &entry.path[self.offset..];
+                let mut iter = path.split('/');
+                let mut last = iter.next().expect("internal error from sorting ArchiveList");
+                let mut depth = 0;
+                for part in iter {
+                    last = part;
+                    depth += 1;
+                }
+                (last, entry.kind, depth, entry.size)
+            })
+    }
+
+    // rows of a folder tree, entries below collapsed folders are left out and
+    // folder sizes are everything below them
+    pub fn tree(&self, is_expanded: impl Fn(&str) -> bool) -> Vec<TreeRow<'_>> {
+        let mut rows = Vec::new();
+        let mut collapsed: Option<&str> = None;
+        for ((path, kind), (name, _, depth, size)) in self.walk("").zip(self.iter()) {
+            if let Some(folder) = collapsed {
+                let inside = path.len() > folder.len()
+                    && path.as_bytes()[folder.len()] == b'/'
+                    && path[..folder.len()].eq_ignore_ascii_case(folder);
+                if inside {
+                    continue;
+                }
+                collapsed = None;
+            }
+
+            let size = match kind {
+                FileType::Dir => {
+                    if !is_expanded(path) {
+                        collapsed = Some(path);
+                    }
+                    self.get(path).map_or(0, |list| list.size())
+                }
+                FileType::File => size,
+            };
+            rows.push(TreeRow {
+                path,
+                name,
+                kind,
+                depth,
+                size,
+                expanded: collapsed.is_none(),
+            });
+        }
+        rows
+    }
+}
+
+pub struct TreeRow<'a> {
+    pub path: &'a str,
+    pub name: &'a str,
+    pub kind: FileType,
+    pub depth: usize,
+    pub size: u64,
+    // always true for files
+    pub expanded: bool,
+}
+
+#[derive(Clone)]
+pub enum Prefix {
+    None,
+    Mods,
+    // strip wrapper folder and optionally nest the remaining entries under `mods/`
+    Strip {
+        wrapper: String,
+        mods: bool,
+    },
+    // archive folders to installed paths from a `.dtmod`, first match wins
+    Map(Vec<(String, String)>),
+}
+
+impl Prefix {
+    // map a path inside the archive to its installed path
+    fn apply(&self, path: &str) -> Option<String> {
+        let (path, mods) = match self {
+            Prefix::None => return Some(path.to_string()),
+            Prefix::Map(install) => {
+                return install.iter().find_map(|(from, to)| {
+                    let rest = path.strip_prefix(from.as_str())?;
+                    (rest.is_empty() || rest.starts_with('/')).then(|| format!("{to}{rest}"))
+                });
+            }
+            Prefix::Mods => (path, true),
+            Prefix::Strip { wrapper, mods } => {
+                let path = path.strip_prefix(wrapper.as_str())?;
+                let path = if path.is_empty() {
+                    path
+                } else {
+                    path.strip_prefix('/')?
+                };
+                (path, *mods)
+            }
+        };
+
+        Some(match (mods, path.is_empty()) {
+            (false, _) => path.to_string(),
+            (true, true) => "mods".to_string(),
+            (true, false) => format!("mods/{path}"),
+        })
+    }
+
+    fn prepend(&self, list: &mut ArchiveList) {
+        let mods = match self {
+            Prefix::None => return,
+            Prefix::Map(_) => {
+                list.entries.retain_mut(|entry| match self.apply(&entry.path) {
+                    Some(path) => {
+                        entry.set_path(path);
+                        true
+                    }
+                    None => false,
+                });
+
+                // install paths can be nested deeper than anything in the archive
+                let mut parents = Vec::new();
+                for entry in &list.entries {
+                    let mut path = &*entry.path;
+                    while let Some((parent, _)) = path.rsplit_once('/') {
+                        path = parent;
+                        parents.push(parent.to_string());
+                    }
+                }
+                parents.sort();
+                parents.dedup();
+                for parent in parents {
+                    if !list.entries.iter().any(|entry| *entry.path == *parent) {
+                        list.entries.push(DirEntry::new(&parent, FileType::Dir, 0));
+                    }
+                }
+                list.entries.sort_by(entry_cmp);
+                return;
+            }
+            Prefix::Mods => true,
+            Prefix::Strip { wrapper, mods } => {
+                let wrapper = format!("{wrapper}/");
+                list.entries.retain_mut(|entry| {
+                    if let Some(path) = entry.path.strip_prefix(&wrapper) {
+                        entry.set_path(path.to_string());
+                        true
+                    } else {
+                        false
+                    }
+                });
+                list.entries.sort_by(entry_cmp);
+                *mods
+            }
+        };
+
+        if mods {
+            let prefix = "mods/";
+            for entry in &mut list.entries {
+                entry.set_path(format!("{prefix}{}", entry.path));
+            }
+
+            let parent = prefix.strip_suffix("/").unwrap();
+            list.entries.insert(0, DirEntry::new(parent, FileType::Dir, 0));
+        }
+    }
+}
+
+// how one archive of an `Archive` is installed
+struct Install {
+    prefix: Prefix,
+    // on disk paths of entries listed lossy, by listed path
+    raw: Vec<(String, PathBuf)>,
+}
+
+impl Install {
+    fn new(prefix: Prefix, list: &ArchiveList) -> Self {
+        let raw = list.entries.iter()
+            .filter_map(|entry| Some((entry.path.to_string(), entry.raw.clone()?)))
+            .collect();
+        Self {
+            prefix,
+            raw,
+        }
+    }
+
+    // where `path` as listed was copied to below `dir`, verbatim paths don't
+    // take '/' so listed paths are pushed by component
+    fn resolve(&self, dir: &Path, path: &str) -> PathBuf {
+        match self.raw.iter().find(|(p, _)| p == path) {
+            Some((_, raw)) => dir.join(raw),
+            None => entry_path(dir, path),
+        }
+    }
+}
+
+struct ArchiveInner {
+    monitor: Monitor,
+    archives: Vec<(PathBuf, Box<dyn ArchiveReader>)>,
+    // paths that couldn't be opened when other paths could
+    failed: Vec<(PathBuf, String)>,
+    fixup: fn(&Path, &ArchiveList, Option<&Package>) -> Result<Prefix>,
+}
+
+pub struct Archive(Arc<ArchiveInner>);
+
+impl Archive {
+    // `limit` is the most bytes an install may extract, see `Config::extract_limit`
+    pub fn new(
+        paths: &[PathBuf],
+        limit: u64,
+        fixup: fn(&Path, &ArchiveList, Option<&Package>) -> Result<Prefix>,
+    ) -> Result<Self> {
+        let mut archives = Vec::with_capacity(paths.len());
+        let mut failed = Vec::new();
+        if let Some(mod_file) = Loose::mod_file(paths) {
+            // files dropped alongside a `.mod` file make up a single mod
+            let archive: Box<dyn ArchiveReader> = Box::new(Loose::new(mod_file, paths)?);
+            archives.push((mod_file.to_path_buf(), archive));
+        } else {
+            let mut first_err = None;
+            for path in paths {
+                let archive = open_archive(path).and_then(|archive| {
+                    archive.ok_or_else(|| io::Error::new(io::ErrorKind::NotADirectory,
+                        "failed to find valid archive"))
+                });
+                match archive {
+                    Ok(archive) => archives.push((path.to_path_buf(), archive)),
+                    Err(err) => {
+                        failed.push((path.to_path_buf(), err.to_string()));
+                        first_err.get_or_insert(err);
+                    }
+                }
+            }
+
+            if archives.is_empty()
+                && let Some(err) = first_err
+            {
+                return Err(err);
+            }
+        }
+        Ok(Archive(Arc::new(ArchiveInner {
+            monitor: Monitor::new(limit),
+            archives,
+            failed,
+            fixup,
+        })))
+    }
+
+    // number of entries listed before `preview` is sent for large archives
+    const PREVIEW_LEN: usize = 512;
+
+    pub fn view(
+        &self,
+        preview: impl FnOnce(ArchiveList) + Send + 'static,
+        complete: impl FnOnce(Result<ArchiveView>) + Send + 'static,
+    ) {
+        let dispatch = self.0.clone();
+        thread::spawn(move || {
+            let d = &dispatch;
+            let fixup = d.fixup;
+            let mut lists = Vec::new();
+            let mut installs = Vec::new();
+            let mut failed = d.failed.clone();
+            let mut first_err = None;
+            let mut preview = Some(preview);
+            for (p, rdr) in &d.archives {
+                let mut entries = Vec::new();
+                let res = rdr.entries(&d.monitor, &mut |entry| {
+                    if is_junk(&entry.path) {
+                        return Ok(());
+                    }
+                    entries.push(entry);
+                    if entries.len() == Self::PREVIEW_LEN
+                        && let Some(preview) = preview.take()
+                    {
+                        preview(ArchiveList::new(entries.clone()));
+                    }
+                    Ok(())
+                });
+                let mut list = ArchiveList::new(entries);
+                let res = res
+                    .and_then(|_| match find_package(&list) {
+                        Some(path) => {
+                            let data = rdr.read(&d.monitor, path, &list.os_path(path))?;
+                            Package::parse(path, &String::from_utf8_lossy(&data)).map(Some)
+                        }
+                        None => Ok(None),
+                    })
+                    .and_then(|package| fixup(p, &list, package.as_ref()));
+
+                // keep going with the remaining archives unless canceled
+                let prefix = match res {
+                    Ok(prefix) => prefix,
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                        complete(Err(err));
+                        return;
+                    }
+                    Err(err) => {
+                        failed.push((p.clone(), err.to_string()));
+                        first_err.get_or_insert(err);
+                        installs.push(None);
+                        continue;
+                    }
+                };
+                installs.push(Some(Install::new(prefix.clone(), &list)));
+                prefix.prepend(&mut list);
+                lists.push(list);
+            }
+
+            if lists.is_empty() {
+                let err = match first_err {
+                    Some(err) if failed.len() == 1 => err,
+                    _ => {
+                        let mut msg = String::new();
+                        for (path, err) in &failed {
+                            let name = path.file_name().unwrap_or(path.as_os_str());
+                            msg.push_str(&format!("{}: {err}\n", name.to_string_lossy()));
+                        }
+                        io::Error::other(msg)
+                    }
+                };
+                complete(Err(err));
+                return;
+            }
+
+            let list = match ArchiveList::compose(lists) {
+                Ok(list) => list,
+                Err(err) => {
+                    complete(Err(err));
+                    return;
+                }
+            };
+            let executables = find_executables(&list);
+            complete(Ok(ArchiveView {
+                inner: dispatch,
+                installs,
+                list,
+                executables,
+                failed,
+                copied: false,
+            }));
+        });
+    }
+}
+
+impl Drop for Archive {
+    fn drop(&mut self) {
+        self.0.monitor.cancel();
+    }
+}
+
+pub struct ArchiveView {
+    inner: Arc<ArchiveInner>,
+    // `None` for archives that failed to list
+    installs: Vec<Option<Install>>,
+    list: ArchiveList,
+    executables: Vec<String>,
+    failed: Vec<(PathBuf, String)>,
+    copied: bool,
+}
+
+impl ArchiveView {
+    pub fn list(&self) -> &ArchiveList {
+        &self.list
+    }
+
+    // dropped paths that were left out along with why
+    pub fn failed(&self) -> &[(PathBuf, String)] {
+        &self.failed
+    }
+
+    // installed paths of executable files outside `binaries/plugins`
+    pub fn executables(&self) -> &[String] {
+        &self.executables
+    }
+
+    // `exclude` lists paths relative to `dest` that are left out of the install
+    //
+    // `progress` gets the bytes copied so far and the total, called again only
+    // once the copied fraction moved by at least 1%
+    pub fn copy(
+        &mut self,
+        dest: &Path,
+        exclude: Vec<String>,
+        policy: CopyPolicy,
+        progress: impl Fn(u64, u64) + Send + 'static,
+        complete: impl FnOnce(Result<u64>) + Send + 'static,
+    ) {
+        assert!(!self.copied);
+        self.copied = true;
+
+        assert!(self.installs.len() == self.inner.archives.len());
+        let installs = core::mem::take(&mut self.installs);
+        let inner = self.inner.clone();
+        let total = install_size(&self.list, &exclude);
+
+        let dest = long_path(dest);
+        thread::spawn(move || {
+            let copied = std::cell::Cell::new(0u64);
+            let reported = std::cell::Cell::new(0u64);
+            // every reader checks the monitor, so going over the limit while
+            // copying stops it like canceling
+            let over_limit = std::cell::Cell::new(false);
+            let on_copy = |len| {
+                copied.set(copied.get() + len);
+                if inner.monitor.check_size(copied.get()).is_err() {
+                    over_limit.set(true);
+                    inner.monitor.cancel();
+                }
+                let percent = copied.get() * 100 / total.max(1);
+                if percent != reported.get() {
+                    reported.set(percent);
+                    progress(copied.get().min(total), total);
+                }
+            };
+
+            let staging = dest.join(STAGING_DIR);
+            let mut journal = Journal::new(dest.join(BACKUP_DIR), policy);
+            let res = inner.monitor.check_size(total)
+                .and_then(|()| Self::copy_staged(&inner, &installs, &exclude, &staging, &on_copy))
+                .and_then(|count| {
+                    inner.monitor.stopped()?;
+                    journal.commit(&staging, &dest)?;
+                    Ok(count)
+                })
+                .map_err(|err| if over_limit.get() { extract_limit_error() } else { err });
+
+            if res.is_err() {
+                journal.rollback();
+            }
+            for dir in [&staging, &journal.backup] {
+                if let Err(err) = fs::remove_dir_all(dir)
+                    && err.kind() != io::ErrorKind::NotFound
+                {
+                    eprintln!("failed to remove {dir:?}: {err:?}");
+                }
+            }
+            complete(res);
+        });
+    }
+
+    fn copy_staged(
+        inner: &ArchiveInner,
+        installs: &[Option<Install>],
+        exclude: &[String],
+        staging: &Path,
+        progress: &dyn Fn(u64),
+    ) -> Result<u64> {
+        // leftover from an interrupted install
+        match fs::remove_dir_all(staging) {
+            Ok(()) => (),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+            Err(err) => return Err(err),
+        }
+        fs::create_dir(staging)?;
+
+        let mut mods_exists = false;
+        let mut count = 0;
+        for (i, install) in installs.iter().enumerate() {
+            let Some(install) = install else {
+                continue;
+            };
+            let prefix = &install.prefix;
+            let rdr = &inner.archives[i].1;
+
+            let filter = |path: &str| {
+                if is_junk(path) {
+                    return false;
+                }
+                let Some(path) = prefix.apply(path) else {
+                    return false;
+                };
+                !exclude.iter().any(|ex| {
+                    path.strip_prefix(ex.as_str())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+                })
+            };
+
+            if let Prefix::Map(map) = prefix {
+                let tmp = staging.join(format!(".map-{i}"));
+                fs::create_dir(&tmp)?;
+                rdr.copy(&inner.monitor, &tmp, &filter, progress)?;
+                for (from, to) in map {
+                    // nested folders were already moved by longer entries
+                    let from = install.resolve(&tmp, from);
+                    if fs::symlink_metadata(&from).is_err() {
+                        continue;
+                    }
+                    let to = entry_path(staging, to);
+                    fs::create_dir_all(to.parent().unwrap())?;
+                    move_tree(&from, &to)?;
+                }
+                fs::remove_dir_all(&tmp)?;
+                count += 1;
+                continue;
+            }
+
+            let (wrapper, mods) = match prefix {
+                Prefix::Map(_) => unreachable!(),
+                Prefix::None => (None, false),
+                Prefix::Mods => (None, true),
+                Prefix::Strip { wrapper, mods } => (Some(wrapper), *mods),
+            };
+
+            let _owner;
+            let path = if mods {
+                _owner = staging.join("mods");
+                if !mods_exists {
+                    mods_exists = true;
+                    // may exist from a `.dtmod` install path
+                    fs::create_dir_all(&_owner)?;
+                }
+                &_owner
+            } else {
+                staging
+            };
+
+            if let Some(wrapper) = wrapper {
+                let tmp = staging.join(format!(".strip-{i}"));
+                fs::create_dir(&tmp)?;
+                rdr.copy(&inner.monitor, &tmp, &filter, progress)?;
+                move_tree(&install.resolve(&tmp, wrapper), path)?;
+                fs::remove_dir_all(&tmp)?;
+            } else {
+                rdr.copy(&inner.monitor, path, &filter, progress)?;
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+// total size of the files in `list` that aren't under an `exclude` path
+fn install_size(list: &ArchiveList, exclude: &[String]) -> u64 {
+    list.entries.iter()
+        .filter(|entry| entry.kind.is_file())
+        .filter(|entry| !exclude.iter().any(|ex| {
+            entry.path.strip_prefix(ex.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        }))
+        .map(|entry| entry.size)
+        .sum()
+}
+
+// shallowest `<NAME>/<NAME>.dtmod` in `list`
+fn find_package(list: &ArchiveList) -> Option<&str> {
+    list.walk("")
+        .filter(|(path, ty)| ty.is_file() && {
+            let mut parts = path.rsplit('/');
+            let name = parts.next().unwrap_or("");
+            parts.next().is_some_and(|dir| {
+                name.strip_suffix(package::EXTENSION).and_then(|n| n.strip_suffix('.')) == Some(dir)
+            })
+        })
+        .map(|(path, _)| path)
+        .min_by_key(|path| path.split('/').count())
+}
+
+const STAGING_DIR: &str = ".modtide-staging";
+
+const BACKUP_DIR: &str = ".modtide-backup";
+
+// move `from` to `to`, merging into directories that already exist
+fn move_tree(from: &Path, to: &Path) -> Result<()> {
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(_) if from.is_dir() && to.is_dir() => {
+            for fd in fs::read_dir(from)? {
+                let fd = fd?;
+                move_tree(&fd.path(), &to.join(fd.file_name()))?;
+            }
+            Ok(())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+// OS metadata that shouldn't be installed or affect layout detection
+fn is_junk(path: &str) -> bool {
+    const JUNK: [&str; 4] = ["__MACOSX", ".DS_Store", "Thumbs.db", "desktop.ini"];
+
+    path.split('/').any(|part| {
+        part.starts_with("._") || JUNK.iter().any(|j| j.eq_ignore_ascii_case(part))
+    })
+}
+
+fn find_executables(list: &ArchiveList) -> Vec<String> {
+    const EXTENSIONS: [&str; 6] = ["exe", "dll", "bat", "cmd", "ps1", "vbs"];
+
+    list.walk("")
+        .filter(|(_, ty)| ty.is_file())
+        .map(|(path, _)| path)
+        .filter(|path| {
+            let plugins = path.get(..17)
+                .is_some_and(|p| p.eq_ignore_ascii_case("binaries/plugins/"));
+            let ext = path.rsplit_once('.').map(|(_, ext)| ext).unwrap_or("");
+            !plugins && EXTENSIONS.iter().any(|e| e.eq_ignore_ascii_case(ext))
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+// extended-length form of `path` so deeply nested mods don't hit MAX_PATH
+// verbatim paths skip normalization so they must be absolute with `\` separators
+fn long_path(path: &Path) -> PathBuf {
+    use std::path::Component;
+    use std::path::Prefix as PathPrefix;
+
+    let Ok(abs) = std::path::absolute(path) else {
+        return path.to_path_buf();
+    };
+
+    let mut components = abs.components();
+    let mut out = match components.next() {
+        Some(Component::Prefix(prefix)) => match prefix.kind() {
+            PathPrefix::Disk(_) => {
+                let mut out = std::ffi::OsString::from(r"\\?\");
+                out.push(prefix.as_os_str());
+                out
+            }
+            PathPrefix::UNC(server, share) => {
+                let mut out = std::ffi::OsString::from(r"\\?\UNC\");
+                out.push(server);
+                out.push(r"\");
+                out.push(share);
+                out
+            }
+            _ => return abs,
+        },
+        _ => return abs,
+    };
+
+    for component in components {
+        match component {
+            Component::Normal(part) => {
+                out.push(r"\");
+                out.push(part);
+            }
+            Component::RootDir | Component::CurDir => (),
+            Component::ParentDir | Component::Prefix(_) => return abs,
+        }
+    }
+    PathBuf::from(out)
+}
+
+// join a `/` separated entry name, which verbatim paths don't split on
+fn entry_path(dest: &Path, name: &str) -> PathBuf {
+    let mut path = dest.to_path_buf();
+    path.extend(name.split('/').filter(|p| !p.is_empty()));
+    path
+}
+
+fn remove_path(path: &Path) -> Result<()> {
+    let res = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    match res {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+// how files that already exist in the install directory are handled
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CopyPolicy {
+    #[default]
+    Overwrite,
+    // keep existing files and only add new ones
+    SkipExisting,
+    // replace existing files only with newer versions
+    MergeNewOnly,
+}
+
+// records moves out of the staging directory so a failed install can be undone
+struct Journal {
+    backup: PathBuf,
+    policy: CopyPolicy,
+    created: Vec<PathBuf>,
+    replaced: Vec<(PathBuf, PathBuf)>,
+}
+
+impl Journal {
+    fn new(backup: PathBuf, policy: CopyPolicy) -> Self {
+        Self {
+            backup,
+            policy,
+            created: Vec::new(),
+            replaced: Vec::new(),
+        }
+    }
+
+    // move staged entries into `dest`, merging with directories that already exist
+    fn commit(&mut self, staging: &Path, dest: &Path) -> Result<()> {
+        for fd in fs::read_dir(staging)? {
+            let fd = fd?;
+            self.move_entry(&fd.path(), &dest.join(fd.file_name()))?;
+        }
+        Ok(())
+    }
+
+    fn move_entry(&mut self, from: &Path, to: &Path) -> Result<()> {
+        if from.is_dir() && to.is_dir() {
+            return self.commit(from, to);
+        }
+
+        if let Ok(meta) = fs::symlink_metadata(to) {
+            let replace = match self.policy {
+                CopyPolicy::Overwrite => true,
+                CopyPolicy::SkipExisting => false,
+                CopyPolicy::MergeNewOnly => fs::metadata(from)?.modified()? > meta.modified()?,
+            };
+            // skipped entries are left in staging
+            if !replace {
+                return Ok(());
+            }
+
+            if self.replaced.is_empty() {
+                remove_path(&self.backup)?;
+                fs::create_dir(&self.backup)?;
+            }
+            let backup = self.backup.join(self.replaced.len().to_string());
+            fs::rename(to, &backup)?;
+            self.replaced.push((to.to_path_buf(), backup));
+        } else {
+            self.created.push(to.to_path_buf());
+        }
+
+        fs::rename(from, to)
+    }
+
+    fn rollback(&mut self) {
+        for path in self.created.drain(..).rev() {
+            if let Err(err) = remove_path(&path) {
+                eprintln!("failed to remove {path:?}: {err:?}");
+            }
+        }
+
+        for (path, backup) in self.replaced.drain(..).rev() {
+            let res = remove_path(&path)
+                .and_then(|_| fs::rename(&backup, &path));
+            if let Err(err) = res {
+                eprintln!("failed to restore {path:?}: {err:?}");
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::SystemTime;
+
+    fn test_list() -> ArchiveList {
+        ArchiveList::new(vec![
+            DirEntry::new("c", FileType::File, 3),
+            DirEntry::new("a/x", FileType::File, 1),
+            DirEntry::new("b", FileType::Dir, 0),
+            DirEntry::new("a/b/y", FileType::File, 2),
+            DirEntry::new("a", FileType::Dir, 0),
+            DirEntry::new("b/z", FileType::File, 3),
+            DirEntry::new("a/b", FileType::Dir, 0),
+        ])
+    }
+
+    #[test]
+    fn compose() {
+        let a = ArchiveList::new(vec![
+            DirEntry::new("mods", FileType::Dir, 0),
+            DirEntry::new("mods/a", FileType::Dir, 0),
+            DirEntry::new("mods/a/a.mod", FileType::File, 1),
+        ]);
+        let b = ArchiveList::new(vec![
+            DirEntry::new("mods", FileType::Dir, 0),
+            DirEntry::new("mods/A", FileType::Dir, 0),
+            DirEntry::new("mods/A/a.mod", FileType::File, 2),
+            DirEntry::new("mods/b", FileType::Dir, 0),
+            DirEntry::new("mods/b/b.mod", FileType::File, 3),
+            DirEntry::new("binaries", FileType::Dir, 0),
+        ]);
+
+        let list = ArchiveList::compose(vec![a, b]).unwrap();
+        let walked = list.walk("")
+            .map(|(p, _)| p.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(walked, ["binaries", "mods", "mods/a", "mods/a/a.mod", "mods/b", "mods/b/b.mod"]);
+        assert_eq!(list.size(), 4);
+
+        let a = ArchiveList::new(vec![
+            DirEntry::new("mods", FileType::Dir, 0),
+            DirEntry::new("mods/a", FileType::File, 1),
+        ]);
+        let b = ArchiveList::new(vec![
+            DirEntry::new("mods", FileType::Dir, 0),
+            DirEntry::new("mods/A", FileType::Dir, 0),
+        ]);
+        assert!(ArchiveList::compose(vec![a, b]).is_err());
+    }
+
+    #[test]
+    fn get() {
+        let list = test_list();
+
+        let paths = |list: ArchiveList<&[DirEntry]>| list.walk("")
+            .map(|(p, _)| p.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(paths(list.get("a").unwrap()), ["b", "b/y", "x"]);
+        assert_eq!(paths(list.get("a/b").unwrap()), ["y"]);
+        assert_eq!(paths(list.get("/a/b/").unwrap()), ["y"]);
+        assert_eq!(paths(list.get("b").unwrap()), ["z"]);
+        assert_eq!(list.get("").unwrap().size(), 9);
+        assert!(list.get("c").is_none());
+        assert!(list.get("a/y").is_none());
+        assert!(list.get("d/e").is_none());
+    }
+
+    #[test]
+    fn tree() {
+        let list = test_list();
+
+        let rows = list.tree(|path| path != "a/b");
+        let rows = rows.iter()
+            .map(|row| (row.path, row.depth, row.size, row.expanded))
+            .collect::<Vec<_>>();
+        assert_eq!(rows, [
+            ("a", 0, 3, true),
+            ("a/b", 1, 2, false),
+            ("a/x", 1, 1, true),
+            ("b", 0, 3, true),
+            ("b/z", 1, 3, true),
+            ("c", 0, 3, true),
+        ]);
+        assert_eq!(list.tree(|_| false).len(), 3);
+    }
+
+    #[test]
+    fn walk() {
+        let list = test_list();
+
+        let walked = list.walk("a")
+            .map(|(p, ty)| (p, ty.is_dir()))
+            .collect::<Vec<_>>();
+        assert_eq!(walked, [("b", true), ("b/y", false), ("x", false)]);
+        assert_eq!(list.walk("a/b/y").count(), 0);
+        assert_eq!(list.walk("").count(), 7);
+    }
+
+    #[test]
+    fn prefix_apply() {
+        let strip = Prefix::Strip {
+            wrapper: "pack/v1".to_string(),
+            mods: true,
+        };
+        assert_eq!(strip.apply("pack/v1/a/a.mod").as_deref(), Some("mods/a/a.mod"));
+        assert_eq!(strip.apply("pack/v1").as_deref(), Some("mods"));
+        assert_eq!(strip.apply("pack/v10/a/a.mod"), None);
+        assert_eq!(strip.apply("readme.txt"), None);
+
+        assert_eq!(Prefix::Mods.apply("a/a.mod").as_deref(), Some("mods/a/a.mod"));
+
+        let map = Prefix::Map(vec![
+            ("pack/a/plugins".to_string(), "binaries/plugins".to_string()),
+            ("pack/a".to_string(), "mods/a".to_string()),
+        ]);
+        assert_eq!(map.apply("pack/a/a.mod").as_deref(), Some("mods/a/a.mod"));
+        assert_eq!(map.apply("pack/a/plugins/x.dll").as_deref(), Some("binaries/plugins/x.dll"));
+        assert_eq!(map.apply("pack/ab/x"), None);
+        assert_eq!(map.apply("pack"), None);
+        assert_eq!(Prefix::None.apply("binaries/x").as_deref(), Some("binaries/x"));
+    }
+
+    #[test]
+    fn install_resolve() {
+        let list = ArchiveList::new(vec![
+            DirEntry::new("pack", FileType::Dir, 0),
+            DirEntry::new("pack/v1", FileType::Dir, 0),
+            DirEntry::new("pack/v1/a", FileType::Dir, 0),
+        ]);
+        let strip = Prefix::Strip {
+            wrapper: "pack/v1".to_string(),
+            mods: true,
+        };
+        let install = Install::new(strip, &list);
+        let dir = Path::new(r"\\?\C:\staging");
+        let path = install.resolve(dir, "pack/v1");
+        assert_eq!(path, dir.join("pack").join("v1"));
+        assert_eq!(path.components().count(), dir.components().count() + 2);
+    }
+
+    #[test]
+    fn executables() {
+        let list = ArchiveList::new(vec![
+            DirEntry::new("binaries", FileType::Dir, 0),
+            DirEntry::new("binaries/plugins", FileType::Dir, 0),
+            DirEntry::new("binaries/plugins/a.dll", FileType::File, 1),
+            DirEntry::new("binaries/b.dll", FileType::File, 1),
+            DirEntry::new("mods", FileType::Dir, 0),
+            DirEntry::new("mods/a", FileType::Dir, 0),
+            DirEntry::new("mods/a/a.mod", FileType::File, 1),
+            DirEntry::new("mods/a/run.BAT", FileType::File, 1),
+        ]);
+        assert_eq!(find_executables(&list), ["binaries/b.dll", "mods/a/run.BAT"]);
+    }
+
+    #[test]
+    fn magic() {
+        assert_eq!(format_from_magic(b"PK\x03\x04\x14\x00"), Some(Format::Zip));
+        assert_eq!(format_from_magic(b"7z\xbc\xaf\x27\x1c\x00\x04"), Some(Format::SevenZip));
+        assert_eq!(format_from_magic(b"Rar!\x1a\x07\x01\x00"), Some(Format::SevenZip));
+
+        let mut tar = vec![0; 512];
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(format_from_magic(&tar), Some(Format::SevenZip));
+
+        assert_eq!(format_from_magic(b"-- lua"), None);
+        assert_eq!(format_from_magic(b""), None);
+    }
+
+    #[test]
+    fn junk() {
+        assert!(is_junk("__MACOSX/a/._a.mod"));
+        assert!(is_junk("a/.DS_Store"));
+        assert!(is_junk("mods/a/thumbs.db"));
+        assert!(!is_junk("mods/a/a.mod"));
+        assert!(!is_junk("mods/.a/a.mod"));
+    }
+
+    #[test]
+    fn entry_paths() {
+        let dest = Path::new("dest");
+        assert_eq!(entry_path(dest, "a/b/c"), dest.join("a").join("b").join("c"));
+        assert_eq!(entry_path(dest, "a/"), dest.join("a"));
+    }
+
+    #[test]
+    fn journal_policy() {
+        let dir = std::env::temp_dir().join(format!("modtide-journal-{}", std::process::id()));
+        let run = |policy, old: SystemTime| {
+            let _ = fs::remove_dir_all(&dir);
+            let staging = dir.join("staging");
+            let dest = dir.join("dest");
+            fs::create_dir_all(staging.join("a")).unwrap();
+            fs::create_dir_all(dest.join("a")).unwrap();
+            fs::write(staging.join("a/x"), "new").unwrap();
+            fs::write(staging.join("a/y"), "new").unwrap();
+            fs::write(dest.join("a/x"), "old").unwrap();
+            File::options().write(true).open(dest.join("a/x")).unwrap()
+                .set_modified(old).unwrap();
+
+            Journal::new(dir.join("backup"), policy).commit(&staging, &dest).unwrap();
+            let read = |name| fs::read_to_string(dest.join(name)).unwrap();
+            (read("a/x"), read("a/y"))
+        };
+
+        let past = SystemTime::now() - std::time::Duration::from_secs(3600);
+        let future = SystemTime::now() + std::time::Duration::from_secs(3600);
+        assert_eq!(run(CopyPolicy::Overwrite, future), ("new".into(), "new".into()));
+        assert_eq!(run(CopyPolicy::SkipExisting, past), ("old".into(), "new".into()));
+        assert_eq!(run(CopyPolicy::MergeNewOnly, past), ("new".into(), "new".into()));
+        assert_eq!(run(CopyPolicy::MergeNewOnly, future), ("old".into(), "new".into()));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn loose_files() {
+        let dir = std::env::temp_dir().join(format!("modtide-loose-{}", std::process::id()));
+        fs::create_dir_all(dir.join("scripts")).unwrap();
+        fs::write(dir.join("MyMod.mod"), "return {}").unwrap();
+        fs::write(dir.join("scripts/init.lua"), "").unwrap();
+
+        let paths = [dir.join("MyMod.mod"), dir.join("scripts")];
+        let mod_file = Loose::mod_file(&paths).unwrap();
+        let loose = Loose::new(mod_file, &paths).unwrap();
+
+        let mut entries = Vec::new();
+        let monitor = Monitor::new(u64::MAX);
+        loose.entries(&monitor, &mut |entry| {
+            entries.push(entry);
+            Ok(())
+        }).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let list = ArchiveList::new(entries);
+        let walked = list.walk("")
+            .map(|(p, _)| p.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(walked, [
+            "mods",
+            "mods/MyMod",
+            "mods/MyMod/scripts",
+            "mods/MyMod/scripts/init.lua",
+            "mods/MyMod/MyMod.mod",
+        ]);
+        assert!(Loose::mod_file(&[dir.join("scripts")]).is_none());
+    }
+
+    #[test]
+    fn package() {
+        let dir = std::env::temp_dir().join(format!("modtide-package-{}", std::process::id()));
+        let src = dir.join("src");
+        fs::create_dir_all(src.join("pack/a/b")).unwrap();
+        fs::create_dir_all(src.join("pack/plugins")).unwrap();
+        fs::write(src.join("pack/a/a.mod"), "return {}").unwrap();
+        fs::write(src.join("pack/a/a.dtmod"), "install = { a = 1, ['a'] = 'mods/a', ['plugins'] = 'binaries/plugins' }").unwrap();
+        fs::write(src.join("pack/a/b/b.dtmod"), "").unwrap();
+        fs::write(src.join("pack/plugins/x.dll"), "x").unwrap();
+        fs::write(src.join("pack/readme.txt"), "").unwrap();
+
+        let raw = RawDir::contents(&src).unwrap();
+        let monitor = Monitor::new(u64::MAX);
+        let mut entries = Vec::new();
+        raw.entries(&monitor, &mut |entry| {
+            entries.push(entry);
+            Ok(())
+        }).unwrap();
+        let mut list = ArchiveList::new(entries);
+        let path = find_package(&list).unwrap();
+        assert_eq!(path, "pack/a/a.dtmod");
+
+        let data = raw.read(&monitor, path, Path::new(path)).unwrap();
+        let prefix = Package::parse(path, &String::from_utf8_lossy(&data)).unwrap().prefix();
+        let install = Install::new(prefix.clone(), &list);
+        prefix.prepend(&mut list);
+        let walked = list.walk("")
+            .map(|(p, _)| p.to_string())
+            .collect::<Vec<_>>();
+
+        let archive = Archive(Arc::new(ArchiveInner {
+            monitor,
+            archives: vec![(src.clone(), Box::new(raw))],
+            failed: Vec::new(),
+            fixup: |_, _, _| unreachable!(),
+        }));
+        let staging = dir.join("staging");
+        let copied = std::cell::Cell::new(0);
+        let res = ArchiveView::copy_staged(&archive.0, &[Some(install)], &[], &staging, &|len| {
+            copied.set(copied.get() + len);
+        });
+        let installed = (
+            fs::read_to_string(staging.join("mods/a/a.mod")).ok(),
+            fs::read_to_string(staging.join("binaries/plugins/x.dll")).ok(),
+            staging.join("readme.txt").exists(),
+        );
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(walked, [
+            "binaries",
+            "binaries/plugins",
+            "binaries/plugins/x.dll",
+            "mods",
+            "mods/a",
+            "mods/a/b",
+            "mods/a/b/b.dtmod",
+            "mods/a/a.dtmod",
+            "mods/a/a.mod",
+        ]);
+        assert_eq!(res.unwrap(), 1);
+        assert_eq!(installed, (Some("return {}".into()), Some("x".into()), false));
+        assert_eq!(copied.get(), install_size(&list, &[]));
+        assert_eq!(install_size(&list, &["mods/a".into()]), 1);
+    }
+}
@@ -0,0 +1,138 @@
+use std::fs;
+use std::fs::File;
+use std::path::Path;
+use std::path::PathBuf;
+use std::io;
+
+use super::ArchiveReader;
+use super::DirEntry;
+use super::FileType;
+use super::Monitor;
+use super::Result;
+
+pub struct RawDir {
+    path: PathBuf,
+    // entries are relative to the parent of `path` when the directory itself is listed
+    base: PathBuf,
+}
+
+impl RawDir {
+    pub fn new(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().canonicalize()?;
+        let base = path.parent().unwrap().to_path_buf();
+        Self::with_base(path, base)
+    }
+
+    // list the contents of `path` without the directory itself
+    pub fn contents(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().canonicalize()?;
+        Self::with_base(path.clone(), path)
+    }
+
+    fn with_base(path: PathBuf, base: PathBuf) -> Result<Self> {
+        if path.metadata()?.is_dir() {
+            Ok(Self {
+                path,
+                base,
+            })
+        } else {
+            Err(io::Error::new(io::ErrorKind::NotADirectory, "RawDir requires valid directory"))
+        }
+    }
+
+    fn iter_all(
+        &self,
+        mut cb: impl FnMut(&Path, &Path, FileType) -> Result<()>,
+    ) -> Result<()> {
+        if self.base != self.path {
+            let name = Path::new(self.path.file_name().unwrap());
+            cb(&self.path, name, FileType::Dir)?;
+        }
+
+        let mut next = fs::read_dir(&self.path)?;
+        let mut iter = Vec::new();
+        loop {
+            for fd in next {
+                let fd = fd?;
+                let path = fd.path();
+                let suffix = path.strip_prefix(&self.base).unwrap();
+                let type_ = match fd.file_type()? {
+                    ty if ty.is_file() => FileType::File,
+                    ty if ty.is_dir() => FileType::Dir,
+                    _ => todo!(),
+                };
+                cb(&path, suffix, type_)?;
+                if type_.is_dir() {
+                    iter.push(path);
+                }
+            }
+
+            let Some(path) = iter.pop() else {
+                break;
+            };
+            next = fs::read_dir(path)?;
+        }
+        Ok(())
+    }
+}
+
+impl ArchiveReader for RawDir {
+    fn entries(
+        &self,
+        monitor: &Monitor,
+        cb: &mut dyn FnMut(DirEntry) -> Result<()>,
+    ) -> Result<()> {
+        self.iter_all(|path, suffix, type_| {
+            monitor.stopped()?;
+
+            let size = if type_.is_file() {
+                fs::metadata(path)?.len()
+            } else {
+                0
+            };
+            cb(DirEntry::from_path(suffix, type_, size))
+        })
+    }
+
+    fn copy(
+        &self,
+        monitor: &Monitor,
+        dest: &Path,
+        filter: &dyn Fn(&str) -> bool,
+        progress: &dyn Fn(u64),
+    ) -> Result<()> {
+        let dest = &super::long_path(dest);
+        self.iter_all(|path, suffix, type_| {
+            monitor.stopped()?;
+
+            if !filter(&entry_name(suffix)) {
+                return Ok(());
+            }
+
+            if type_.is_dir() {
+                // parents may have been filtered out
+                fs::create_dir_all(dest.join(suffix))?;
+            } else if type_.is_file() {
+                progress(copy_file(path, &dest.join(suffix))?);
+            }
+            Ok(())
+        })
+    }
+}
+
+// names for filtering, matching `DirEntry::from_path`, copies use `suffix` so
+// paths that aren't valid unicode are kept intact on disk
+pub fn entry_name(suffix: &Path) -> String {
+    suffix.to_string_lossy().replace('\\', "/")
+}
+
+// copy keeping the modified time of `from`, returns the number of bytes copied
+pub fn copy_file(from: &Path, to: &Path) -> Result<u64> {
+    let len = fs::copy(from, to)?;
+    let modified = fs::metadata(from)?.modified()?;
+    File::options()
+        .write(true)
+        .open(to)?
+        .set_modified(modified)?;
+    Ok(len)
+}
@@ -0,0 +1,284 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+
+use super::ArchiveReader;
+use super::DirEntry;
+use super::FileType;
+use super::Monitor;
+use super::RawDir;
+use super::Result;
+
+// formats handed to an installed 7-Zip
+static EXTENSIONS: &[&str] = &[
+    "7z", "rar", "tar", "gz", "tgz", "bz2", "tbz2", "xz", "txz", "zst", "lzma", "cab", "iso",
+];
+
+// listed with `7z l`, copies extract into a temp dir on first use and read it
+// back as a `RawDir`
+pub struct SevenZip {
+    exe: PathBuf,
+    path: PathBuf,
+    temp: PathBuf,
+    dir: Mutex<Option<RawDir>>,
+}
+
+impl SevenZip {
+    pub fn supports(path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| EXTENSIONS.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+    }
+
+    pub fn new(path: impl AsRef<Path>) -> Result<Option<Self>> {
+        let Some(exe) = find_exe() else {
+            return Ok(None);
+        };
+
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let temp = std::env::temp_dir().join(format!(
+            "modtide-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed),
+        ));
+
+        Ok(Some(Self {
+            exe,
+            path: path.as_ref().canonicalize()?,
+            temp,
+            dir: Mutex::new(None),
+        }))
+    }
+
+    fn extract(&self, monitor: &Monitor) -> Result<()> {
+        let mut dir = self.dir.lock().unwrap();
+        if dir.is_some() {
+            return Ok(());
+        }
+
+        super::remove_path(&self.temp)?;
+        fs::create_dir_all(&self.temp)?;
+
+        let mut cmd = self.command();
+        cmd.arg("x")
+            .arg("-y")
+            .arg(format!("-o{}", self.temp.display()))
+            .arg(&self.path)
+            .stdout(Stdio::null());
+        Self::run(monitor, cmd, "extract archive")?;
+
+        *dir = Some(RawDir::contents(&self.temp)?);
+        Ok(())
+    }
+
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.exe);
+        cmd.stdin(Stdio::null())
+            .stderr(Stdio::null());
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.creation_flags(0x08000000 /*CREATE_NO_WINDOW*/);
+        }
+        cmd
+    }
+
+    // waits for `cmd` while checking `monitor`, returns what it wrote to stdout
+    fn run(monitor: &Monitor, mut cmd: Command, action: &str) -> Result<Vec<u8>> {
+        let mut child = cmd.spawn()?;
+        // read on another thread so a full pipe can't block the child
+        let reader = child.stdout.take().map(|mut stdout| thread::spawn(move || {
+            let mut out = Vec::new();
+            stdout.read_to_end(&mut out).map(|_| out)
+        }));
+        let status = loop {
+            if let Err(err) = monitor.stopped() {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(err);
+            }
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            thread::sleep(Duration::from_millis(50));
+        };
+
+        let out = match reader {
+            Some(reader) => reader.join().unwrap()?,
+            None => Vec::new(),
+        };
+        if !status.success() {
+            return Err(io::Error::other(format!("7-Zip failed to {action} ({status})")));
+        }
+        Ok(out)
+    }
+}
+
+// entries from the `-slt` listing, one `Key = value` line per property and a
+// blank line between entries after the `----------` separator
+fn parse_listing(
+    out: &str,
+    monitor: &Monitor,
+    cb: &mut dyn FnMut(DirEntry) -> Result<()>,
+) -> Result<()> {
+    // CRLF on Windows
+    let out = out.replace("\r\n", "\n");
+    let Some((_, entries)) = out.split_once("\n----------") else {
+        return Ok(());
+    };
+
+    // archives aren't required to list parent directories
+    let mut dirs = HashSet::new();
+    let mut total = 0u64;
+    for block in entries.split("\n\n") {
+        let mut path = None;
+        let mut size = 0;
+        let mut dir = false;
+        for line in block.lines() {
+            let Some((key, value)) = line.split_once(" = ") else {
+                continue;
+            };
+            match key.trim() {
+                "Path" => path = Some(value.replace('\\', "/")),
+                "Size" => size = value.trim().parse().unwrap_or(0),
+                "Folder" => dir |= value.trim() == "+",
+                "Attributes" => dir |= value.trim_start().starts_with('D'),
+                _ => (),
+            }
+        }
+        let Some(path) = path else {
+            continue;
+        };
+
+        monitor.stopped()?;
+        let mut parent = path.as_str();
+        let mut parents = Vec::new();
+        while let Some((p, _)) = parent.rsplit_once('/') {
+            parent = p;
+            if dirs.insert(p.to_string()) {
+                parents.push(p.to_string());
+            }
+        }
+        for p in parents.into_iter().rev() {
+            cb(DirEntry::new(&p, FileType::Dir, 0))?;
+        }
+
+        if dir {
+            if dirs.insert(path.clone()) {
+                cb(DirEntry::new(&path, FileType::Dir, 0))?;
+            }
+        } else {
+            total += size;
+            monitor.check_size(total)?;
+            cb(DirEntry::new(&path, FileType::File, size))?;
+        }
+    }
+    Ok(())
+}
+
+impl Drop for SevenZip {
+    fn drop(&mut self) {
+        if let Err(err) = super::remove_path(&self.temp) {
+            eprintln!("failed to remove {:?}: {err}", self.temp);
+        }
+    }
+}
+
+impl ArchiveReader for SevenZip {
+    fn entries(
+        &self,
+        monitor: &Monitor,
+        cb: &mut dyn FnMut(DirEntry) -> Result<()>,
+    ) -> Result<()> {
+        let mut cmd = self.command();
+        cmd.arg("l")
+            .arg("-slt")
+            .arg("-sccUTF-8")
+            .arg(&self.path)
+            .stdout(Stdio::piped());
+        let out = Self::run(monitor, cmd, "list archive")?;
+        parse_listing(&String::from_utf8_lossy(&out), monitor, cb)
+    }
+
+    // only the one file instead of extracting everything
+    fn read(&self, monitor: &Monitor, path: &str, _os_path: &Path) -> Result<Vec<u8>> {
+        let mut cmd = self.command();
+        cmd.arg("e")
+            .arg("-so")
+            .arg(&self.path)
+            .arg(path)
+            .stdout(Stdio::piped());
+        Self::run(monitor, cmd, "read archive")
+    }
+
+    fn copy(
+        &self,
+        monitor: &Monitor,
+        dest: &Path,
+        filter: &dyn Fn(&str) -> bool,
+        progress: &dyn Fn(u64),
+    ) -> Result<()> {
+        self.extract(monitor)?;
+        self.dir.lock().unwrap().as_ref().unwrap().copy(monitor, dest, filter, progress)
+    }
+}
+
+fn find_exe() -> Option<PathBuf> {
+    let mut paths = Vec::new();
+    for var in ["ProgramFiles", "ProgramW6432", "ProgramFiles(x86)"] {
+        if let Some(dir) = std::env::var_os(var) {
+            paths.push(Path::new(&dir).join("7-Zip").join("7z.exe"));
+        }
+    }
+    if let Some(path) = std::env::var_os("PATH") {
+        for dir in std::env::split_paths(&path) {
+            paths.push(dir.join("7z.exe"));
+        }
+    }
+
+    paths.into_iter().find(|p| p.is_file())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn listing() {
+        let out = "7-Zip 24.08\n\nPath = test.7z\nType = 7z\n\n----------\n\
+            Path = wrap\\a\\a.mod\nFolder = -\nSize = 9\nAttributes = A\n\n\
+            Path = wrap\\a\nFolder = +\nSize = 0\nAttributes = D\n\n\
+            Path = wrap\\b.txt\nSize = 5\nAttributes = A\n\n";
+        let mut entries = Vec::new();
+        parse_listing(out, &Monitor::new(u64::MAX), &mut |entry| {
+            entries.push((entry.path.to_string(), entry.kind, entry.size));
+            Ok(())
+        }).unwrap();
+        assert_eq!(entries, [
+            ("wrap".into(), FileType::Dir, 0),
+            ("wrap/a".into(), FileType::Dir, 0),
+            ("wrap/a/a.mod".into(), FileType::File, 9),
+            ("wrap/b.txt".into(), FileType::File, 5),
+        ]);
+
+        let res = parse_listing(out, &Monitor::new(10), &mut |_| Ok(()));
+        assert!(res.is_err());
+
+        let mut crlf = Vec::new();
+        parse_listing(&out.replace('\n', "\r\n"), &Monitor::new(u64::MAX), &mut |entry| {
+            crlf.push((entry.path.to_string(), entry.kind, entry.size));
+            Ok(())
+        }).unwrap();
+        assert_eq!(crlf, entries);
+    }
+}
@@ -5,13 +5,17 @@ use std::io;
 use std::io::Read;
 use std::io::Seek;
 use std::io::SeekFrom;
+use std::io::Write;
+use std::time::Duration;
+use std::time::SystemTime;
 
 use super::ArchiveReader;
-use super::ArchiveList;
 use super::DirEntry;
 use super::FileType;
 use super::Monitor;
 use super::Result;
+use super::entry_path;
+use super::long_path;
 
 static HEADER_MAGIC_RECORD: [u8; 4] = [0x50, 0x4b, 0x01, 0x02];
 static HEADER_MAGIC_FILE: [u8; 4] = [0x50, 0x4b, 0x03, 0x04];
@@ -41,6 +45,33 @@ fn error(msg: &'static str) -> Result<()> {
     Err(io::Error::other(msg))
 }
 
+impl ZipRecord<'_> {
+    // DOS timestamps have no timezone so they are treated as UTC
+    fn modified(&self) -> Option<SystemTime> {
+        let year = (self.date >> 9) as i64 + 1980;
+        let month = ((self.date >> 5) & 0xf) as i64;
+        let day = (self.date & 0x1f) as i64;
+        let hour = (self.time >> 11) as u64;
+        let minute = ((self.time >> 5) & 0x3f) as u64;
+        let second = (self.time & 0x1f) as u64 * 2;
+        if !(1..=12).contains(&month) || day == 0 || hour > 23 || minute > 59 {
+            return None;
+        }
+
+        // days_from_civil from http://howardhinnant.github.io/date_algorithms.html
+        let y = if month <= 2 { year - 1 } else { year };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let mp = (month + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = u64::try_from(era * 146097 + doe - 719468).ok()?;
+
+        let secs = days * 86400 + hour * 3600 + minute * 60 + second;
+        SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(secs))
+    }
+}
+
 impl Zip {
     pub fn new(path: impl AsRef<Path>) -> Result<Self> {
         let path = path.as_ref();
@@ -213,58 +244,62 @@ impl Zip {
 }
 
 impl ArchiveReader for Zip {
-    fn list(&self, monitor: &Monitor) -> Result<ArchiveList> {
-        let mut entries = Vec::new();
-        let mut total = 0;
+    fn entries(
+        &self,
+        monitor: &Monitor,
+        cb: &mut dyn FnMut(DirEntry) -> Result<()>,
+    ) -> Result<()> {
+        let mut total = 0u64;
         let mut first = true;
         self.records(|record| {
             monitor.stopped()?;
 
             total += record.size as u64;
-            if total > u32::MAX as u64 {
-                return Err(io::Error::other("zip output larger than supported"));
-            }
+            monitor.check_size(total)?;
 
             if first && let Some((root, _)) = record.name.split_once('/') {
-                entries.push(DirEntry::new(root, FileType::Dir));
+                cb(DirEntry::new(root, FileType::Dir, 0))?;
             }
             first = false;
-            entries.push(DirEntry::new(record.name, record.attr));
-            Ok(())
-        })?;
-        Ok(ArchiveList::new(entries))
+            cb(DirEntry::new(record.name, record.attr, record.size as u64))
+        })
     }
 
-    fn copy(&self, monitor: &Monitor, dest: &Path) -> Result<()> {
+    fn copy(
+        &self,
+        monitor: &Monitor,
+        dest: &Path,
+        filter: &dyn Fn(&str) -> bool,
+        progress: &dyn Fn(u64),
+    ) -> Result<()> {
+        let dest = &long_path(dest);
         let mut buffer = Vec::new();
-        let mut total = 0;
-        let mut first = true;
+        let mut total = 0u64;
         self.records(|record| {
             monitor.stopped()?;
 
-            if first && let Some((root, _)) = record.name.split_once('/')
-                && let Err(err) = fs::create_dir(dest.join(root))
-                && err.kind() != io::ErrorKind::AlreadyExists
-            {
-                return Err(err);
+            if !filter(record.name) {
+                return Ok(());
             }
-            first = false;
 
             if record.attr.is_dir() {
-                if let Err(err) = fs::create_dir(dest.join(record.name))
-                    && err.kind() != io::ErrorKind::AlreadyExists
-                {
-                    return Err(err);
-                }
+                // parents may have been filtered out
+                fs::create_dir_all(entry_path(dest, record.name))?;
             } else if record.attr.is_file() {
                 let data = self.read_record(record, &mut buffer)?;
 
                 total += data.len() as u64;
-                if total > u32::MAX as u64 {
-                    return Err(io::Error::other("zip output larger than supported"));
+                monitor.check_size(total)?;
+
+                // zips aren't required to list parent directories
+                let path = entry_path(dest, record.name);
+                fs::create_dir_all(path.parent().unwrap())?;
+                let mut file = File::create(path)?;
+                file.write_all(data)?;
+                if let Some(modified) = record.modified() {
+                    file.set_modified(modified)?;
                 }
-
-                fs::write(dest.join(record.name), data)?;
+                progress(data.len() as u64);
             }
             Ok(())
         })
@@ -0,0 +1,157 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::manifest::MANIFEST_DIR;
+
+pub const LOAD_ORDER: &str = "mod_load_order.txt";
+// number of backups kept after each rewrite
+pub const KEEP: usize = 10;
+
+fn backup_dir(mods_path: &Path) -> PathBuf {
+    mods_path.join(MANIFEST_DIR).join("backups")
+}
+
+// load orders replaced by `restore`, newest last
+fn undo_dir(mods_path: &Path) -> PathBuf {
+    backup_dir(mods_path).join("undo")
+}
+
+// backup paths sorted oldest first
+//
+// names are `mod_load_order.<stamp>-<n>.txt` so sorting by name sorts by time
+fn list(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut out = Vec::new();
+    let fds = match fs::read_dir(dir) {
+        Ok(fds) => fds,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(out),
+        Err(err) => return Err(err),
+    };
+    for fd in fds {
+        let path = fd?.path();
+        if path.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with("mod_load_order.") && n.ends_with(".txt"))
+        {
+            out.push(path);
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+// first free `mod_load_order.<stamp>-<n>.txt` in `dir`, the counter keeps
+// backups within the same second apart
+fn unique_path(dir: &Path, stamp: &str) -> PathBuf {
+    let mut n = 0;
+    loop {
+        let path = dir.join(format!("mod_load_order.{stamp}-{n:03}.txt"));
+        if !path.exists() {
+            return path;
+        }
+        n += 1;
+    }
+}
+
+// moves or copies `from` into `dir` and keeps the last `keep`
+fn push(dir: &Path, from: &Path, stamp: &str, keep: usize, keep_from: bool) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let to = unique_path(dir, stamp);
+    if keep_from {
+        fs::copy(from, to)?;
+    } else {
+        fs::rename(from, to)?;
+    }
+
+    let paths = list(dir)?;
+    for path in &paths[..paths.len().saturating_sub(keep)] {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+// copies the current load order into the backups folder and keeps the last `keep`
+//
+// `stamp` should sort by time and be a valid file name (no `:`)
+pub fn backup(mods_path: &Path, stamp: &str, keep: usize) -> io::Result<()> {
+    let from = mods_path.join(LOAD_ORDER);
+    if !from.exists() {
+        return Ok(());
+    }
+    push(&backup_dir(mods_path), &from, stamp, keep, true)
+}
+
+// moves the newest backup over the load order so repeated restores walk back,
+// the replaced load order is kept for `undo_restore`
+//
+// returns false if there are no backups
+pub fn restore(mods_path: &Path, stamp: &str) -> io::Result<bool> {
+    let Some(path) = list(&backup_dir(mods_path))?.pop() else {
+        return Ok(false);
+    };
+    let lorder = mods_path.join(LOAD_ORDER);
+    if lorder.exists() {
+        push(&undo_dir(mods_path), &lorder, stamp, KEEP, false)?;
+    }
+    fs::rename(path, lorder)?;
+    Ok(true)
+}
+
+// puts back the load order replaced by the last `restore`, the restored one
+// becomes the newest backup again
+//
+// returns false if there is nothing to undo
+pub fn undo_restore(mods_path: &Path, stamp: &str) -> io::Result<bool> {
+    let Some(path) = list(&undo_dir(mods_path))?.pop() else {
+        return Ok(false);
+    };
+    let lorder = mods_path.join(LOAD_ORDER);
+    if lorder.exists() {
+        push(&backup_dir(mods_path), &lorder, stamp, KEEP, false)?;
+    }
+    fs::rename(path, lorder)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rotate_restore() {
+        let dir = std::env::temp_dir().join(format!("modtide-backup-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let lorder = dir.join(LOAD_ORDER);
+
+        backup(&dir, "20240101T000000", 2).unwrap();
+        assert!(list(&backup_dir(&dir)).unwrap().is_empty());
+
+        for (i, stamp) in ["20240101T000001", "20240101T000002", "20240101T000003"].iter().enumerate() {
+            fs::write(&lorder, format!("{i}\n")).unwrap();
+            backup(&dir, stamp, 2).unwrap();
+        }
+        fs::write(&lorder, "3\n").unwrap();
+        assert_eq!(list(&backup_dir(&dir)).unwrap().len(), 2);
+
+        assert!(restore(&dir, "20240101T000004").unwrap());
+        assert_eq!(fs::read_to_string(&lorder).unwrap(), "2\n");
+        assert!(restore(&dir, "20240101T000004").unwrap());
+        assert_eq!(fs::read_to_string(&lorder).unwrap(), "1\n");
+        let empty = restore(&dir, "20240101T000004").unwrap();
+
+        // undo walks forward again, in the same second
+        assert!(undo_restore(&dir, "20240101T000005").unwrap());
+        assert_eq!(fs::read_to_string(&lorder).unwrap(), "2\n");
+        assert!(undo_restore(&dir, "20240101T000005").unwrap());
+        assert_eq!(fs::read_to_string(&lorder).unwrap(), "3\n");
+        let undone = undo_restore(&dir, "20240101T000005").unwrap();
+        assert!(restore(&dir, "20240101T000006").unwrap());
+        let restored = fs::read_to_string(&lorder).unwrap();
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(!empty);
+        assert!(!undone);
+        assert_eq!(restored, "2\n");
+    }
+}
@@ -0,0 +1,248 @@
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::manifest::MANIFEST_DIR;
+
+// only the subset of toml written by `Config::to_toml` is read back
+//
+// auto_enable = true
+// show_on_start = false
+// show_legend = true
+// scroll_speed = 1
+// smooth_scroll = true
+// ui_scale = 100
+// toggle_key = "F10"
+// extract_limit_gb = 64
+// scan_depth = 1
+//
+// [colors]
+// enabled = [0.28, 0.77, 0.82, 1.0]
+pub const FILE: &str = "config.toml";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    // enable mods right after installing them
+    pub auto_enable: bool,
+    pub show_on_start: bool,
+    // color legend under the list
+    pub show_legend: bool,
+    // rows per mouse wheel step
+    pub scroll_speed: u32,
+    // animate wheel scrolling instead of jumping by rows
+    pub smooth_scroll: bool,
+    // percent, widgets and text are drawn scaled
+    pub ui_scale: u32,
+    // shows or hides the mod list while the launcher is focused, empty for none
+    pub toggle_key: String,
+    // installs extracting more than this are stopped, in GiB
+    pub extract_limit_gb: u64,
+    // folders searched below `mods` for grouped mods, see `ModEngine::scan`
+    pub scan_depth: u32,
+    // RGBA values from `[colors]` by name
+    pub colors: Vec<(String, [f32; 4])>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            auto_enable: true,
+            show_on_start: false,
+            show_legend: true,
+            scroll_speed: 1,
+            smooth_scroll: true,
+            ui_scale: 100,
+            toggle_key: "F10".to_string(),
+            extract_limit_gb: 64,
+            scan_depth: 1,
+            colors: Vec::new(),
+        }
+    }
+}
+
+pub fn path(mods_path: &Path) -> PathBuf {
+    mods_path.join(MANIFEST_DIR).join(FILE)
+}
+
+fn parse_color(value: &str) -> Option<[f32; 4]> {
+    let list = value.strip_prefix('[')?.strip_suffix(']')?;
+    let mut out = [0.0; 4];
+    let mut parts = list.split(',').map(str::trim).filter(|p| !p.is_empty());
+    for c in &mut out {
+        *c = parts.next()?.parse::<f32>().ok()?.clamp(0.0, 1.0);
+    }
+    parts.next().is_none().then_some(out)
+}
+
+impl Config {
+    pub const MAX_SCROLL_SPEED: u32 = 10;
+    pub const MIN_UI_SCALE: u32 = 50;
+    pub const MAX_UI_SCALE: u32 = 200;
+    pub const UI_SCALE_STEP: u32 = 10;
+    pub const MAX_SCAN_DEPTH: u32 = 4;
+
+    // unknown keys and invalid values are skipped so hand edited files still load
+    pub fn parse(file: &str) -> Self {
+        let mut config = Self::default();
+        let mut section = "";
+        for line in file.lines() {
+            let line = line.split('#').next().unwrap().trim();
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.trim();
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+
+            match (section, key) {
+                ("", "auto_enable") => if let Ok(v) = value.parse() {
+                    config.auto_enable = v;
+                },
+                ("", "show_on_start") => if let Ok(v) = value.parse() {
+                    config.show_on_start = v;
+                },
+                ("", "show_legend") => if let Ok(v) = value.parse() {
+                    config.show_legend = v;
+                },
+                ("", "scroll_speed") => if let Ok(v) = value.parse::<u32>() {
+                    config.scroll_speed = v.clamp(1, Self::MAX_SCROLL_SPEED);
+                },
+                ("", "smooth_scroll") => if let Ok(v) = value.parse() {
+                    config.smooth_scroll = v;
+                },
+                ("", "ui_scale") => if let Ok(v) = value.parse::<u32>() {
+                    config.ui_scale = v.clamp(Self::MIN_UI_SCALE, Self::MAX_UI_SCALE);
+                },
+                ("", "toggle_key") => if let Some(v) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+                    config.toggle_key = v.trim().to_string();
+                },
+                ("", "extract_limit_gb") => if let Ok(v) = value.parse::<u64>() {
+                    config.extract_limit_gb = v.max(1);
+                },
+                ("", "scan_depth") => if let Ok(v) = value.parse::<u32>() {
+                    config.scan_depth = v.clamp(1, Self::MAX_SCAN_DEPTH);
+                },
+                ("colors", name) => if let Some(color) = parse_color(value) {
+                    config.colors.retain(|(n, _)| n != name);
+                    config.colors.push((name.to_string(), color));
+                },
+                _ => (),
+            }
+        }
+        config
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "auto_enable = {}", self.auto_enable);
+        let _ = writeln!(out, "show_on_start = {}", self.show_on_start);
+        let _ = writeln!(out, "show_legend = {}", self.show_legend);
+        let _ = writeln!(out, "scroll_speed = {}", self.scroll_speed);
+        let _ = writeln!(out, "smooth_scroll = {}", self.smooth_scroll);
+        let _ = writeln!(out, "ui_scale = {}", self.ui_scale);
+        let _ = writeln!(out, "toggle_key = \"{}\"", self.toggle_key);
+        let _ = writeln!(out, "extract_limit_gb = {}", self.extract_limit_gb);
+        let _ = writeln!(out, "scan_depth = {}", self.scan_depth);
+        if !self.colors.is_empty() {
+            out.push_str("\n[colors]\n");
+            for (name, [r, g, b, a]) in &self.colors {
+                let _ = writeln!(out, "{name} = [{r}, {g}, {b}, {a}]");
+            }
+        }
+        out
+    }
+
+    // defaults when there is no config yet
+    pub fn load(mods_path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path(mods_path)) {
+            Ok(file) => Ok(Self::parse(&file)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, mods_path: &Path) -> io::Result<()> {
+        let path = path(mods_path);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, self.to_toml())
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.ui_scale as f32 / 100.0
+    }
+
+    // one `UI_SCALE_STEP` up or down, returns false at the limits
+    pub fn step_ui_scale(&mut self, up: bool) -> bool {
+        let scale = match up {
+            true => self.ui_scale + Self::UI_SCALE_STEP,
+            false => self.ui_scale.saturating_sub(Self::UI_SCALE_STEP),
+        }.clamp(Self::MIN_UI_SCALE, Self::MAX_UI_SCALE);
+        let changed = scale != self.ui_scale;
+        self.ui_scale = scale;
+        changed
+    }
+
+    // Windows virtual key code of `toggle_key`, only `F1` to `F24` so it
+    // can't be typed into the filter
+    pub fn toggle_vk(&self) -> Option<u16> {
+        let n = self.toggle_key
+            .strip_prefix(['F', 'f'])?
+            .parse::<u16>()
+            .ok()?;
+        (1..=24).contains(&n).then_some(0x70 + n - 1)
+    }
+
+    // in bytes
+    pub fn extract_limit(&self) -> u64 {
+        self.extract_limit_gb.saturating_mul(1 << 30)
+    }
+
+    pub fn color(&self, name: &str) -> Option<[f32; 4]> {
+        self.colors.iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, color)| *color)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_round_trip() {
+        let file = "# comment\nauto_enable = false\nscroll_speed = 40\nunknown = 1\nshow_legend = false\nsmooth_scroll = false\nui_scale = 20\nshow_on_start = yes\ntoggle_key = \"f5\"\nextract_limit_gb = 0\nscan_depth = 9\n\n\
+            [colors]\nenabled = [0.1, 0.2, 0.3, 1.0] # blue\nbroken = [1, 2]\n";
+        let config = Config::parse(file);
+        assert_eq!(config, Config {
+            auto_enable: false,
+            show_on_start: false,
+            show_legend: false,
+            scroll_speed: Config::MAX_SCROLL_SPEED,
+            smooth_scroll: false,
+            ui_scale: Config::MIN_UI_SCALE,
+            toggle_key: "f5".into(),
+            extract_limit_gb: 1,
+            scan_depth: Config::MAX_SCAN_DEPTH,
+            colors: vec![("enabled".into(), [0.1, 0.2, 0.3, 1.0])],
+        });
+        assert_eq!(Config::parse(&config.to_toml()), config);
+        assert_eq!(config.color("enabled"), Some([0.1, 0.2, 0.3, 1.0]));
+        assert_eq!(config.color("broken"), None);
+        assert_eq!(config.toggle_vk(), Some(0x74));
+        assert_eq!(config.extract_limit(), 1 << 30);
+        assert_eq!(Config::default().toggle_vk(), Some(0x79));
+        for (key, vk) in [("F24", Some(0x87)), ("m", None), ("F25", None), ("", None), ("F", None)] {
+            assert_eq!(Config { toggle_key: key.into(), ..Config::default() }.toggle_vk(), vk);
+        }
+
+        let mut config = Config::default();
+        assert!(config.step_ui_scale(true));
+        assert_eq!(config.scale(), 1.1);
+        config.ui_scale = Config::MAX_UI_SCALE;
+        assert!(!config.step_ui_scale(true));
+    }
+}
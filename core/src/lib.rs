@@ -0,0 +1,9 @@
+// platform independent mod management used by the launcher overlay
+pub mod archive;
+pub mod backup;
+pub mod config;
+mod lua;
+pub mod manifest;
+pub mod mod_engine;
+pub mod package;
+pub mod patch;
@@ -0,0 +1,231 @@
+// tolerant scanner for the tables in `.mod` files
+//
+// only tokenizes, which is enough to skip comments and strings before
+// matching keys. malformed input never fails, unterminated strings and
+// comments run to the end of the file.
+
+#[derive(Debug, PartialEq)]
+pub enum Token<'a> {
+    Name(&'a str),
+    // contents with escapes resolved
+    Str(String),
+    Number(&'a str),
+    // punctuation and operators
+    Sym(&'a str),
+}
+
+const SYMBOLS: &[&str] = &["...", "..", "==", "~=", "<=", ">=", "::", "//", "<<", ">>"];
+
+// level of a `[==[` long bracket at the start of `s`
+fn long_bracket(s: &str) -> Option<usize> {
+    let rest = s.strip_prefix('[')?;
+    let level = rest.bytes().take_while(|b| *b == b'=').count();
+    rest[level..].starts_with('[').then_some(level)
+}
+
+// returns the long string contents and the length consumed
+fn long_string(s: &str, level: usize) -> (&str, usize) {
+    let start = level + 2;
+    let close = format!("]{}]", "=".repeat(level));
+    match s[start..].find(&close) {
+        Some(end) => (&s[start..start + end], start + end + close.len()),
+        None => (&s[start..], s.len()),
+    }
+}
+
+// returns the unescaped string and the length consumed including quotes
+fn quoted_string(s: &str) -> (String, usize) {
+    let quote = s.as_bytes()[0] as char;
+    let mut out = String::new();
+    let mut chars = s.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            c if c == quote => return (out, i + 1),
+            // unfinished string
+            '\n' => return (out, i),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => out.push('\n'),
+                Some((_, 't')) => out.push('\t'),
+                Some((_, 'r')) => out.push('\r'),
+                Some((_, 'z')) => {
+                    while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+                }
+                Some((_, c)) => out.push(c),
+                None => break,
+            },
+            c => out.push(c),
+        }
+    }
+    (out, s.len())
+}
+
+pub fn tokenize(src: &str) -> Vec<Token<'_>> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < src.len() {
+        let s = &src[i..];
+        let c = s.chars().next().unwrap();
+
+        if c.is_whitespace() {
+            i += c.len_utf8();
+        } else if let Some(comment) = s.strip_prefix("--") {
+            i += 2 + match long_bracket(comment) {
+                Some(level) => long_string(comment, level).1,
+                None => comment.find('\n').unwrap_or(comment.len()),
+            };
+        } else if let Some(level) = long_bracket(s) {
+            let (text, len) = long_string(s, level);
+            // lua long strings skip a leading newline
+            let text = text.strip_prefix("\r\n")
+                .or_else(|| text.strip_prefix('\n'))
+                .unwrap_or(text);
+            out.push(Token::Str(text.to_string()));
+            i += len;
+        } else if c == '"' || c == '\'' {
+            let (text, len) = quoted_string(s);
+            out.push(Token::Str(text));
+            i += len;
+        } else if c.is_alphabetic() || c == '_' {
+            let len = s.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(s.len());
+            out.push(Token::Name(&s[..len]));
+            i += len;
+        } else if c.is_ascii_digit() || (c == '.' && s[1..].starts_with(|c: char| c.is_ascii_digit())) {
+            let mut len = 0;
+            let mut prev = ' ';
+            for c in s.chars() {
+                let exponent_sign = (c == '-' || c == '+') && matches!(prev, 'e' | 'E' | 'p' | 'P');
+                if !(c.is_ascii_alphanumeric() || c == '.' || exponent_sign) {
+                    break;
+                }
+                len += 1;
+                prev = c;
+            }
+            out.push(Token::Number(&s[..len]));
+            i += len;
+        } else {
+            let len = SYMBOLS.iter()
+                .find(|sym| s.starts_with(*sym))
+                .map_or(c.len_utf8(), |sym| sym.len());
+            out.push(Token::Sym(&s[..len]));
+            i += len;
+        }
+    }
+    out
+}
+
+// a string or a list of names with an optional value from `["name"] = "value"` items
+pub type Value = Result<String, Vec<(String, Option<String>)>>;
+
+// value tokens of a table item up to the next top level `,` or `;`
+fn split_item<'a, 'b>(tokens: &'a [Token<'b>]) -> (&'a [Token<'b>], &'a [Token<'b>]) {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Sym("{" | "(" | "[") => depth += 1,
+            Token::Sym("}" | ")" | "]") => depth = depth.saturating_sub(1),
+            Token::Sym("," | ";") if depth == 0 => return (&tokens[..i], &tokens[i + 1..]),
+            _ => (),
+        }
+    }
+    (tokens, &[])
+}
+
+fn parse_value(tokens: &[Token]) -> Option<Value> {
+    match tokens.first()? {
+        Token::Str(text) => Some(Ok(text.clone())),
+        Token::Number(text) => Some(Ok(text.to_string())),
+        Token::Sym("{") => {
+            // tokens up to the matching `}`
+            let mut depth = 0usize;
+            let end = tokens.iter().position(|token| {
+                match token {
+                    Token::Sym("{") => depth += 1,
+                    Token::Sym("}") => depth -= 1,
+                    _ => (),
+                }
+                depth == 0
+            }).unwrap_or(tokens.len());
+
+            // items that aren't names or `["name"] = value` are skipped
+            let mut list = Vec::new();
+            let mut rest = &tokens[1..end];
+            while !rest.is_empty() {
+                let item;
+                (item, rest) = split_item(rest);
+                match item {
+                    [Token::Str(name)] => list.push((name.clone(), None)),
+                    [Token::Sym("["), Token::Str(name), Token::Sym("]"), Token::Sym("="), value @ ..] => {
+                        let value = match value {
+                            [Token::Str(v)] => Some(v.clone()),
+                            _ => None,
+                        };
+                        list.push((name.clone(), value));
+                    }
+                    _ => (),
+                }
+            }
+            Some(Err(list))
+        }
+        _ => None,
+    }
+}
+
+// prefers the least nested match so keys in nested tables don't shadow the mod's own
+pub fn find_key_value(tokens: &[Token], key: &str) -> Option<Value> {
+    let mut found: Option<(usize, Value)> = None;
+    let mut depth = 0usize;
+    for i in 0..tokens.len() {
+        let value = match &tokens[i..] {
+            [Token::Sym("{"), ..] => {
+                depth += 1;
+                continue;
+            }
+            [Token::Sym("}"), ..] => {
+                depth = depth.saturating_sub(1);
+                continue;
+            }
+            [Token::Name(name), Token::Sym("="), value @ ..] if *name == key => value,
+            [Token::Sym("["), Token::Str(name), Token::Sym("]"), Token::Sym("="), value @ ..]
+                if name == key => value,
+            _ => continue,
+        };
+
+        if found.as_ref().is_none_or(|(d, _)| depth < *d)
+            && let Some(value) = parse_value(value)
+        {
+            found = Some((depth, value));
+        }
+    }
+    found.map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tokens() {
+        let src = "a = { 'x\\'y', [==[\nlong]]]==] } -- c = 'no'\n--[[ b = 1 ]] n = -1.5e-3 ~= x";
+        assert_eq!(tokenize(src), [
+            Token::Name("a"),
+            Token::Sym("="),
+            Token::Sym("{"),
+            Token::Str("x'y".into()),
+            Token::Sym(","),
+            Token::Str("long]]".into()),
+            Token::Sym("}"),
+            Token::Name("n"),
+            Token::Sym("="),
+            Token::Sym("-"),
+            Token::Number("1.5e-3"),
+            Token::Sym("~="),
+            Token::Name("x"),
+        ]);
+
+        assert_eq!(tokenize("s = \"open"), [
+            Token::Name("s"),
+            Token::Sym("="),
+            Token::Str("open".into()),
+        ]);
+    }
+}
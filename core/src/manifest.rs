@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::mod_engine::walk_files;
+
+// manifests are kept out of the mod folders so they don't change them, they
+// are keyed by the mod folder relative to `mods` so renamed or disabled
+// (`_NAME`) folders need `rename`
+pub const MANIFEST_DIR: &str = ".modtide";
+
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    Modified(String),
+    Missing(String),
+}
+
+impl Change {
+    pub fn path(&self) -> &str {
+        match self {
+            Change::Modified(path) | Change::Missing(path) => path,
+        }
+    }
+}
+
+fn manifest_path(mods_path: &Path, folder: &str) -> PathBuf {
+    mods_path.join(MANIFEST_DIR).join(format!("{folder}.manifest"))
+}
+
+// FNV-1a, only used to spot changed files
+fn hash_file(path: &Path) -> io::Result<(u64, u64)> {
+    let mut file = fs::File::open(path)?;
+    let mut buffer = vec![0; 0x10000];
+    let mut hash = 0xcbf29ce484222325u64;
+    let mut size = 0;
+    loop {
+        let len = file.read(&mut buffer)?;
+        if len == 0 {
+            break;
+        }
+        size += len as u64;
+        for b in &buffer[..len] {
+            hash ^= *b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    Ok((hash, size))
+}
+
+fn hash_folder(root: &Path) -> io::Result<Vec<(String, u64, u64)>> {
+    let mut out = Vec::new();
+    for (name, path) in walk_files(root)? {
+        let (hash, size) = hash_file(&path)?;
+        out.push((name, hash, size));
+    }
+    Ok(out)
+}
+
+// records the hashes of every file in `mods/<folder>`
+pub fn write(mods_path: &Path, folder: &str) -> io::Result<()> {
+    let mut out = String::new();
+    for (path, hash, size) in hash_folder(&mods_path.join(folder))? {
+        out.push_str(&format!("{hash:016x} {size} {path}\n"));
+    }
+
+    // nested for grouped mods
+    let path = manifest_path(mods_path, folder);
+    fs::create_dir_all(path.parent().unwrap())?;
+    fs::write(path, out)
+}
+
+pub fn rename(mods_path: &Path, old: &str, new: &str) -> io::Result<()> {
+    fs::rename(manifest_path(mods_path, old), manifest_path(mods_path, new))
+}
+
+pub fn remove(mods_path: &Path, folder: &str) -> io::Result<()> {
+    match fs::remove_file(manifest_path(mods_path, folder)) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+// manifests are written on install so their time stands in for the install time
+pub fn installed(mods_path: &Path, folder: &str) -> Option<SystemTime> {
+    fs::metadata(manifest_path(mods_path, folder)).ok()?.modified().ok()
+}
+
+// returns `None` when the mod has no manifest
+pub fn verify(mods_path: &Path, folder: &str) -> io::Result<Option<Vec<Change>>> {
+    let data = match fs::read_to_string(manifest_path(mods_path, folder)) {
+        Ok(data) => data,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    let mut expected = HashMap::new();
+    for line in data.lines() {
+        let mut parts = line.splitn(3, ' ');
+        if let (Some(hash), Some(size), Some(path)) = (parts.next(), parts.next(), parts.next())
+            && let Ok(hash) = u64::from_str_radix(hash, 16)
+            && let Ok(size) = size.parse::<u64>()
+        {
+            expected.insert(path, (hash, size));
+        }
+    }
+
+    let mut changes = Vec::new();
+    let root = mods_path.join(folder);
+    if root.is_dir() {
+        for (path, hash, size) in hash_folder(&root)? {
+            match expected.remove(path.as_str()) {
+                Some(found) if found != (hash, size) => changes.push(Change::Modified(path)),
+                // files added since install aren't reported
+                _ => (),
+            }
+        }
+    }
+    for path in expected.into_keys() {
+        changes.push(Change::Missing(path.to_string()));
+    }
+    changes.sort_by(|a, b| a.path().cmp(b.path()));
+
+    Ok(Some(changes))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn verify_changes() {
+        let dir = std::env::temp_dir().join(format!("modtide-manifest-{}", std::process::id()));
+        fs::create_dir_all(dir.join("a/scripts")).unwrap();
+        fs::write(dir.join("a/a.mod"), "return {}").unwrap();
+        fs::write(dir.join("a/scripts/init.lua"), "print(1)").unwrap();
+        fs::write(dir.join("a/scripts/other.lua"), "").unwrap();
+
+        assert_eq!(verify(&dir, "a").unwrap(), None);
+        assert!(installed(&dir, "a").is_none());
+        write(&dir, "a").unwrap();
+        assert!(installed(&dir, "a").is_some());
+        assert_eq!(verify(&dir, "a").unwrap(), Some(Vec::new()));
+
+        fs::write(dir.join("a/scripts/init.lua"), "print(2)").unwrap();
+        fs::remove_file(dir.join("a/scripts/other.lua")).unwrap();
+        fs::write(dir.join("a/scripts/new.lua"), "").unwrap();
+        let changes = verify(&dir, "a").unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(changes, Some(vec![
+            Change::Modified("scripts/init.lua".into()),
+            Change::Missing("scripts/other.lua".into()),
+        ]));
+    }
+}
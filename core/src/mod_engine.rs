@@ -0,0 +1,1562 @@
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs;
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::lua;
+use crate::lua::Token;
+
+#[derive(Default)]
+pub struct ModEngine {
+    pub header: String,
+    pub mods: Vec<ModEntry>,
+    // unknown lines after the last entry
+    footer: Vec<String>,
+    // mod name to the names of mods that require it
+    required_by: HashMap<String, Vec<String>>,
+}
+
+impl ModEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // folders without a `.mod` file are searched for grouped mods while `depth`
+    // allows, e.g. 3 finds `mods/collections/<SET>/<NAME>/<NAME>.mod`
+    pub fn scan(
+        path: impl AsRef<Path>,
+        depth: usize,
+    ) -> Result<Vec<Metadata>, Box<dyn std::error::Error>> {
+        let mut out = Vec::new();
+        let path = path.as_ref();
+        let mut folders = vec![(path.to_path_buf(), depth)];
+        while let Some((folder, depth)) = folders.pop() {
+            for fd in fs::read_dir(folder)? {
+                let folder = fd?.path();
+                let dir = match fs::read_dir(&folder) {
+                    Ok(fd) => fd,
+                    Err(err) if err.kind() == std::io::ErrorKind::NotADirectory => continue,
+                    Err(err) => return Err(err.into()),
+                };
+
+                let mut meta = None;
+                for fd in dir {
+                    let file_path = fd?.path();
+                    if file_path.extension() != Some(OsStr::new("mod")) {
+                        continue;
+                    }
+
+                    // folders may be prefixed with `_` to disable the mod
+                    if let Ok(p) = file_path.strip_prefix(path)
+                        && let Some(stem) = p.file_stem().and_then(|s| s.to_str())
+                        && let Some(folder) = p.parent().and_then(|p| p.file_name()).and_then(|p| p.to_str())
+                        && folder.strip_suffix(stem).is_some_and(|p| p.bytes().all(|b| b == b'_'))
+                        && let Some(name) = p.to_str()
+                        && let Ok(file) = fs::read_to_string(&file_path)
+                    {
+                        let mut m = Metadata::fuzzy_parse_mod(name, &file);
+                        if let Ok(package) = fs::read_to_string(file_path.with_extension(crate::package::EXTENSION)) {
+                            m.merge_package(&package);
+                        }
+                        meta = Some(m);
+                        break;
+                    }
+                }
+
+                let hidden = folder.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.'));
+                match meta {
+                    Some(meta) => out.push(meta),
+                    None if depth > 1 && !hidden => folders.push((folder, depth - 1)),
+                    None => (),
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    // relative paths of the files in each mod folder, excluding the `.mod` file
+    pub fn scan_files(
+        path: impl AsRef<Path>,
+        found: &[Metadata],
+    ) -> Result<ModFiles, Box<dyn std::error::Error>> {
+        let mut out = Vec::new();
+        let path = path.as_ref();
+        for meta in found {
+            let (Some(folder), Some(name)) = (meta.folder(), meta.name()) else {
+                continue;
+            };
+
+            let files = walk_files(&path.join(folder))?
+                .into_iter()
+                // skip the `.mod` file
+                .filter(|(p, _)| p.contains('/') || !p.ends_with(".mod"))
+                .map(|(p, _)| p)
+                .collect();
+            out.push((name.to_string(), files));
+        }
+        Ok(out)
+    }
+
+    pub fn load(
+        &mut self,
+        load_order: &str,
+        found: Vec<Metadata>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.header.clear();
+        self.mods.clear();
+        self.footer.clear();
+
+        // blank lines and comments are kept with the entry that follows them
+        let mut leading = Vec::new();
+        let mut in_comments = true;
+        for line in load_order.lines() {
+            if in_comments && line.starts_with("-- ") && parse_group(line).is_none() {
+                self.header.push_str(line);
+                self.header.push('\n');
+                continue;
+            } else {
+                in_comments = false;
+            }
+
+            // trailing `-- comment` after an entry
+            let (entry, comment) = match line.match_indices("--")
+                .find(|(i, _)| line[..*i].ends_with(char::is_whitespace))
+            {
+                Some((i, _)) => (line[..i].trim_end(), Some(line[i..].to_string())),
+                None => (line.trim_end(), None),
+            };
+
+            let mut state = ModState::Enabled;
+            let mut name = entry;
+            if let Some(m) = entry.strip_prefix("--") {
+                state = ModState::Disabled;
+                name = m.trim_start();
+            }
+
+            // `-- some text` is a comment unless it names an installed mod
+            let is_comment = state == ModState::Disabled
+                && name.contains(char::is_whitespace)
+                && !found.iter().any(|meta| meta.name() == Some(name));
+            if name.is_empty() || is_comment {
+                leading.push(line.to_string());
+                continue;
+            }
+
+            if name == "base" || name == "dmf" {
+                continue;
+            }
+
+            self.mods.push(ModEntry {
+                meta: Metadata::new(""),
+                state,
+                name: name.to_string(),
+                leading: std::mem::take(&mut leading),
+                comment,
+            });
+        }
+        self.footer = leading;
+
+        for meta in found {
+            let Some(name) = meta.name() else {
+                continue;
+            };
+            if name == "base" || name == "dmf" {
+                continue;
+            }
+
+            let m = self.mods.iter_mut()
+                .find(|m| m.name == name);
+
+            if let Some(m) = m {
+                m.meta = meta;
+            } else {
+                self.mods.push(ModEntry {
+                    state: ModState::MissingEntry,
+                    name: name.to_string(),
+                    meta,
+                    leading: Vec::new(),
+                    comment: None,
+                });
+            }
+        }
+
+        for m in &mut self.mods {
+            if *m.path() == *"" {
+                m.state = ModState::NotInstalled;
+            }
+
+            match m.meta.folder_prefix() {
+                0 => (),
+                1 => m.state = ModState::Disabled,
+                _ => m.state = ModState::NotInstalled,
+            }
+        }
+
+        self.required_by.clear();
+        for m in &self.mods {
+            for name in &m.meta.require {
+                self.required_by.entry(name.clone())
+                    .or_default()
+                    .push(m.name.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    // entries from `start` up to the next group
+    pub fn group_range(&self, start: usize) -> std::ops::Range<usize> {
+        let end = self.mods.iter()
+            .enumerate()
+            .skip(start + 1)
+            .find(|(_, m)| m.group().is_some())
+            .map_or(self.mods.len(), |(i, _)| i);
+        start..end
+    }
+
+    // moves the group line of entry `from` to just above entry `to`
+    pub fn move_group(&mut self, from: usize, to: usize) {
+        if from == to || to >= self.mods.len() || self.mods[to].group().is_some() {
+            return;
+        }
+        let Some(m) = self.mods.get_mut(from) else {
+            return;
+        };
+        let Some(i) = m.leading.iter().position(|line| parse_group(line).is_some()) else {
+            return;
+        };
+        let line = m.leading.remove(i);
+        self.mods[to].leading.push(line);
+    }
+
+    // drops the load order entry, returns false if `name` isn't listed
+    pub fn remove_entry(&mut self, name: &str) -> bool {
+        let Some(i) = self.mods.iter().position(|m| m.name == name) else {
+            return false;
+        };
+        // the next entry starts the group instead
+        self.move_group(i, i + 1);
+        self.mods.remove(i);
+        true
+    }
+
+    // renames the load order entry, returns false if `old` isn't listed
+    pub fn rename_entry(&mut self, old: &str, new: &str) -> bool {
+        if let Some(m) = self.mods.iter_mut().find(|m| m.name == old) {
+            m.name = new.to_string();
+            true
+        } else {
+            false
+        }
+    }
+
+    // (folder, name) of mods disabled by prefixing the folder with `_`
+    pub fn prefixed_folders(&self) -> Vec<(String, String)> {
+        self.mods.iter()
+            .filter(|m| m.meta.folder_prefix() > 0)
+            .filter_map(|m| Some((m.meta.folder()?.to_string(), m.name.clone())))
+            .collect()
+    }
+
+    // enabled mods that directly or indirectly require any of `names`
+    pub fn enabled_dependents(&self, names: &[&str]) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        let mut queue = names.to_vec();
+        while let Some(name) = queue.pop() {
+            for dependent in self.required_by.get(name).into_iter().flatten() {
+                let enabled = self.mods.iter()
+                    .any(|m| m.name == *dependent && m.state == ModState::Enabled);
+                if enabled
+                    && !names.contains(&dependent.as_str())
+                    && !out.contains(dependent)
+                {
+                    out.push(dependent.clone());
+                    queue.push(dependent);
+                }
+            }
+        }
+        out
+    }
+
+    // returns missing requirements, or the cycles that prevent sorting
+    #[cfg(test)]
+    fn sort(&mut self) -> Result<Vec<(String, String)>, Vec<Vec<String>>> {
+        let preview = self.sort_preview(SortMode::Full)?;
+        self.apply_sort(&preview);
+        Ok(preview.missing)
+    }
+
+    pub fn apply_sort(&mut self, preview: &SortPreview) {
+        assert!(preview.order.len() == self.mods.len());
+
+        let mut mods = Vec::with_capacity(self.mods.len());
+        for m in self.mods.drain(..) {
+            mods.push(Some(m));
+        }
+
+        for &i in &preview.order {
+            self.mods.push(mods[i].take().unwrap());
+        }
+    }
+
+    // dry run of `sort` that leaves the current order alone
+    pub fn sort_preview(&self, mode: SortMode) -> Result<SortPreview, Vec<Vec<String>>> {
+        let mut dag: HashMap<&str, Vec<&str>> = self.mods.iter()
+            .map(|m| (m.name.as_str(), Vec::new()))
+            .collect();
+
+        let mut used = HashSet::new();
+
+        let mut missing = Vec::new();
+        for m in &self.mods {
+            let meta = &m.meta;
+            for name in &meta.require {
+                if !dag.contains_key(name.as_str()) {
+                    missing.push((m.name.to_string(), name.to_string()));
+                }
+            }
+        }
+
+        if mode == SortMode::Stable {
+            let order = self.constrained_order(|i| (String::new(), i))?;
+            return Ok(self.preview(order, missing));
+        }
+        if mode == SortMode::Alphabetical {
+            let order = self.constrained_order(|i| (self.mods[i].name.to_lowercase(), i))?;
+            return Ok(self.preview(order, missing));
+        }
+
+        for m in &self.mods {
+            let meta = &m.meta;
+            if meta.load_before.is_empty()
+                && meta.load_after.is_empty()
+                && meta.require.is_empty()
+            {
+                continue;
+            } else {
+                used.insert(m.name.as_str());
+            }
+
+            for name in &meta.load_before {
+                let Some(entry) = dag.get_mut(name.as_str()) else {
+                    continue;
+                };
+                if let Err(i) = entry.binary_search(&name.as_str()) {
+                    used.insert(name.as_str());
+                    entry.insert(i, &m.name);
+                }
+            }
+
+            let entry = dag.get_mut(m.name.as_str()).unwrap();
+            for name in &meta.load_after {
+                if let Err(i) = entry.binary_search(&name.as_str()) {
+                    used.insert(name.as_str());
+                    entry.insert(i, name);
+                }
+            }
+            for name in &meta.require {
+                if !meta.load_before.contains(name)
+                    && let Err(i) = entry.binary_search(&name.as_str())
+                {
+                    used.insert(name.as_str());
+                    entry.insert(i, name);
+                }
+            }
+        }
+
+        let mut queue = Vec::with_capacity(self.mods.len());
+        let mut order = Vec::with_capacity(self.mods.len());
+        for (i, m) in self.mods.iter().enumerate() {
+            if used.contains(m.name.as_str()) {
+                queue.push(Some(m.name.as_str()));
+            } else {
+                queue.push(None);
+                dag.remove(m.name.as_str());
+                order.push((u32::MAX, i));
+            }
+        }
+
+        let mut round = 0;
+        let mut offset = usize::MAX;
+        while offset != order.len() {
+            offset = order.len();
+            for (i, name_) in queue.iter_mut().enumerate() {
+                let Some(name) = name_ else {
+                    continue;
+                };
+
+                let mut resolved = true;
+                if let Some(lb_list) = dag.get(name) {
+                    for lb in lb_list {
+                        if dag.contains_key(lb) {
+                            resolved = false;
+                            break;
+                        }
+                    }
+                }
+                if resolved {
+                    order.push((round, i));
+                    *name_ = None;
+                }
+            }
+
+            for (_, i) in &order[offset..] {
+                let name = &self.mods[*i].name;
+                dag.remove(name.as_str());
+            }
+
+            round += 1;
+        }
+
+        if offset != queue.len() {
+            let remaining = queue.iter().flatten().copied();
+            return Err(find_cycles(&dag, remaining));
+        }
+
+        order.sort_by(|a, b| {
+            let mut ord = a.0.cmp(&b.0);
+            if ord.is_eq() {
+                let a = &self.mods[a.1].name;
+                let b = &self.mods[b.1].name;
+
+                let mut a = a.as_bytes().iter();
+                let mut b = b.as_bytes().iter();
+                while let (Some(a), Some(b)) = (a.next(), b.next()) {
+                    let a = a.to_ascii_lowercase();
+                    let b = b.to_ascii_lowercase();
+                    ord = a.cmp(&b);
+                    if ord.is_ne() {
+                        break;
+                    }
+                }
+            }
+            ord
+        });
+
+        let order = order.into_iter()
+            .map(|(_, i)| i)
+            .collect::<Vec<_>>();
+        Ok(self.preview(order, missing))
+    }
+
+    fn preview(&self, order: Vec<usize>, missing: Vec<(String, String)>) -> SortPreview {
+        let mut moved = Vec::new();
+        for (to, &from) in order.iter().enumerate() {
+            if to != from {
+                moved.push((self.mods[from].name.clone(), from, to));
+            }
+        }
+
+        SortPreview {
+            order,
+            moved,
+            missing,
+        }
+    }
+
+    // topological sort from the end of the load order that always places the
+    // mod with the greatest `key` first, so with the current index as key mods
+    // only move when a constraint requires it and dependencies are pulled up to
+    // their dependents
+    fn constrained_order<K: Ord>(
+        &self,
+        key: impl Fn(usize) -> K,
+    ) -> Result<Vec<usize>, Vec<Vec<String>>> {
+        let len = self.mods.len();
+        let index: HashMap<&str, usize> = self.mods.iter()
+            .enumerate()
+            .map(|(i, m)| (m.name.as_str(), i))
+            .collect();
+
+        // `before[i]` lists mods that have to load before mod `i`
+        let mut before = vec![Vec::new(); len];
+        let mut later = vec![0; len];
+        let mut edge = |first: usize, second: usize| {
+            if first != second && !before[second].contains(&first) {
+                before[second].push(first);
+                later[first] += 1;
+            }
+        };
+
+        for (i, m) in self.mods.iter().enumerate() {
+            let meta = &m.meta;
+            for name in &meta.load_before {
+                if let Some(&j) = index.get(name.as_str()) {
+                    edge(i, j);
+                }
+            }
+            for name in meta.load_after.iter().chain(&meta.require) {
+                if !meta.load_before.contains(name)
+                    && let Some(&j) = index.get(name.as_str())
+                {
+                    edge(j, i);
+                }
+            }
+        }
+
+        let mut ready = (0..len)
+            .filter(|&i| later[i] == 0)
+            .map(|i| (key(i), i))
+            .collect::<BinaryHeap<_>>();
+        let mut order = Vec::with_capacity(len);
+        while let Some((_, i)) = ready.pop() {
+            order.push(i);
+            for &j in &before[i] {
+                later[j] -= 1;
+                if later[j] == 0 {
+                    ready.push((key(j), j));
+                }
+            }
+        }
+
+        if order.len() != len {
+            let name = |i: usize| self.mods[i].name.as_str();
+            let mut dag: HashMap<&str, Vec<&str>> = HashMap::new();
+            for (i, first) in before.iter().enumerate() {
+                if later[i] == 0 {
+                    continue;
+                }
+                let entry = dag.entry(name(i)).or_default();
+                for &j in first {
+                    if later[j] > 0 {
+                        entry.push(name(j));
+                    }
+                }
+            }
+
+            let remaining = (0..len).filter(|&i| later[i] > 0).map(name);
+            return Err(find_cycles(&dag, remaining));
+        }
+
+        order.reverse();
+        Ok(order)
+    }
+
+    // required mods whose version doesn't satisfy the constraint
+    // requirements on mods that aren't installed are reported by `sort`
+    pub fn validate(&self) -> Vec<VersionMismatch> {
+        let mut out = Vec::new();
+        for m in &self.mods {
+            for (name, constraint) in &m.meta.require_version {
+                let Some(required) = self.mods.iter().find(|r| r.name == *name) else {
+                    continue;
+                };
+
+                let found = required.meta.version();
+                if !found.is_some_and(|v| version_matches(v, constraint)) {
+                    out.push(VersionMismatch {
+                        mod_name: m.name.clone(),
+                        require: name.clone(),
+                        constraint: constraint.clone(),
+                        found: found.map(str::to_string),
+                    });
+                }
+            }
+        }
+        out
+    }
+
+    // files shipped by more than one installed mod, compared case insensitively
+    pub fn conflicts(&self, files: &ModFiles) -> Vec<FileConflict> {
+        let mut owners: HashMap<String, (&str, Vec<String>)> = HashMap::new();
+        for (name, paths) in files {
+            let installed = self.mods.iter()
+                .any(|m| m.name == *name && m.state != ModState::NotInstalled);
+            if !installed {
+                continue;
+            }
+
+            for path in paths {
+                let (_, mods) = owners.entry(path.to_lowercase())
+                    .or_insert_with(|| (path, Vec::new()));
+                mods.push(name.clone());
+            }
+        }
+
+        let mut out: Vec<FileConflict> = owners.into_values()
+            .filter(|(_, mods)| mods.len() > 1)
+            .map(|(path, mut mods)| {
+                mods.sort();
+                FileConflict {
+                    path: path.to_string(),
+                    mods,
+                }
+            })
+            .collect();
+        out.sort_by(|a, b| a.path.cmp(&b.path));
+        out
+    }
+
+    // replaces the order with `load_order` while keeping the installed mods
+    // returns listed mods that aren't installed
+    pub fn import(&mut self, load_order: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let header = std::mem::take(&mut self.header);
+        let found = self.mods.drain(..)
+            .map(|m| m.meta)
+            .filter(|meta| meta.name().is_some())
+            .collect();
+        self.load(load_order, found)?;
+        self.header = header;
+
+        Ok(self.mods.iter()
+            .filter(|m| m.state == ModState::NotInstalled)
+            .map(|m| m.name.clone())
+            .collect())
+    }
+
+    // load order with the installed versions listed in the header
+    pub fn export(&self, out: &mut String) -> Result<(), Box<dyn std::error::Error>> {
+        writeln!(out, "-- exported from modtide")?;
+        for m in &self.mods {
+            if let Some(version) = m.version() {
+                writeln!(out, "-- {} {version}", m.name)?;
+            }
+        }
+        self.write_entries(out)
+    }
+
+    pub fn generate(&self, out: &mut String) -> Result<(), Box<dyn std::error::Error>> {
+        out.push_str(&self.header);
+        self.write_entries(out)
+    }
+
+    fn write_entries(&self, out: &mut String) -> Result<(), Box<dyn std::error::Error>> {
+        for m in &self.mods {
+            for line in &m.leading {
+                writeln!(out, "{line}")?;
+            }
+            match m.state {
+                ModState::Enabled => (),
+                ModState::Disabled
+                | ModState::NotInstalled => write!(out, "--")?,
+                ModState::MissingEntry => continue,
+            }
+            write!(out, "{}", m.name)?;
+            if let Some(comment) = &m.comment {
+                write!(out, " {comment}")?;
+            }
+            out.push('\n');
+        }
+        for line in &self.footer {
+            writeln!(out, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+// renames `mods/<folder>` and its `.mod` file from `old` to `new`
+// `_` prefixes on the folder are kept and paths in the `.mod` file are updated
+pub fn rename_mod_folder(
+    mods_path: &Path,
+    folder: &str,
+    old: &str,
+    new: &str,
+) -> std::io::Result<()> {
+    let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg);
+    if new.is_empty() || new.contains(['/', '\\', ':', '*', '?', '"', '<', '>', '|']) {
+        return Err(invalid("invalid mod name"));
+    }
+    let Some(prefix) = folder.strip_suffix(old) else {
+        return Err(invalid("mod folder doesn't match its name"));
+    };
+    // `_` only disables, a mod can't have both folders
+    let to = mods_path.join(format!("{prefix}{new}"));
+    let taken = [to.clone(), mods_path.join(new), mods_path.join(format!("_{new}"))];
+    if taken.iter().any(|path| path.exists()) {
+        return Err(invalid("a mod folder with that name already exists"));
+    }
+
+    let from = mods_path.join(folder);
+    let data = fs::read_to_string(from.join(format!("{old}.mod")))?;
+    let data = data
+        .replace(&format!("\"{old}/"), &format!("\"{new}/"))
+        .replace(&format!("'{old}/"), &format!("'{new}/"));
+    // the folder first so a locked one leaves the mod as it was
+    fs::rename(&from, &to)?;
+    if let Err(err) = fs::write(to.join(format!("{new}.mod")), data) {
+        let _ = fs::rename(&to, &from);
+        return Err(err);
+    }
+    if old != new {
+        fs::remove_file(to.join(format!("{old}.mod")))?;
+    }
+    Ok(())
+}
+
+// files under `root` as ("/" separated relative path, full path)
+pub fn walk_files(root: &Path) -> std::io::Result<Vec<(String, std::path::PathBuf)>> {
+    let mut out = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for fd in fs::read_dir(dir)? {
+            let file_path = fd?.path();
+            if file_path.is_dir() {
+                dirs.push(file_path);
+            } else if let Ok(p) = file_path.strip_prefix(root) {
+                out.push((p.to_string_lossy().replace('\\', "/"), file_path.clone()));
+            }
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+// mod folder names and the relative paths of their files
+pub type ModFiles = Vec<(String, Vec<String>)>;
+
+#[derive(Debug, PartialEq)]
+pub struct FileConflict {
+    pub path: String,
+    pub mods: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortMode {
+    // group mods by dependency depth and sort alphabetically within groups
+    Full,
+    // keep the current order except where constraints are violated
+    Stable,
+    // case insensitive by name except where constraints are violated
+    Alphabetical,
+}
+
+pub struct SortPreview {
+    // indices into the current order
+    order: Vec<usize>,
+    // mods that change position as (name, from, to)
+    pub moved: Vec<(String, usize, usize)>,
+    // (mod, requirement) pairs for requirements that aren't installed
+    pub missing: Vec<(String, String)>,
+}
+
+// cycles in `dag` between the unresolved mods, each listed once in load order
+fn find_cycles<'a>(
+    dag: &HashMap<&'a str, Vec<&'a str>>,
+    remaining: impl Iterator<Item = &'a str>,
+) -> Vec<Vec<String>> {
+    fn visit<'a>(
+        name: &'a str,
+        dag: &HashMap<&'a str, Vec<&'a str>>,
+        visited: &mut HashSet<&'a str>,
+        stack: &mut Vec<&'a str>,
+        out: &mut Vec<Vec<String>>,
+    ) {
+        visited.insert(name);
+        stack.push(name);
+        for &dep in dag.get(name).into_iter().flatten() {
+            if !dag.contains_key(dep) {
+                continue;
+            }
+
+            if let Some(i) = stack.iter().position(|n| *n == dep) {
+                out.push(stack[i..].iter().rev().map(|n| n.to_string()).collect());
+            } else if !visited.contains(dep) {
+                visit(dep, dag, visited, stack, out);
+            }
+        }
+        stack.pop();
+    }
+
+    let mut visited = HashSet::new();
+    let mut out = Vec::new();
+    for name in remaining {
+        if !visited.contains(name) {
+            visit(name, dag, &mut visited, &mut Vec::new(), &mut out);
+        }
+    }
+    out
+}
+
+pub struct VersionMismatch {
+    pub mod_name: String,
+    pub require: String,
+    pub constraint: String,
+    pub found: Option<String>,
+}
+
+// version of the mod loader or framework from `version = "1.2"` or
+// assignments like `MOD_LOADER_VERSION = "1.2"`
+pub fn builtin_version(file: &str) -> Option<String> {
+    let tokens = lua::tokenize(file);
+    if let Some(Ok(version)) = lua::find_key_value(&tokens, "version") {
+        return Some(version);
+    }
+
+    tokens.windows(3).find_map(|w| {
+        let (name, version) = match w {
+            [Token::Name(name), Token::Sym("="), Token::Str(version)] => (name, version.as_str()),
+            [Token::Name(name), Token::Sym("="), Token::Number(version)] => (name, *version),
+            _ => return None,
+        };
+        (name.to_lowercase().contains("version") && parse_version(version).is_some())
+            .then(|| version.to_string())
+    })
+}
+
+fn parse_version(text: &str) -> Option<Vec<u64>> {
+    let text = text.trim().trim_start_matches(['v', 'V']);
+    let text = text.split(['-', '+', ' ']).next()?;
+    text.split('.').map(|p| p.parse().ok()).collect()
+}
+
+// compare versions with missing components treated as 0
+fn cmp_version(a: &[u64], b: &[u64]) -> std::cmp::Ordering {
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)))
+        .find(|ord| ord.is_ne())
+        .unwrap_or(std::cmp::Ordering::Equal)
+}
+
+// orders parsable versions first, then by text
+pub fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    match (parse_version(a), parse_version(b)) {
+        (Some(va), Some(vb)) => cmp_version(&va, &vb).then_with(|| a.cmp(b)),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+// `constraint` is a comma separated list like ">=1.2, <2"
+// `^` allows changes after the major version and `~` after the minor version
+fn version_matches(version: &str, constraint: &str) -> bool {
+    let Some(version) = parse_version(version) else {
+        return false;
+    };
+
+    constraint.split(',').all(|c| {
+        let c = c.trim();
+        if c.is_empty() || c == "*" {
+            return true;
+        }
+
+        let (op, req) = [">=", "<=", "==", ">", "<", "=", "^", "~"].iter()
+            .find_map(|op| c.strip_prefix(op).map(|req| (*op, req)))
+            .unwrap_or(("=", c));
+        let Some(req) = parse_version(req) else {
+            return false;
+        };
+
+        let ord = cmp_version(&version, &req);
+        let same_prefix = |n: usize| (0..n).all(|i| version.get(i).unwrap_or(&0) == req.get(i).unwrap_or(&0));
+        match op {
+            ">=" => ord.is_ge(),
+            "<=" => ord.is_le(),
+            ">" => ord.is_gt(),
+            "<" => ord.is_lt(),
+            "^" => ord.is_ge() && same_prefix(1),
+            "~" => ord.is_ge() && same_prefix(2),
+            _ => ord.is_eq(),
+        }
+    })
+}
+
+#[derive(Clone)]
+pub struct Metadata {
+    path: String,
+    load_before: Vec<String>,
+    load_after: Vec<String>,
+    require: Vec<String>,
+    // version constraints from `require = { ["name"] = ">=1.2" }`
+    require_version: Vec<(String, String)>,
+    version: Option<String>,
+    title: Option<String>,
+    author: Option<String>,
+    description: Option<String>,
+}
+
+impl Metadata {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.replace('\\', "/").trim_start_matches("./").to_string(),
+            load_before: Vec::new(),
+            load_after: Vec::new(),
+            require: Vec::new(),
+            require_version: Vec::new(),
+            version: None,
+            title: None,
+            author: None,
+            description: None,
+        }
+    }
+
+    pub fn fuzzy_parse_mod(path: &str, file: &str) -> Self {
+        let mut meta = Self {
+            path: path.replace('\\', "/"),
+            ..Self::new("")
+        };
+        meta.parse_fields(file);
+        meta
+    }
+
+    // fields from a packaged `.dtmod` manifest replace the ones guessed from the `.mod` file
+    pub fn merge_package(&mut self, file: &str) {
+        self.parse_fields(file);
+    }
+
+    // only overwrites fields that are present in `file`
+    fn parse_fields(&mut self, file: &str) {
+        let tokens = lua::tokenize(file);
+        let names = |list: Vec<(String, Option<String>)>| list.into_iter()
+            .map(|(name, _)| name)
+            .collect();
+
+        if let Some(Err(list)) = lua::find_key_value(&tokens, "load_before") {
+            self.load_before = names(list);
+        }
+
+        if let Some(Err(list)) = lua::find_key_value(&tokens, "load_after") {
+            self.load_after = names(list);
+        }
+
+        if let Some(Err(list)) = lua::find_key_value(&tokens, "require") {
+            self.require_version = list.iter()
+                .filter_map(|(name, constraint)| Some((name.clone(), constraint.clone()?)))
+                .collect();
+            self.require = names(list);
+        }
+
+        if let Some(Ok(value)) = lua::find_key_value(&tokens, "version") {
+            self.version = Some(value);
+        }
+
+        let text = |key| match lua::find_key_value(&tokens, key) {
+            Some(Ok(value)) if !value.trim().is_empty() => Some(value.trim().to_string()),
+            _ => None,
+        };
+        for (key, field) in [
+            ("title", &mut self.title),
+            ("author", &mut self.author),
+            ("description", &mut self.description),
+        ] {
+            if let Some(value) = text(key) {
+                *field = Some(value);
+            }
+        }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.path.rsplit_once('/').and_then(|(_, name)| name.strip_suffix(".mod"))
+    }
+
+    // path relative to the mods folder, nested for grouped mods
+    pub fn folder(&self) -> Option<&str> {
+        self.path.rsplit_once('/').map(|(folder, _)| folder)
+    }
+
+    // `_NAME` folders are disabled and `__NAME` folders are not installed
+    pub fn folder_prefix(&self) -> usize {
+        let folder = self.folder().map(|f| f.rsplit('/').next().unwrap_or(f));
+        match (folder, self.name()) {
+            (Some(folder), Some(name)) if folder != name => {
+                folder.strip_suffix(name)
+                    .filter(|prefix| prefix.bytes().all(|b| b == b'_'))
+                    .map_or(0, str::len)
+            }
+            _ => 0,
+        }
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn require(&self) -> &[String] {
+        &self.require
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+pub struct ModEntry {
+    pub meta: Metadata,
+    pub state: ModState,
+    name: String,
+    // unknown lines before the entry in mod_load_order.txt
+    leading: Vec<String>,
+    // trailing `-- comment` on the entry line
+    comment: Option<String>,
+}
+
+impl ModEntry {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> &str {
+        &self.meta.path
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.meta.version()
+    }
+
+    // title from the .mod file, falling back to the folder name
+    pub fn display_name(&self) -> &str {
+        self.meta.title().unwrap_or(&self.name)
+    }
+
+    // name of the group this entry starts
+    pub fn group(&self) -> Option<&str> {
+        self.leading.iter().find_map(|line| parse_group(line))
+    }
+}
+
+// `-- [Group: NAME]` lines start a group of entries
+fn parse_group(line: &str) -> Option<&str> {
+    line.strip_prefix("--")?
+        .trim()
+        .strip_prefix("[Group:")?
+        .strip_suffix(']')
+        .map(str::trim)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModState {
+    Enabled,
+    Disabled,
+    MissingEntry,
+    NotInstalled,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn state() {
+        use ModState::*;
+
+        let header = "-- line1\n-- line2\nbase\ndmf\n--dmf\n";
+        let test: &[(&str, &str, ModState)] = &[
+            ("on1", "on1/on1.mod", Enabled),
+            ("on2", "./on2/on2.mod", Enabled),
+            ("--off1", "off1/off1.mod", Disabled),
+            ("off2", "_off2/off2.mod", Disabled),
+            ("not_ins1", "", NotInstalled),
+            ("not_ins2", "__not_ins2/not_ins2.mod", NotInstalled),
+            ("", "miss_ent1/miss_ent1.mod", MissingEntry),
+            ("", "_miss_ent2/miss_ent2.mod", Disabled),
+        ];
+
+        let mut load_order = String::from(header);
+        for (name, ..) in test {
+            load_order.push_str(name);
+            load_order.push('\n');
+        }
+
+        let mut metas = Vec::new();
+        for (_, path, ..) in test {
+            metas.push(Metadata::new(path));
+        }
+
+        let mut engine = ModEngine::new();
+        engine.load(&load_order, metas).unwrap();
+        for (m, t) in engine.mods.iter().zip(test.iter()) {
+            let name = t.1
+                .split("/")
+                .nth(1)
+                .and_then(|s| s.strip_suffix(".mod"))
+                .unwrap_or(t.0);
+
+            assert_eq!(m.name, name);
+            assert_eq!(m.state, t.2, "{name}");
+        }
+
+        let folders = engine.prefixed_folders();
+        assert_eq!(folders.len(), 3);
+        assert_eq!(folders[0], ("_off2".to_string(), "off2".to_string()));
+    }
+
+    #[test]
+    fn scan_nested() {
+        let dir = std::env::temp_dir().join(format!("modtide-scan-{}", std::process::id()));
+        for path in ["a/a.mod", "_b/b.mod", "sets/pvp/c/c.mod", "sets/d/d.mod", ".modtide/e/e.mod"] {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "return {}").unwrap();
+        }
+
+        let scan = |depth| {
+            let mut found: Vec<_> = ModEngine::scan(&dir, depth).unwrap()
+                .iter()
+                .map(|m| (m.folder().unwrap().replace('\\', "/"), m.name().unwrap().to_string(), m.folder_prefix()))
+                .collect();
+            found.sort();
+            found
+        };
+        let shallow = scan(1);
+        let deep = scan(3);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let entry = |folder: &str, name: &str, prefix| (folder.to_string(), name.to_string(), prefix);
+        assert_eq!(shallow, [entry("_b", "b", 1), entry("a", "a", 0)]);
+        assert_eq!(deep, [
+            entry("_b", "b", 1),
+            entry("a", "a", 0),
+            entry("sets/d", "d", 0),
+            entry("sets/pvp/c", "c", 0),
+        ]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let load_order = "\
+-- header
+a -- keep a first
+
+-- a note about b
+--b --temporarily off
+not_installed
+-- trailing note
+";
+        let metas = vec![
+            Metadata::new("a/a.mod"),
+            Metadata::new("b/b.mod"),
+        ];
+        let mut engine = ModEngine::new();
+        engine.load(load_order, metas).unwrap();
+
+        let names: Vec<_> = engine.mods.iter().map(|m| m.name()).collect();
+        assert_eq!(names, ["a", "b", "not_installed"]);
+        assert_eq!(engine.mods[1].state, ModState::Disabled);
+
+        let mut out = String::new();
+        engine.generate(&mut out).unwrap();
+        assert_eq!(out, load_order.replace("\nnot_installed", "\n--not_installed"));
+
+        engine.mods.swap(0, 1);
+        out.clear();
+        engine.generate(&mut out).unwrap();
+        assert_eq!(out, "\
+-- header
+
+-- a note about b
+--b --temporarily off
+a -- keep a first
+--not_installed
+-- trailing note
+");
+    }
+
+    #[test]
+    fn rename() {
+        let dir = std::env::temp_dir().join(format!("modtide-rename-{}", std::process::id()));
+        fs::create_dir_all(dir.join("_old/scripts")).unwrap();
+        fs::write(dir.join("_old/old.mod"), "mod_script = \"old/scripts/init\",").unwrap();
+        fs::create_dir_all(dir.join("taken")).unwrap();
+        fs::create_dir_all(dir.join("_hidden")).unwrap();
+
+        assert!(rename_mod_folder(&dir, "_old", "old", "taken").is_err());
+        assert!(rename_mod_folder(&dir, "taken", "taken", "hidden").is_err());
+        assert!(rename_mod_folder(&dir, "_old", "old", "a/b").is_err());
+        assert!(rename_mod_folder(&dir, "_old", "old", "old").is_err());
+        let kept = dir.join("_old/old.mod").is_file();
+        rename_mod_folder(&dir, "_old", "old", "new").unwrap();
+        let data = fs::read_to_string(dir.join("_new/new.mod"));
+        let scripts = dir.join("_new/scripts").is_dir();
+        let removed = !dir.join("_new/old.mod").exists();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(kept);
+        assert_eq!(data.unwrap(), "mod_script = \"new/scripts/init\",");
+        assert!(scripts && removed);
+
+        let mut engine = ModEngine::new();
+        engine.load("a\n--b\n", Vec::new()).unwrap();
+        assert!(engine.rename_entry("b", "c"));
+        assert!(!engine.rename_entry("b", "d"));
+        let mut out = String::new();
+        engine.generate(&mut out).unwrap();
+        assert_eq!(out, "--a\n--c\n");
+    }
+
+    #[test]
+    fn groups() {
+        let load_order = "\
+-- header
+-- [Group: UI]
+a
+b
+-- [Group: Fixes]
+c
+";
+        let mut engine = ModEngine::new();
+        engine.load(load_order, Vec::new()).unwrap();
+        assert_eq!(engine.header, "-- header\n");
+        let groups: Vec<_> = engine.mods.iter().map(|m| m.group()).collect();
+        assert_eq!(groups, [Some("UI"), None, Some("Fixes")]);
+        assert_eq!(engine.group_range(0), 0..2);
+        assert_eq!(engine.group_range(2), 2..3);
+
+        engine.move_group(0, 2);
+        assert_eq!(engine.mods[0].group(), Some("UI"));
+        engine.move_group(0, 1);
+        assert_eq!(engine.mods[0].group(), None);
+        assert_eq!(engine.mods[1].group(), Some("UI"));
+
+        engine.move_group(1, 0);
+        let mut out = String::new();
+        engine.generate(&mut out).unwrap();
+        assert_eq!(out, load_order.replace("a\nb\n", "--a\n--b\n").replace("c\n", "--c\n"));
+
+        assert!(engine.remove_entry("a"));
+        assert!(!engine.remove_entry("a"));
+        let groups: Vec<_> = engine.mods.iter().map(|m| m.group()).collect();
+        assert_eq!(groups, [Some("UI"), Some("Fixes")]);
+    }
+
+    #[test]
+    fn metadata() {
+        let file = r#"
+            return {
+                title = "My Mod",
+                author = 'someone',
+                description = [[
+Does things.]],
+                version = "1.2.0",
+                load_after = { "other" },
+            }
+        "#;
+        let meta = Metadata::fuzzy_parse_mod("my_mod/my_mod.mod", file);
+        assert_eq!(meta.title(), Some("My Mod"));
+        assert_eq!(meta.author(), Some("someone"));
+        assert_eq!(meta.description(), Some("Does things."));
+        assert_eq!(meta.version(), Some("1.2.0"));
+        assert_eq!(meta.load_after, ["other"]);
+
+        let meta = Metadata::fuzzy_parse_mod("my_mod/my_mod.mod", "return {}");
+        assert_eq!(meta.title(), None);
+
+        let file = r#"
+            -- load_after = { "commented" }
+            return {
+                run = function()
+                    new_mod("my_mod", { version = "9.9", mod_script = "require" })
+                end,
+                --[[ version = "0.0" ]]
+                version = '1.0', -- title = "not this"
+                load_after = { 'single', "double"; ["keyed"] = true, other = "skipped" },
+                require = { ["dmf"] = { min = 2 }, ["b"] = ">=1" },
+            }
+        "#;
+        let meta = Metadata::fuzzy_parse_mod("my_mod/my_mod.mod", file);
+        assert_eq!(meta.title(), None);
+        assert_eq!(meta.version(), Some("1.0"));
+        assert_eq!(meta.load_after, ["single", "double", "keyed"]);
+        assert_eq!(meta.require, ["dmf", "b"]);
+        assert_eq!(meta.require_version, [("b".to_string(), ">=1".to_string())]);
+
+        let mut meta = meta;
+        meta.merge_package("return { name = 'my_mod', title = 'Packaged', require = { 'dmf' } }");
+        assert_eq!(meta.title(), Some("Packaged"));
+        assert_eq!(meta.version(), Some("1.0"));
+        assert_eq!(meta.require, ["dmf"]);
+        assert!(meta.require_version.is_empty());
+    }
+
+    #[test]
+    fn builtin() {
+        let file = "-- version = \"0.1\"\nlocal LOADER_VERSION = \"1.4.2\"\nlocal VERSION_NAME = \"beta\"";
+        assert_eq!(builtin_version(file).as_deref(), Some("1.4.2"));
+        assert_eq!(builtin_version("return { version = '2.0', run = nil }").as_deref(), Some("2.0"));
+        assert_eq!(builtin_version("return {}"), None);
+    }
+
+    #[test]
+    fn require_version() {
+        assert!(version_matches("1.2.0", ">=1.2"));
+        assert!(version_matches("v1.10", ">1.9, <2"));
+        assert!(!version_matches("2.0", ">1.9, <2"));
+        assert!(version_matches("1.4.1", "^1.2"));
+        assert!(!version_matches("2.0.0", "^1.2"));
+        assert!(version_matches("1.2.9", "~1.2.3"));
+        assert!(!version_matches("1.3.0", "~1.2.3"));
+        assert!(version_matches("1.2", "1.2.0"));
+        assert!(!version_matches("beta", ">=1"));
+
+        let mut versions = ["beta", "1.10", "v1.9", "1.2.0", "1.2"];
+        versions.sort_by(|a, b| compare_versions(a, b));
+        assert_eq!(versions, ["1.2", "1.2.0", "v1.9", "1.10", "beta"]);
+
+        let test: &[(&str, &str)] = &[
+            ("a", "require = { [\"b\"] = \">=1.2\", \"c\" }"),
+            ("b", "version = \"1.1.0\""),
+            ("c", ""),
+            ("d", "require = { [\"a\"] = \"1\" }"),
+        ];
+
+        let mut metas = Vec::new();
+        for (name, file) in test {
+            let path = format!("{name}/{name}.mod");
+            metas.push(Metadata::fuzzy_parse_mod(&path, file));
+        }
+        assert_eq!(metas[0].require, ["b", "c"]);
+
+        let mut engine = ModEngine::new();
+        engine.load("", metas).unwrap();
+        let mismatches = engine.validate();
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].mod_name, "a");
+        assert_eq!(mismatches[0].found.as_deref(), Some("1.1.0"));
+        assert_eq!(mismatches[1].mod_name, "d");
+        assert_eq!(mismatches[1].found, None);
+    }
+
+    #[test]
+    fn dependents() {
+        let test: &[(&str, &str)] = &[
+            ("lib", ""),
+            ("a", "require = {\"lib\"}"),
+            ("b", "require = {\"a\"}"),
+            ("c", "require = {\"lib\"}"),
+        ];
+
+        let mut metas = Vec::new();
+        for (name, file) in test {
+            let path = format!("{name}/{name}.mod");
+            metas.push(Metadata::fuzzy_parse_mod(&path, file));
+        }
+
+        let mut engine = ModEngine::new();
+        engine.load("lib\na\nb\n--c\n", metas).unwrap();
+        let mut dependents = engine.enabled_dependents(&["lib"]);
+        dependents.sort();
+        assert_eq!(dependents, ["a", "b"]);
+        assert_eq!(engine.enabled_dependents(&["lib", "a"]), ["b"]);
+        assert!(engine.enabled_dependents(&["b"]).is_empty());
+    }
+
+    #[test]
+    fn conflicts() {
+        let metas = ["a", "b", "c"].iter()
+            .map(|name| Metadata::fuzzy_parse_mod(&format!("{name}/{name}.mod"), ""))
+            .collect();
+        let mut engine = ModEngine::new();
+        engine.load("a\nb\nc\n", metas).unwrap();
+
+        let files: ModFiles = vec![
+            ("a".into(), vec!["scripts/shared.lua".into(), "scripts/a.lua".into()]),
+            ("b".into(), vec!["scripts/Shared.lua".into()]),
+            ("c".into(), vec!["scripts/c.lua".into()]),
+            ("d".into(), vec!["scripts/c.lua".into()]),
+        ];
+        let conflicts = engine.conflicts(&files);
+        assert_eq!(conflicts, [FileConflict {
+            path: "scripts/shared.lua".into(),
+            mods: vec!["a".into(), "b".into()],
+        }]);
+    }
+
+    #[test]
+    fn import_export() {
+        let metas = vec![
+            Metadata::fuzzy_parse_mod("a/a.mod", "version = \"1.0\""),
+            Metadata::fuzzy_parse_mod("b/b.mod", ""),
+            Metadata::fuzzy_parse_mod("c/c.mod", ""),
+        ];
+        let mut engine = ModEngine::new();
+        engine.load("-- header\na\n--b\nc\n", metas).unwrap();
+
+        let mut out = String::new();
+        engine.export(&mut out).unwrap();
+        assert_eq!(out, "-- exported from modtide\n-- a 1.0\na\n--b\nc\n");
+
+        let missing = engine.import("-- other\nc\nunknown\n--a\n").unwrap();
+        assert_eq!(missing, ["unknown"]);
+        assert_eq!(engine.header, "-- header\n");
+        let order: Vec<_> = engine.mods.iter()
+            .map(|m| (m.name(), m.state.clone()))
+            .collect();
+        assert_eq!(order, [
+            ("c", ModState::Enabled),
+            ("unknown", ModState::NotInstalled),
+            ("a", ModState::Disabled),
+            ("b", ModState::MissingEntry),
+        ]);
+    }
+
+    #[test]
+    fn sort() {
+        let expected: &[&str] = &[
+            "abc",
+            "load_before1",
+            "bca",
+            "load_before2",
+            "requires",
+            "late",
+            "aaa",
+        ];
+        let test: &[(&str, &str)] = &[
+            ("aaa", ""),
+            ("abc", ""),
+            ("bca", ""),
+            ("requires", "require = {\"bca\"}"),
+            ("load_before1", "load_before = {\"bca\"}"),
+            ("load_before2", "load_after = {\"bca\"} require = {\"abc\"}"),
+            ("late", "require = {\"requires\"}"),
+        ];
+
+        let mut metas = Vec::new();
+        for (name, file) in test {
+            let path = format!("{name}/{name}.mod");
+            metas.push(Metadata::fuzzy_parse_mod(&path, file));
+        }
+
+        let mut engine = ModEngine::new();
+        engine.load("", metas).unwrap();
+        let missing = engine.sort().unwrap();
+        assert!(missing.is_empty());
+
+        let mut failed = None;
+        for i in 0..test.len() {
+            let a = &engine.mods[i].name;
+            let b = expected[i];
+            if a != b {
+                failed = Some((a, b));
+                break;
+            }
+        }
+
+        if let Some((a, b)) = failed {
+            for i in 0..test.len() {
+                let a = &engine.mods[i].name;
+                let b = expected[i];
+                eprintln!("{a}, {b}");
+            }
+            panic!("{a} != {b}");
+        }
+    }
+
+    #[test]
+    fn sort_preview() {
+        let test: &[(&str, &str)] = &[
+            ("a", "load_after = {\"b\"}"),
+            ("b", ""),
+            ("c", ""),
+        ];
+
+        let mut metas = Vec::new();
+        for (name, file) in test {
+            let path = format!("{name}/{name}.mod");
+            metas.push(Metadata::fuzzy_parse_mod(&path, file));
+        }
+
+        let mut engine = ModEngine::new();
+        engine.load("a\nb\nc\n", metas).unwrap();
+        let preview = engine.sort_preview(SortMode::Full).unwrap();
+        assert_eq!(engine.mods[0].name, "a");
+        assert_eq!(preview.moved, [
+            ("b".to_string(), 1, 0),
+            ("a".to_string(), 0, 1),
+        ]);
+
+        engine.apply_sort(&preview);
+        let names = engine.mods.iter().map(|m| m.name()).collect::<Vec<_>>();
+        assert_eq!(names, ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn sort_stable() {
+        let test: &[(&str, &str)] = &[
+            ("c", ""),
+            ("a", "load_after = {\"d\"}"),
+            ("b", ""),
+            ("d", ""),
+            ("e", "load_before = {\"c\"}"),
+        ];
+
+        let mut metas = Vec::new();
+        for (name, file) in test {
+            let path = format!("{name}/{name}.mod");
+            metas.push(Metadata::fuzzy_parse_mod(&path, file));
+        }
+
+        let mut engine = ModEngine::new();
+        engine.load("c\na\nb\nd\ne\n", metas).unwrap();
+        let preview = engine.sort_preview(SortMode::Stable).unwrap();
+        engine.apply_sort(&preview);
+        let names = engine.mods.iter().map(|m| m.name()).collect::<Vec<_>>();
+        assert_eq!(names, ["e", "c", "d", "a", "b"]);
+
+        // already satisfied orders are left alone
+        let preview = engine.sort_preview(SortMode::Stable).unwrap();
+        assert!(preview.moved.is_empty());
+    }
+
+    #[test]
+    fn sort_alphabetical() {
+        let test: &[(&str, &str)] = &[
+            ("c", ""),
+            ("a", "load_after = {\"d\"}"),
+            ("B", ""),
+            ("d", ""),
+            ("e", "load_before = {\"c\"}"),
+        ];
+
+        let mut metas = Vec::new();
+        for (name, file) in test {
+            let path = format!("{name}/{name}.mod");
+            metas.push(Metadata::fuzzy_parse_mod(&path, file));
+        }
+
+        let mut engine = ModEngine::new();
+        engine.load("c\na\nB\nd\ne\n", metas).unwrap();
+        let preview = engine.sort_preview(SortMode::Alphabetical).unwrap();
+        engine.apply_sort(&preview);
+        let names = engine.mods.iter().map(|m| m.name()).collect::<Vec<_>>();
+        assert_eq!(names, ["d", "a", "B", "e", "c"]);
+    }
+
+    #[test]
+    fn sort_fail() {
+        let test: &[(&str, &str)] = &[
+            ("aa", "load_before = {\"bb\"}"),
+            ("bb", ""),
+            ("ba", "require = {\"bb\"} load_before = {\"aa\"}"),
+        ];
+
+        let mut metas = Vec::new();
+        for (name, file) in test {
+            let path = format!("{name}/{name}.mod");
+            metas.push(Metadata::fuzzy_parse_mod(&path, file));
+        }
+
+        let mut engine = ModEngine::new();
+        engine.load("", metas).unwrap();
+        let cycles = engine.sort().unwrap_err();
+        assert_eq!(cycles, [["bb", "ba", "aa"]]);
+        assert!(engine.sort_preview(SortMode::Stable).is_err());
+    }
+
+    #[test]
+    fn sort_missing_require() {
+        let test: &[(&str, &str)] = &[
+            ("a", "require = {\"b\"}"),
+        ];
+
+        let mut metas = Vec::new();
+        for (name, file) in test {
+            let path = format!("{name}/{name}.mod");
+            metas.push(Metadata::fuzzy_parse_mod(&path, file));
+        }
+
+        let mut engine = ModEngine::new();
+        engine.load("", metas).unwrap();
+        assert_eq!(1, engine.sort().unwrap().len());
+    }
+}
@@ -0,0 +1,112 @@
+use std::io;
+
+use crate::archive::Prefix;
+use crate::lua;
+
+// `<NAME>/<NAME>.dtmod` is an optional manifest shipped next to `<NAME>/<NAME>.mod`
+//
+// return {
+//     name = "my_mod",
+//     version = "1.2.0",
+//     title = "My Mod",
+//     author = "someone",
+//     require = { "dmf", ["other_mod"] = ">=1.2" },
+//     load_after = { "other_mod" },
+//     -- folders next to `<NAME>/` mapped to paths in the game folder,
+//     -- defaults to `{ ["<NAME>"] = "mods/<NAME>" }`
+//     install = { ["my_mod"] = "mods/my_mod", ["plugins"] = "binaries/plugins" },
+// }
+//
+// everything but `install` is read by `Metadata::merge_package` once installed
+pub const EXTENSION: &str = "dtmod";
+
+#[derive(Debug, PartialEq)]
+pub struct Package {
+    pub name: String,
+    // archive paths to installed paths, longest first so nested folders map first
+    pub install: Vec<(String, String)>,
+}
+
+// relative paths without `..` so a manifest can't write outside the game folder
+fn is_safe(path: &str) -> bool {
+    !path.is_empty()
+        && !path.starts_with('/')
+        && !path.contains(['\\', ':'])
+        && path.split('/').all(|part| !part.is_empty() && part != "." && part != "..")
+}
+
+impl Package {
+    // `path` is where the manifest was found in the archive
+    pub fn parse(path: &str, file: &str) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, format!("{path}: {msg}"));
+
+        let (wrapper, stem) = path.strip_suffix(EXTENSION)
+            .and_then(|p| p.strip_suffix('.'))
+            .and_then(|p| p.rsplit_once('/'))
+            .ok_or_else(|| invalid("expected <NAME>/<NAME>.dtmod".into()))?;
+        let wrapper = wrapper.rsplit_once('/').map_or("", |(wrapper, _)| wrapper);
+
+        let tokens = lua::tokenize(file);
+        let name = match lua::find_key_value(&tokens, "name") {
+            Some(Ok(name)) if name != stem => return Err(invalid(format!("name {name:?} doesn't match folder"))),
+            _ => stem.to_string(),
+        };
+
+        let join = |from: &str| if wrapper.is_empty() {
+            from.to_string()
+        } else {
+            format!("{wrapper}/{from}")
+        };
+        let mut install = Vec::new();
+        match lua::find_key_value(&tokens, "install") {
+            Some(Err(list)) => for (from, to) in list {
+                let from = from.trim_end_matches('/');
+                let to = to.as_deref().unwrap_or("").trim_end_matches('/');
+                if !is_safe(from) || !is_safe(to) {
+                    return Err(invalid(format!("invalid install path {from:?} = {to:?}")));
+                }
+                install.push((join(from), to.to_string()));
+            },
+            Some(Ok(_)) => return Err(invalid("install should be a table".into())),
+            None => install.push((join(&name), format!("mods/{name}"))),
+        }
+        install.sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+
+        Ok(Self {
+            name,
+            install,
+        })
+    }
+
+    pub fn prefix(&self) -> Prefix {
+        Prefix::Map(self.install.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let package = Package::parse("pack/a/a.dtmod", "return { version = '1' }").unwrap();
+        assert_eq!(package, Package {
+            name: "a".into(),
+            install: vec![("pack/a".into(), "mods/a".into())],
+        });
+
+        let file = r#"return {
+            name = "a",
+            install = { ["a"] = "mods/a", ["extra/plugins/"] = "binaries/plugins" },
+        }"#;
+        let package = Package::parse("a/a.dtmod", file).unwrap();
+        assert_eq!(package.install, [
+            ("extra/plugins".to_string(), "binaries/plugins".to_string()),
+            ("a".to_string(), "mods/a".to_string()),
+        ]);
+
+        assert!(Package::parse("a/a.dtmod", "name = 'b'").is_err());
+        assert!(Package::parse("a/a.dtmod", "install = { ['a'] = '../a' }").is_err());
+        assert!(Package::parse("a/a.dtmod", "install = { ['a'] = 'C:/a' }").is_err());
+    }
+}
@@ -1,10 +1,14 @@
-// TODO: custom font loading with IDWriteInMemoryFontFileLoader for OTF (OTTO)
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::os::windows::ffi::OsStrExt;
 use std::ffi::OsStr;
+use std::ffi::OsString;
 
 use windows::core::PCWSTR;
 use windows::core::Result;
 use windows::core::Interface;
+use windows::Win32::Foundation::D2DERR_RECREATE_TARGET;
 use windows::Win32::Foundation::HMODULE;
 use windows::Win32::Graphics::Gdi::*;
 use windows::Win32::Graphics::Direct2D::*;
@@ -29,12 +33,46 @@ const FEATURE_LEVELS: &[D3D_FEATURE_LEVEL] = &[
     D3D_FEATURE_LEVEL_9_1,
 ];
 
+// layouts copy the format's alignment and wrapping so those are part of the key
+struct LayoutFormat {
+    format: IDWriteTextFormat,
+    size: [u32; 2],
+    align: [i32; 3],
+    layouts: HashMap<OsString, IDWriteTextLayout>,
+}
+
+// `IDWriteTextLayout`s kept between frames for `DrawScope::draw_text_layout`,
+// everything is dropped once `MAX_LAYOUTS` is reached instead of tracking use
+struct LayoutCache {
+    dwfactory: IDWriteFactory,
+    formats: RefCell<Vec<LayoutFormat>>,
+}
+
+impl LayoutCache {
+    const MAX_LAYOUTS: usize = 2048;
+}
+
+// what `DrawScope`s share with their `DxgiContext`
+struct ScopeState {
+    layouts: LayoutCache,
+    // an `EndDraw` returned `D2DERR_RECREATE_TARGET`, see `DxgiContext::recreate`
+    device_lost: Cell<bool>,
+    // last `EndDraw` error, logged once instead of every frame
+    draw_error: Cell<Option<windows::core::HRESULT>>,
+    // reused by `DrawScope::draw_text` so encoding doesn't allocate every frame
+    wide: RefCell<Vec<u16>>,
+}
+
 pub struct DxgiContext {
     factory: ID2D1Factory1,
     dwfactory: IDWriteFactory,
-    device: ID3D11Device,
+    // `None` when neither a hardware nor a WARP device could be created, see
+    // `resize_`
+    device: Option<(ID3D11Device, ID2D1DeviceContext)>,
     context: ID2D1RenderTarget,
-    d2dcontext: ID2D1DeviceContext,
+    // collection and family name from `load_fonts`
+    font: Option<(IDWriteFontCollection, Vec<u16>)>,
+    state: ScopeState,
 
     width: u32,
     height: u32,
@@ -69,16 +107,15 @@ impl DxgiContext {
         }
     }
 
+    // without a device Direct2D draws on the CPU into a WIC bitmap, the
+    // window only sees it through GDI like with a device
     fn resize_(
         factory: &ID2D1Factory1,
-        device: &ID3D11Device,
+        device: Option<&ID3D11Device>,
         width: u32,
         height: u32,
     ) -> Result<ID2D1RenderTarget> {
         unsafe {
-            let texture = Self::create_texture2d_(device, width, height)?;
-            let surface = texture.cast::<IDXGISurface>()?;
-
             let mut props: D2D1_RENDER_TARGET_PROPERTIES = core::mem::zeroed();
             props.pixelFormat.format = DXGI_FORMAT_B8G8R8A8_UNORM;
             props.pixelFormat.alphaMode = D2D1_ALPHA_MODE_PREMULTIPLIED;
@@ -86,62 +123,135 @@ impl DxgiContext {
             props.dpiY = 0.0;
             props.usage = D2D1_RENDER_TARGET_USAGE_GDI_COMPATIBLE;
 
+            let Some(device) = device else {
+                let wic: IWICImagingFactory = CoCreateInstance(
+                    &CLSID_WICImagingFactory,
+                    None,
+                    CLSCTX_INPROC_SERVER,
+                )?;
+                let bitmap = wic.CreateBitmap(
+                    width,
+                    height,
+                    &GUID_WICPixelFormat32bppPBGRA,
+                    WICBitmapCacheOnLoad,
+                )?;
+                props.r#type = D2D1_RENDER_TARGET_TYPE_SOFTWARE;
+                return factory.CreateWicBitmapRenderTarget(&bitmap, &props);
+            };
+
+            let texture = Self::create_texture2d_(device, width, height)?;
+            let surface = texture.cast::<IDXGISurface>()?;
             factory.CreateDxgiSurfaceRenderTarget(&surface, &props)
         }
     }
 
+    // `None` leaves drawing to the software path in `resize_`
+    fn create_device_(
+        factory: &ID2D1Factory1,
+    ) -> Result<Option<(ID3D11Device, ID2D1DeviceContext)>> {
+        unsafe {
+            // WARP renders on the CPU for VMs and broken drivers
+            let mut device = None;
+            let mut res = Ok(());
+            for driver_type in [D3D_DRIVER_TYPE_HARDWARE, D3D_DRIVER_TYPE_WARP] {
+                res = D3D11CreateDevice(
+                    None,
+                    driver_type,
+                    HMODULE(core::ptr::null_mut()),
+                    D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                    Some(FEATURE_LEVELS),
+                    D3D11_SDK_VERSION,
+                    Some(&mut device),
+                    None,
+                    None,
+                );
+                match &res {
+                    Ok(()) => break,
+                    Err(err) if driver_type == D3D_DRIVER_TYPE_HARDWARE => {
+                        crate::log::log(&format!("failed to create hardware device, using WARP: {err}"));
+                    }
+                    Err(_) => (),
+                }
+            }
+            if let Err(err) = res {
+                crate::log::log(&format!("failed to create WARP device, drawing without Direct3D: {err}"));
+                return Ok(None);
+            }
+            let device = device.unwrap();
+
+            let dxgi = device.cast::<IDXGIDevice1>()?;
+            let d2d = factory.CreateDevice(&dxgi)?;
+            let d2dcontext = d2d.CreateDeviceContext(D2D1_DEVICE_CONTEXT_OPTIONS_NONE)?;
+            Ok(Some((device, d2dcontext)))
+        }
+    }
+
     pub fn new() -> Result<Self> {
         let factory: ID2D1Factory1;
-        let dwfactory;
-        let device;
-        let context;
-        let d2dcontext;
+        let dwfactory: IDWriteFactory;
         unsafe {
-            let mut device_ = None;
-            D3D11CreateDevice(
-                None,
-                D3D_DRIVER_TYPE_HARDWARE,
-                HMODULE(core::ptr::null_mut()),
-                D3D11_CREATE_DEVICE_BGRA_SUPPORT,
-                Some(FEATURE_LEVELS),
-                D3D11_SDK_VERSION,
-                Some(&mut device_),
-                None,
-                None,
-            )?;
-            device = device_.unwrap();
-
             factory = D2D1CreateFactory(
                 D2D1_FACTORY_TYPE_MULTI_THREADED,
                 None,
             )?;
 
             dwfactory = DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED)?;
-
-            let dxgi = device.cast::<IDXGIDevice1>()?;
-            let d2d = factory.CreateDevice(&dxgi)?;
-            d2dcontext = d2d.CreateDeviceContext(D2D1_DEVICE_CONTEXT_OPTIONS_NONE)?;
-
-            context = Self::resize_(&factory, &device, Self::DEFAULT_WIDTH, Self::DEFAULT_HEIGHT)?;
         }
+        let device = Self::create_device_(&factory)?;
+        let context = Self::resize_(
+            &factory,
+            device.as_ref().map(|(device, _)| device),
+            Self::DEFAULT_WIDTH,
+            Self::DEFAULT_HEIGHT,
+        )?;
 
         Ok(Self {
             factory,
+            state: ScopeState {
+                layouts: LayoutCache {
+                    dwfactory: dwfactory.clone(),
+                    formats: RefCell::new(Vec::new()),
+                },
+                device_lost: Cell::new(false),
+                draw_error: Cell::new(None),
+                wide: RefCell::new(Vec::new()),
+            },
             dwfactory,
             device,
             context,
-            d2dcontext,
+            font: None,
 
             width: Self::DEFAULT_WIDTH,
             height: Self::DEFAULT_HEIGHT,
         })
     }
 
+    pub fn is_device_lost(&self) -> bool {
+        self.state.device_lost.get()
+    }
+
+    // new device and render target after a GPU reset or driver update, every
+    // brush and bitmap made before has to be created again
+    //
+    // text formats, fonts and layouts don't depend on the device
+    pub fn recreate(&mut self) -> Result<()> {
+        let device = Self::create_device_(&self.factory)?;
+        self.context = Self::resize_(
+            &self.factory,
+            device.as_ref().map(|(device, _)| device),
+            self.width,
+            self.height,
+        )?;
+        self.device = device;
+        self.state.device_lost.set(false);
+        Ok(())
+    }
+
     pub fn resize(&mut self, width: u32, height: u32) -> Result<bool> {
         if width != self.width || height != self.height {
             self.context = Self::resize_(
                 &self.factory,
-                &self.device,
+                self.device.as_ref().map(|(device, _)| device),
                 width,
                 height,
             )?;
@@ -158,12 +268,17 @@ impl DxgiContext {
         (self.width, self.height)
     }
 
+    // textures need Direct3D, there's none on the software path
+    fn device(&self) -> Result<&(ID3D11Device, ID2D1DeviceContext)> {
+        self.device.as_ref().ok_or_else(|| DXGI_ERROR_UNSUPPORTED.into())
+    }
+
     pub fn create_texture2d(
         &mut self,
         width: u32,
         height: u32,
     ) -> Result<ID3D11Texture2D> {
-        Self::create_texture2d_(&self.device, width, height)
+        Self::create_texture2d_(&self.device()?.0, width, height)
     }
 
     pub fn create_solid_color_brush(
@@ -189,6 +304,7 @@ impl DxgiContext {
         width: u32,
         height: u32,
     ) -> Result<ID2D1Bitmap> {
+        let (device, d2dcontext) = self.device()?;
         unsafe {
             let mut desc: D3D11_TEXTURE2D_DESC = core::mem::zeroed();
             desc.Width = width;
@@ -200,7 +316,7 @@ impl DxgiContext {
             desc.BindFlags = (D3D11_BIND_SHADER_RESOURCE | D3D11_BIND_RENDER_TARGET).0 as u32;
 
             let mut texture = None;
-            self.device.CreateTexture2D(
+            device.CreateTexture2D(
                 &desc,
                 None,
                 Some(&mut texture),
@@ -208,7 +324,7 @@ impl DxgiContext {
             let texture = texture.unwrap();
 
             let surface = texture.cast::<IDXGISurface>().unwrap();
-            let bitmap = self.d2dcontext.CreateBitmapFromDxgiSurface(&surface, None)?;
+            let bitmap = d2dcontext.CreateBitmapFromDxgiSurface(&surface, None)?;
             Ok(bitmap.into())
         }
     }
@@ -272,22 +388,57 @@ impl DxgiContext {
         &mut self,
         texture: &ID3D11Texture2D,
     ) -> Result<ID2D1Bitmap> {
+        let (_, d2dcontext) = self.device()?;
         let surface = texture.cast::<IDXGISurface>()?;
         unsafe {
-            self.d2dcontext.CreateBitmapFromDxgiSurface(&surface, None)
+            d2dcontext.CreateBitmapFromDxgiSurface(&surface, None)
                 .map(|b| b.into())
         }
     }
 
+    // registers OTF/TTF files kept in memory, text formats created after use
+    // the family of the first font instead of the requested one
+    pub fn load_fonts(&mut self, fonts: &[&[u8]]) -> Result<()> {
+        unsafe {
+            let factory = self.dwfactory.cast::<IDWriteFactory5>()?;
+            let loader = factory.CreateInMemoryFontFileLoader()?;
+            factory.RegisterFontFileLoader(&loader)?;
+
+            let builder = factory.CreateFontSetBuilder()?;
+            for font in fonts {
+                // copied by the loader without an owner object
+                let file = loader.CreateInMemoryFontFileReference(
+                    &factory,
+                    font.as_ptr() as *const _,
+                    font.len() as u32,
+                    None,
+                )?;
+                builder.AddFontFile(&file)?;
+            }
+            let collection = factory.CreateFontCollectionFromFontSet(&builder.CreateFontSet()?)?;
+
+            let names = collection.GetFontFamily(0)?.GetFamilyNames()?;
+            let mut family = vec![0; names.GetStringLength(0)? as usize + 1];
+            names.GetString(0, &mut family)?;
+
+            self.font = Some((collection.cast()?, family));
+        }
+        Ok(())
+    }
+
     pub fn create_text_format(
         &mut self,
         font_family: PCWSTR,
         font_size: f32,
     ) -> Result<TextFormat> {
+        let (collection, font_family) = match &self.font {
+            Some((collection, family)) => (Some(collection), PCWSTR(family.as_ptr())),
+            None => (None, font_family),
+        };
         unsafe {
             self.dwfactory.CreateTextFormat(
                 font_family,
-                None,
+                collection,
                 DWRITE_FONT_WEIGHT_SEMI_BOLD,
                 DWRITE_FONT_STYLE_NORMAL,
                 DWRITE_FONT_STRETCH_NORMAL,
@@ -297,6 +448,19 @@ impl DxgiContext {
         }
     }
 
+    // text wider than its rect ends in an ellipsis instead of being cut off
+    pub fn set_ellipsis_trimming(&self, text_format: &TextFormat) -> Result<()> {
+        unsafe {
+            let sign = self.dwfactory.CreateEllipsisTrimmingSign(&text_format.0)?;
+            let trimming = DWRITE_TRIMMING {
+                granularity: DWRITE_TRIMMING_GRANULARITY_CHARACTER,
+                delimiter: 0,
+                delimiterCount: 0,
+            };
+            text_format.0.SetTrimming(&trimming, &sign)
+        }
+    }
+
     pub fn create_text_layout(
         &mut self,
         text: &[u16],
@@ -319,25 +483,7 @@ impl DxgiContext {
         width: u32,
         height: u32,
     ) -> Result<DrawScope<'_>> {
-        unsafe {
-            let size = D2D_SIZE_U {
-                width,
-                height,
-            };
-            let context = self.context.CreateCompatibleRenderTarget(
-                None,
-                Some(&size),
-                None,
-                D2D1_COMPATIBLE_RENDER_TARGET_OPTIONS_NONE,
-            )?;
-
-            context.BeginDraw();
-
-            Ok(DrawScope {
-                context: context.into(),
-                _marker: Default::default(),
-            })
-        }
+        compatible_render_target(&self.context, &self.state, width, height)
     }
 
     pub fn begin_draw(&self) -> DrawScope<'_> {
@@ -346,14 +492,65 @@ impl DxgiContext {
         }
         DrawScope {
             context: self.context.clone(),
-            _marker: Default::default(),
+            state: &self.state,
+            finished: false,
         }
     }
 }
 
+// offscreen target sharing resources with `context`, `DrawScope::get_bitmap`
+// has what was drawn
+fn compatible_render_target<'a>(
+    context: &ID2D1RenderTarget,
+    state: &'a ScopeState,
+    width: u32,
+    height: u32,
+) -> Result<DrawScope<'a>> {
+    unsafe {
+        let size = D2D_SIZE_U {
+            width,
+            height,
+        };
+        let context = context.CreateCompatibleRenderTarget(
+            None,
+            Some(&size),
+            None,
+            D2D1_COMPATIBLE_RENDER_TARGET_OPTIONS_NONE,
+        )?;
+
+        context.BeginDraw();
+
+        Ok(DrawScope {
+            context: context.into(),
+            state,
+            finished: false,
+        })
+    }
+}
+
+// offscreen target kept between frames, see `DrawScope::begin_cached`
+pub struct CachedTarget {
+    context: ID2D1BitmapRenderTarget,
+    bitmap: ID2D1Bitmap,
+    size: (u32, u32),
+}
+
+impl CachedTarget {
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    // what was drawn last, shared with the target
+    pub fn bitmap(&self) -> &ID2D1Bitmap {
+        &self.bitmap
+    }
+}
+
 pub struct DrawScope<'a> {
     context: ID2D1RenderTarget,
-    _marker: core::marker::PhantomData<&'a ()>,
+    state: &'a ScopeState,
+    // `finish` already ended drawing
+    finished: bool,
 }
 
 impl<'a> DrawScope<'a> {
@@ -363,18 +560,57 @@ impl<'a> DrawScope<'a> {
         }
     }
 
-    pub fn set_translation(
+    // draws into `target` while this scope is still open, it's only made
+    // again when the size changes and starts out cleared
+    pub fn begin_cached(
+        &mut self,
+        target: &mut Option<CachedTarget>,
+        width: u32,
+        height: u32,
+    ) -> Result<DrawScope<'_>> {
+        unsafe {
+            if target.as_ref().is_none_or(|target| target.size != (width, height)) {
+                let size = D2D_SIZE_U {
+                    width,
+                    height,
+                };
+                let context = self.context.CreateCompatibleRenderTarget(
+                    None,
+                    Some(&size),
+                    None,
+                    D2D1_COMPATIBLE_RENDER_TARGET_OPTIONS_NONE,
+                )?;
+                *target = Some(CachedTarget {
+                    bitmap: context.GetBitmap()?,
+                    context,
+                    size: (width, height),
+                });
+            }
+            let context = &target.as_ref().unwrap().context;
+            context.BeginDraw();
+            context.Clear(None);
+            Ok(DrawScope {
+                context: context.clone().into(),
+                state: self.state,
+                finished: false,
+            })
+        }
+    }
+
+    // `x` and `y` are unscaled
+    pub fn set_transform(
         &mut self,
         x: f32,
         y: f32,
+        scale: f32,
     ) {
         let mat: [f32; 6] = [
-            1.0,
+            scale,
             0.0,
             0.0,
-            1.0,
-            x,
-            y,
+            scale,
+            x * scale,
+            y * scale,
         ];
         unsafe {
             self.context.SetTransform(mat.as_ptr() as *const _);
@@ -464,24 +700,21 @@ impl<'a> DrawScope<'a> {
         brush: &SolidColorBrush,
         rect: &[f32; 4],
     ) {
-        let _owner;
-        let mut buf = [0; 256];
-        let text = if text.len() > buf.len() {
-            let mut b = Vec::new();
-            for c in text.encode_wide() {
-                b.push(c);
-            }
-            _owner = b;
-            &_owner
-        } else {
-            let mut i = 0;
-            for c in text.encode_wide() {
-                buf[i] = c;
-                i += 1;
-            }
-            &buf[0..i]
-        };
+        let state = self.state;
+        let mut wide = state.wide.borrow_mut();
+        wide.clear();
+        wide.extend(text.encode_wide());
+        self.draw_text_wide(&wide, text_format, brush, rect);
+    }
 
+    // for text already in UTF-16, nothing is converted
+    pub fn draw_text_wide(
+        &mut self,
+        text: &[u16],
+        text_format: &TextFormat,
+        brush: &SolidColorBrush,
+        rect: &[f32; 4],
+    ) {
         let rect = D2D_RECT_F {
             left: rect[0],
             top: rect[1],
@@ -500,6 +733,71 @@ impl<'a> DrawScope<'a> {
         }
     }
 
+    // like `draw_text` but the layout is kept for the next frame, for text
+    // drawn every frame like list rows
+    pub fn draw_text_layout(
+        &mut self,
+        text: &OsStr,
+        text_format: &TextFormat,
+        brush: &SolidColorBrush,
+        rect: &[f32; 4],
+    ) {
+        let format = &text_format.0;
+        let width = rect[2] - rect[0];
+        let height = rect[3] - rect[1];
+        let size = [width.to_bits(), height.to_bits()];
+        let align = unsafe {
+            [
+                format.GetTextAlignment().0,
+                format.GetParagraphAlignment().0,
+                format.GetWordWrapping().0,
+            ]
+        };
+
+        let mut formats = self.state.layouts.formats.borrow_mut();
+        let i = match formats.iter().position(|f| f.format == *format && f.size == size && f.align == align) {
+            Some(i) => i,
+            None => {
+                formats.push(LayoutFormat {
+                    format: format.clone(),
+                    size,
+                    align,
+                    layouts: HashMap::new(),
+                });
+                formats.len() - 1
+            }
+        };
+
+        if !formats[i].layouts.contains_key(text) {
+            if formats.iter().map(|f| f.layouts.len()).sum::<usize>() >= LayoutCache::MAX_LAYOUTS {
+                for f in formats.iter_mut() {
+                    f.layouts.clear();
+                }
+            }
+            let mut wide = self.state.wide.borrow_mut();
+            wide.clear();
+            wide.extend(text.encode_wide());
+            let Ok(layout) = (unsafe {
+                self.state.layouts.dwfactory.CreateTextLayout(&wide, format, width, height)
+            }) else {
+                return;
+            };
+            formats[i].layouts.insert(text.to_owned(), layout);
+        }
+
+        unsafe {
+            self.context.DrawTextLayout(
+                windows_numerics::Vector2 {
+                    X: rect[0],
+                    Y: rect[1],
+                },
+                &formats[i].layouts[text],
+                &brush.0,
+                D2D1_DRAW_TEXT_OPTIONS_CLIP,
+            );
+        }
+    }
+
     pub fn draw_rounded_rect(
         &mut self,
         brush: &SolidColorBrush,
@@ -574,6 +872,94 @@ impl<'a> DrawScope<'a> {
         }
     }
 
+    // everything drawn until `pop_layer` is blended with `opacity`, nothing
+    // outside `rect` is drawn
+    pub fn push_layer(&mut self, opacity: f32, rect: [f32; 4]) -> Result<()> {
+        let [left, top, right, bottom] = rect;
+        let mut params = D2D1_LAYER_PARAMETERS {
+            contentBounds: D2D_RECT_F {
+                left,
+                top,
+                right,
+                bottom,
+            },
+            opacity,
+            layerOptions: D2D1_LAYER_OPTIONS_NONE,
+            ..Default::default()
+        };
+        params.maskTransform.M11 = 1.0;
+        params.maskTransform.M22 = 1.0;
+        unsafe {
+            let layer = self.context.CreateLayer(None)?;
+            self.context.PushLayer(&params, &layer);
+        }
+        Ok(())
+    }
+
+    pub fn pop_layer(&mut self) {
+        unsafe {
+            self.context.PopLayer();
+        }
+    }
+
+    // replaces what was drawn inside `rect` with a gaussian blur of it, `rect`
+    // is in pixels and ignores the transform
+    pub fn blur(&mut self, rect: [u32; 4], deviation: f32) -> Result<()> {
+        let [left, top, right, bottom] = rect;
+        if right <= left || bottom <= top {
+            return Ok(());
+        }
+        unsafe {
+            let context: ID2D1DeviceContext = self.context.cast()?;
+            let (mut dpi_x, mut dpi_y) = (0.0, 0.0);
+            self.context.GetDpi(&mut dpi_x, &mut dpi_y);
+            let props = D2D1_BITMAP_PROPERTIES {
+                pixelFormat: self.context.GetPixelFormat(),
+                dpiX: dpi_x,
+                dpiY: dpi_y,
+            };
+            let size = D2D_SIZE_U {
+                width: right - left,
+                height: bottom - top,
+            };
+            let bitmap = self.context.CreateBitmap(size, None, 0, &props)?;
+            let src = D2D_RECT_U {
+                left,
+                top,
+                right,
+                bottom,
+            };
+            bitmap.CopyFromRenderTarget(None, &self.context, Some(&src))?;
+
+            let effect = context.CreateEffect(&CLSID_D2D1GaussianBlur)?;
+            effect.SetInput(0, &bitmap, true);
+            effect.SetValue(
+                D2D1_GAUSSIANBLUR_PROP_STANDARD_DEVIATION.0 as u32,
+                D2D1_PROPERTY_TYPE_FLOAT,
+                &deviation.to_ne_bytes(),
+            )?;
+            // the edges would fade to transparent otherwise
+            effect.SetValue(
+                D2D1_GAUSSIANBLUR_PROP_BORDER_MODE.0 as u32,
+                D2D1_PROPERTY_TYPE_ENUM,
+                &(D2D1_BORDER_MODE_HARD.0 as u32).to_ne_bytes(),
+            )?;
+
+            let offset = windows_numerics::Vector2 {
+                X: left as f32 * 96.0 / dpi_x,
+                Y: top as f32 * 96.0 / dpi_y,
+            };
+            context.DrawImage(
+                &effect.GetOutput()?,
+                Some(&offset),
+                None,
+                D2D1_INTERPOLATION_MODE_LINEAR,
+                D2D1_COMPOSITE_MODE_SOURCE_COPY,
+            );
+        }
+        Ok(())
+    }
+
     pub fn get_dc(&mut self) -> Result<HdcScope<'_>> {
         let (interop, hdc) = unsafe {
             let interop: ID2D1GdiInteropRenderTarget = self.context.cast()?;
@@ -587,6 +973,30 @@ impl<'a> DrawScope<'a> {
         })
     }
 
+    // errors from any draw call since `BeginDraw` show up here, dropping the
+    // scope only logs them
+    pub fn finish(mut self) -> Result<()> {
+        self.finished = true;
+        self.end_draw()
+    }
+
+    fn end_draw(&mut self) -> Result<()> {
+        let res = unsafe {
+            self.context.EndDraw(None, None)
+        };
+        let code = res.as_ref().err().map(windows::core::Error::code);
+        if let Some(code) = code
+            && self.state.draw_error.get() != Some(code)
+        {
+            crate::log::log(&format!("failed to draw: {}", windows::core::Error::from(code)));
+        }
+        self.state.draw_error.set(code);
+        if code == Some(D2DERR_RECREATE_TARGET) {
+            self.state.device_lost.set(true);
+        }
+        res
+    }
+
     pub fn get_bitmap(&mut self) -> Result<ID2D1Bitmap> {
         unsafe {
             let context: ID2D1BitmapRenderTarget = self.context.cast()?;
@@ -597,8 +1007,8 @@ impl<'a> DrawScope<'a> {
 
 impl<'a> Drop for DrawScope<'a> {
     fn drop(&mut self) {
-        unsafe {
-            let _ = self.context.EndDraw(None, None);
+        if !self.finished {
+            let _ = self.end_draw();
         }
     }
 }
@@ -1,4 +1,7 @@
 const PNG_HEADER: &[u8] = &[137, 80, 78, 71, 13, 10, 26, 10];
+// sfnt versions for CFF (OTTO) and TrueType outlines
+const FONT_HEADERS: &[&[u8]] = &[b"OTTO", &[0, 1, 0, 0]];
+const MAX_FONT_TABLES: usize = 64;
 
 #[allow(dead_code)]
 pub struct Png<'a> {
@@ -83,3 +86,68 @@ impl<'a> Iterator for ExtractPng<'a> {
         None
     }
 }
+
+// OTF/TTF files found by a valid looking sfnt table directory
+pub struct ExtractFont<'a> {
+    buffer: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ExtractFont<'a> {
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self {
+            buffer,
+            offset: 0,
+        }
+    }
+
+    // length of the font starting at `start`, or `None` if it isn't one
+    fn font_len(buf: &[u8], start: usize) -> Option<usize> {
+        let u16_at = |o: usize| Some(u16::from_be_bytes(buf.get(o..o + 2)?.try_into().ok()?) as usize);
+        let u32_at = |o: usize| Some(u32::from_be_bytes(buf.get(o..o + 4)?.try_into().ok()?) as usize);
+
+        let num_tables = u16_at(start + 4)?;
+        if num_tables == 0 || num_tables > MAX_FONT_TABLES {
+            return None;
+        }
+
+        let mut len = 12 + num_tables * 16;
+        let mut has_name = false;
+        for i in 0..num_tables {
+            let record = start + 12 + i * 16;
+            let tag = buf.get(record..record + 4)?;
+            if !tag.iter().all(|c| (0x20..0x7f).contains(c)) {
+                return None;
+            }
+            has_name |= tag == b"name";
+
+            let offset = u32_at(record + 8)?;
+            let length = u32_at(record + 12)?;
+            len = len.max(offset.checked_add(length)?);
+        }
+
+        (has_name && start + len <= buf.len()).then_some(len)
+    }
+}
+
+impl<'a> Iterator for ExtractFont<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let buf = self.buffer;
+        while self.offset + 12 <= buf.len() {
+            let start = self.offset;
+            let header = &buf[start..start + 4];
+            if FONT_HEADERS.contains(&header)
+                && let Some(len) = Self::font_len(buf, start)
+            {
+                self.offset = start + len;
+                return Some(&buf[start..start + len]);
+            }
+            self.offset += 1;
+        }
+
+        self.offset = buf.len();
+        None
+    }
+}
@@ -48,6 +48,12 @@ type Callback = dyn FnMut(
 
 static CALLBACK: Mutex<Option<Box<Callback>>> = Mutex::new(None);
 static BYPASS: AtomicBool = AtomicBool::new(false);
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+// installed and not bypassed after a panic
+pub fn is_active() -> bool {
+    INSTALLED.load(Ordering::SeqCst) && !BYPASS.load(Ordering::SeqCst)
+}
 
 unsafe extern "system" fn update_layered_window_indirect_hook(
     hwnd: HWND,
@@ -138,6 +144,7 @@ pub fn hook_ulw(
             &mut old_flags,
         )?;
     }
+    INSTALLED.store(true, Ordering::SeqCst);
 
     Ok(())
 }
@@ -6,7 +6,9 @@ use windows::Win32::Foundation::GetLastError;
 use windows::Win32::Graphics::Gdi::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
 
-mod archive;
+use modtide_core::archive;
+use modtide_core::backup;
+use modtide_core::config;
 mod log;
 mod extract;
 mod hook;
@@ -16,8 +18,14 @@ mod widget;
 use widget::button::ButtonWidget;
 use widget::dropdown::DropdownWidget;
 use widget::list::ModListWidget;
-mod mod_engine;
-mod patch;
+use widget::tooltip::TooltipWidget;
+use widget::confirm::ConfirmWidget;
+use widget::settings::SettingsWidget;
+use widget::drop_hint::DropHintWidget;
+use modtide_core::mod_engine;
+use modtide_core::manifest;
+use modtide_core::package;
+use modtide_core::patch;
 
 // TODO: stub like wine/dlls/dwmapi/dwmapi_main.c
 #[unsafe(no_mangle)]
@@ -92,108 +100,68 @@ fn init() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    let mut context = dxgi::DxgiContext::new().unwrap();
-    let brush_color = [1.0, 1.0, 1.0, 1.0];
-    let brush = context.create_solid_color_brush(&brush_color).unwrap();
-    let text_format = context.create_text_format(windows::core::w!("Arial"), 17.0).unwrap();
-
-    let (button_active, button_idle) = match (button_active, button_idle) {
-        (Some(button_active), Some(button_idle)) => {
-            (
-                context.create_bitmap_from_png(button_active, None).unwrap(),
-                context.create_bitmap_from_png(button_idle, None).unwrap(),
-            )
-        }
-        _ => {
-            let mut button_active = None;
-            let mut button_idle = None;
-            for (button, is_active) in [
-                (&mut button_active, true),
-                (&mut button_idle, false),
-            ] {
-                let mut draw = context.create_compatible_render_target(
-                    ButtonWidget::WIDTH,
-                    ButtonWidget::HEIGHT,
-                ).unwrap();
-                ButtonWidget::fallback(&mut draw, &brush, is_active);
-                *button = draw.get_bitmap().ok();
-            }
-
-            (
-                button_active.unwrap(),
-                button_idle.unwrap(),
-            )
+    // colors are needed before the fallback images are drawn
+    let config = config::Config::load(&root.join("mods")).unwrap_or_else(|err| {
+        log::log(&format!("failed to load settings: {err}"));
+        config::Config::default()
+    });
+    let theme = widget::theme::Theme::from_config(&config);
+
+    // without Direct2D the launcher is left to draw itself, missing Direct3D
+    // only falls back to drawing on the CPU
+    let mut context = match dxgi::DxgiContext::new() {
+        Ok(context) => context,
+        Err(err) => {
+            log::log(&format!("failed to create render device, mod list disabled: {err}"));
+            return Ok(());
         }
     };
+    // Arial is kept if the launcher's fonts can't be loaded
+    let fonts: Vec<&[u8]> = extract::ExtractFont::new(&data).collect();
+    if !fonts.is_empty()
+        && let Err(err) = context.load_fonts(&fonts)
+    {
+        log::log(&format!("failed to load launcher fonts: {err}"));
+    }
+    let text_format = context.create_text_format(windows::core::w!("Arial"), 17.0).unwrap();
+    // separate so long mod names get an ellipsis without other widgets' text
+    let list_format = context.create_text_format(windows::core::w!("Arial"), 17.0).unwrap();
+    if let Err(err) = context.set_ellipsis_trimming(&list_format) {
+        log::log(&format!("failed to set text trimming: {err}"));
+    }
 
-    let background = if let Some(background) = background {
-        context.create_bitmap_from_png(background, Some(reduce_alpha)).unwrap()
-    } else {
-        let mut draw = context.create_compatible_render_target(
-            ModListWidget::WIDTH,
-            ModListWidget::HEIGHT,
-        ).unwrap();
-        ModListWidget::fallback(&mut draw, &brush);
-        draw.get_bitmap().unwrap()
-    };
-
-    unsafe {
-        brush.set_color(&brush_color);
-
-        let size = button_active.GetPixelSize();
-        let sizef = button_active.GetSize();
-        let rectf = [
-            0.0,
-            0.0,
-            sizef.width,
-            sizef.height,
-        ];
-
-        text_format.set_text_alignment(crate::dxgi::Alignment::Mid).unwrap();
-        text_format.set_paragraph_alignment(crate::dxgi::Alignment::Mid).unwrap();
-        //let text_layout = context.create_text_layout(
-        //    &b"MODS".map(u16::from),
-        //    &text_format,
-        //    sizef.width,
-        //    sizef.height,
-        //).unwrap();
-
-        let mut draw = context.create_compatible_render_target(size.width, size.height).unwrap();
-        for bitmap in [&button_active, &button_idle] {
-            draw.clear();
-            draw.draw_bitmap(
-                bitmap,
-                None,
-                None,
-            );
-            draw.draw_text(
-                "MODS".as_ref(),
-                &text_format,
-                &brush,
-                &rectf,
-            );
-            let target = draw.get_bitmap().unwrap();
-            bitmap.CopyFromBitmap(None, &target, None).unwrap();
+    // kept to make the bitmaps again after the device is lost
+    let pngs = [button_active, button_idle, background].map(|png| png.map(<[u8]>::to_vec));
+    let widget::Assets {
+        brush,
+        button_active,
+        button_idle,
+        background,
+    } = match create_assets(&mut context, &pngs, &text_format, &theme) {
+        Ok(assets) => assets,
+        Err(err) => {
+            log::log(&format!("failed to create render assets, mod list disabled: {err}"));
+            return Ok(());
         }
-        drop(draw);
-
-        text_format.set_text_alignment(crate::dxgi::Alignment::Min).unwrap();
-    }
+    };
 
     let dropdown = DropdownWidget::new(brush.clone(), text_format.clone());
-    let button = ButtonWidget::new(button_active, button_idle);
+    let tooltip = TooltipWidget::new(brush.clone(), text_format.clone());
+    let confirm = ConfirmWidget::new(brush.clone(), text_format.clone());
+    let settings = SettingsWidget::new(brush.clone(), text_format.clone(), root.join("mods"));
+    let drop_hint = DropHintWidget::new(brush.clone(), text_format.clone());
+    let button = ButtonWidget::new(button_active, button_idle, brush.clone(), text_format.clone());
     let mut mod_list = ModListWidget::new(
         root.join("mods"),
         background,
         brush,
-        text_format);
-    if let Err(err) = mod_list.mount() {
-        eprintln!("failed mod list mount: {err:?}");
-    }
-    let mut widgets = Some((mod_list, button, dropdown));
+        list_format);
+    mod_list.restore_patch();
+    // shown with `Retry` once the list gets its first event
+    mod_list.refresh();
+    let mut widgets = Some((mod_list, button, dropdown, tooltip, confirm, settings, drop_hint));
 
     hook::hook_ulw(Box::new(move |hwnd, org_info| {
-        // TODO: blur and dim widgets when settings are open
         if let Some(control) = &mut *widget::CONTROL.lock().unwrap()
             && hwnd != control.display // !control.is_hooked_hwnd(hwnd)
         {
@@ -210,7 +178,12 @@ fn init() -> Result<(), Box<dyn std::error::Error>> {
         let height = rect.bottom - rect.top;
         let widthu = u32::try_from(width).unwrap();
         let heightu = u32::try_from(height).unwrap();
-        context.resize(widthu, heightu).unwrap();
+        if let Err(err) = context.resize(widthu, heightu) {
+            log::log(&format!("failed to resize render target: {err}"));
+            recreate_device(&mut context, &pngs, &text_format, &theme);
+            hook::update_layered_window_indirect(hwnd, org_info);
+            return;
+        }
 
         let bf = BLENDFUNCTION {
             BlendOp: AC_SRC_OVER as u8,
@@ -240,6 +213,7 @@ fn init() -> Result<(), Box<dyn std::error::Error>> {
             }
 
             if let Some(control) = &mut *widget::CONTROL.lock().unwrap() {
+                control.resize(width, height);
                 control.render(&mut draw);
             }
 
@@ -260,14 +234,146 @@ fn init() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
 
+        // `EndDraw` failed, the next frame is drawn with the new device
+        if context.is_device_lost() {
+            log::log("render target lost, recreating device");
+            recreate_device(&mut context, &pngs, &text_format, &theme);
+        }
+
         if let Some(w) = widgets.take() {
-            widget::Control::hook(w.0, w.1, w.2, hwnd);
+            widget::Control::hook(w.0, w.1, w.2, w.3, w.4, w.5, w.6, hwnd);
         }
     })).unwrap();
 
     Ok(())
 }
 
+fn create_assets(
+    context: &mut dxgi::DxgiContext,
+    pngs: &[Option<Vec<u8>>; 3],
+    text_format: &dxgi::TextFormat,
+    theme: &widget::theme::Theme,
+) -> Result<widget::Assets, Box<dyn std::error::Error>> {
+    let brush_color = [1.0, 1.0, 1.0, 1.0];
+    let brush = context.create_solid_color_brush(&brush_color)?;
+
+    let (button_active, button_idle) = match (&pngs[0], &pngs[1]) {
+        (Some(button_active), Some(button_idle)) => {
+            (
+                context.create_bitmap_from_png(button_active, None)?,
+                context.create_bitmap_from_png(button_idle, None)?,
+            )
+        }
+        _ => {
+            let mut button_active = None;
+            let mut button_idle = None;
+            for (button, is_active) in [
+                (&mut button_active, true),
+                (&mut button_idle, false),
+            ] {
+                let mut draw = context.create_compatible_render_target(
+                    ButtonWidget::WIDTH,
+                    ButtonWidget::HEIGHT,
+                )?;
+                ButtonWidget::fallback(&mut draw, &brush, theme, is_active);
+                *button = Some(draw.get_bitmap()?);
+                draw.finish()?;
+            }
+
+            (
+                button_active.unwrap(),
+                button_idle.unwrap(),
+            )
+        }
+    };
+
+    let background = if let Some(background) = &pngs[2] {
+        context.create_bitmap_from_png(background, Some(reduce_alpha))?
+    } else {
+        let mut draw = context.create_compatible_render_target(
+            ModListWidget::WIDTH,
+            ModListWidget::HEIGHT,
+        )?;
+        ModListWidget::fallback(&mut draw, &brush, theme);
+        let bitmap = draw.get_bitmap()?;
+        draw.finish()?;
+        bitmap
+    };
+
+    unsafe {
+        brush.set_color(&brush_color);
+
+        let size = button_active.GetPixelSize();
+        let sizef = button_active.GetSize();
+        let rectf = [
+            0.0,
+            0.0,
+            sizef.width,
+            sizef.height,
+        ];
+
+        text_format.set_text_alignment(crate::dxgi::Alignment::Mid)?;
+        text_format.set_paragraph_alignment(crate::dxgi::Alignment::Mid)?;
+        //let text_layout = context.create_text_layout(
+        //    &b"MODS".map(u16::from),
+        //    &text_format,
+        //    sizef.width,
+        //    sizef.height,
+        //).unwrap();
+
+        let mut draw = context.create_compatible_render_target(size.width, size.height)?;
+        for bitmap in [&button_active, &button_idle] {
+            draw.clear();
+            draw.draw_bitmap(
+                bitmap,
+                None,
+                None,
+            );
+            draw.draw_text(
+                "MODS".as_ref(),
+                text_format,
+                &brush,
+                &rectf,
+            );
+            let target = draw.get_bitmap()?;
+            bitmap.CopyFromBitmap(None, &target, None)?;
+        }
+        draw.finish()?;
+
+        text_format.set_text_alignment(crate::dxgi::Alignment::Min)?;
+    }
+
+    Ok(widget::Assets {
+        brush,
+        button_active,
+        button_idle,
+        background,
+    })
+}
+
+fn recreate_device(
+    context: &mut dxgi::DxgiContext,
+    pngs: &[Option<Vec<u8>>; 3],
+    text_format: &dxgi::TextFormat,
+    theme: &widget::theme::Theme,
+) {
+    // the list's colors once it's hooked, `theme` is from startup
+    let theme = widget::CONTROL.lock().unwrap()
+        .as_ref()
+        .map_or(*theme, |control| control.theme());
+    let res = context.recreate()
+        .map_err(Into::into)
+        .and_then(|()| create_assets(context, pngs, text_format, &theme));
+    match res {
+        Ok(assets) => {
+            if let Some(control) = &mut *widget::CONTROL.lock().unwrap() {
+                control.recreate(&assets);
+            }
+        }
+        Err(err) => log::log(&format!("failed to recreate device: {err}")),
+    }
+}
+
 fn reduce_alpha(buf: &mut [[u8; 4]]) {
     for pixel in buf {
         let mut p = *pixel;
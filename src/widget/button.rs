@@ -1,8 +1,17 @@
+use std::any::Any;
+
 use windows::Win32::Graphics::Direct2D::ID2D1Bitmap;
 use crate::dxgi::SolidColorBrush;
+use crate::dxgi::TextFormat;
 
+use super::Control;
 use super::Event;
 use super::EventKind;
+use super::KeyKind;
+use super::WidgetConfig;
+use super::dropdown::DropdownMenu;
+use super::dropdown::DropdownWidget;
+use super::theme::Theme;
 
 // launcher exit button is anchor
 pub(super) const EXIT_WIDTH: u32 = 38;
@@ -21,10 +30,17 @@ enum Mode {
 pub struct ButtonWidget {
     active: ID2D1Bitmap,
     idle: ID2D1Bitmap,
+    brush: SolidColorBrush,
+    theme: Theme,
+    text_format: TextFormat,
     width: u32,
     height: u32,
 
     mode: Mode,
+    // keyboard focus, drawn like hovering
+    focused: bool,
+    // from the mod list, see `Control::hook`
+    patched: Option<bool>,
 }
 
 impl ButtonWidget {
@@ -33,29 +49,35 @@ impl ButtonWidget {
 
     pub(super) const MARGIN_RIGHT: u32 = EXIT_WIDTH + EXIT_X_OFFSET * 2;
     pub(super) const MARGIN_TOP: u32 = EXIT_Y_OFFSET + EXIT_HEIGHT / 2;
-
-    const FALLBACK_ACTIVE: [f32; 4] = [0.2, 0.2, 0.2, 0.8];
-    const FALLBACK_IDLE: [f32; 4] = [0.0, 0.0, 0.0, 0.8];
-    const FALLBACK_BORDER: [f32; 4] = [0.6, 0.6, 0.6, 1.0];
+    // patch status under the button, outside `rect` so it doesn't take clicks
+    const STATUS_HEIGHT: u32 = 20;
 
     pub fn new(
         active: ID2D1Bitmap,
         idle: ID2D1Bitmap,
+        brush: SolidColorBrush,
+        text_format: TextFormat,
     ) -> Self {
         let size = unsafe { active.GetPixelSize() };
         Self {
             active,
             idle,
+            brush,
+            theme: Theme::DEFAULT,
+            text_format,
             width: size.width,
             height: size.height,
 
             mode: Mode::Idle,
+            focused: false,
+            patched: None,
         }
     }
 
     pub fn fallback(
         context: &mut super::DrawScope,
         brush: &SolidColorBrush,
+        theme: &Theme,
         is_active: bool,
     ) {
         let rect = [
@@ -67,9 +89,9 @@ impl ButtonWidget {
         let radius = 2.0;
 
         let color = if is_active {
-            Self::FALLBACK_ACTIVE
+            theme.button_active
         } else {
-            Self::FALLBACK_IDLE
+            theme.panel
         };
         brush.set_color(&color);
         context.fill_rounded_rect(
@@ -78,7 +100,7 @@ impl ButtonWidget {
             radius,
         );
 
-        brush.set_color(&Self::FALLBACK_BORDER);
+        brush.set_color(&theme.border);
         context.draw_rounded_rect(
             brush,
             rect,
@@ -90,11 +112,19 @@ impl ButtonWidget {
 }
 
 impl super::Widget for ButtonWidget {
+    fn config(&self) -> WidgetConfig {
+        WidgetConfig {
+            focusable: true,
+            ..Default::default()
+        }
+    }
+
     fn rect(&self, width: u32, _height: u32) -> [u32; 4] {
+        let right = width.saturating_sub(Self::MARGIN_RIGHT).max(self.width);
         [
-            width - Self::MARGIN_RIGHT - self.width,
+            right - self.width,
             Self::MARGIN_TOP - self.height / 2,
-            width - Self::MARGIN_RIGHT,
+            right,
             Self::MARGIN_TOP + self.height / 2,
         ]
     }
@@ -129,7 +159,7 @@ impl super::Widget for ButtonWidget {
                 EventKind::MouseLeftRelease => {
                     control.release_mouse();
                     if old == Mode::Active {
-                        control.toggle_widget(super::Control::MOD_LIST_WIDGET);
+                        control.toggle_widget(Control::MOD_LIST_WIDGET);
                     }
                 }
                 EventKind::MouseLeftPress => control.capture_mouse(),
@@ -138,6 +168,39 @@ impl super::Widget for ButtonWidget {
 
             control.redraw();
         }
+
+        // sort previews and prompts are drawn in the list so it's opened too
+        if event.kind == EventKind::MouseRightRelease && intersect {
+            control.show_widget(Control::MOD_LIST_WIDGET);
+            DropdownWidget::show(control, 0, self.height as i32, DropdownMenu::Meta);
+        }
+
+        match event.kind {
+            EventKind::KeyFocus(_) => self.focused = true,
+            EventKind::KeyBlur => self.focused = false,
+            EventKind::KeyDown(KeyKind::Enter | KeyKind::Space) => {
+                control.toggle_widget(Control::MOD_LIST_WIDGET);
+            }
+            _ => return,
+        }
+        control.redraw();
+    }
+
+    fn set_theme(&mut self, theme: &Theme) {
+        self.theme = *theme;
+    }
+
+    // the patch state from the mod list
+    fn receive(&mut self, data: Box<dyn Any>) {
+        if let Ok(is_patched) = data.downcast::<bool>() {
+            self.patched = Some(*is_patched);
+        }
+    }
+
+    fn recreate(&mut self, assets: &super::Assets) {
+        self.active = assets.button_active.clone();
+        self.idle = assets.button_idle.clone();
+        self.brush = assets.brush.clone();
     }
 
     fn render(&mut self, context: &mut super::DrawScope) {
@@ -152,13 +215,42 @@ impl super::Widget for ButtonWidget {
         }
 
         let bitmap = match self.mode {
-            Mode::Idle => &self.idle,
+            Mode::Idle if !self.focused => &self.idle,
+            Mode::Idle => &self.active,
 
             Mode::Held
             | Mode::Hover
             | Mode::Active => &self.active,
         };
 
-        context.draw_bitmap(bitmap, Some(&rect), None);
+        let theme = self.theme;
+        if theme.high_contrast {
+            let is_active = self.focused || self.mode != Mode::Idle;
+            Self::fallback(context, &self.brush, &theme, is_active);
+        } else {
+            context.draw_bitmap(bitmap, Some(&rect), None);
+        }
+
+        let Some(is_patched) = self.patched else {
+            return;
+        };
+        let (text, color) = match is_patched {
+            true => ("mods active", theme.enabled),
+            false => ("patch missing", theme.not_installed),
+        };
+        self.brush.set_color(&color);
+        self.text_format.set_text_alignment(crate::dxgi::Alignment::Mid).unwrap();
+        context.draw_text(
+            text.as_ref(),
+            &self.text_format,
+            &self.brush,
+            &[
+                0.0,
+                self.height as f32,
+                self.width as f32,
+                (self.height + Self::STATUS_HEIGHT) as f32,
+            ],
+        );
+        self.text_format.set_text_alignment(crate::dxgi::Alignment::Min).unwrap();
     }
 }
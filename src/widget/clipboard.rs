@@ -0,0 +1,104 @@
+use std::ffi::OsString;
+use std::os::windows::ffi::OsStringExt;
+use std::path::PathBuf;
+
+use windows::Win32::Foundation::GlobalFree;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::HGLOBAL;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::System::DataExchange::*;
+use windows::Win32::System::Memory::GlobalAlloc;
+use windows::Win32::System::Memory::GlobalLock;
+use windows::Win32::System::Memory::GMEM_MOVEABLE;
+use windows::Win32::System::Memory::GlobalUnlock;
+use windows::Win32::System::Ole::CF_HDROP;
+use windows::Win32::System::Ole::CF_UNICODETEXT;
+use windows::Win32::UI::Shell::DragQueryFileW;
+use windows::Win32::UI::Shell::HDROP;
+
+// files copied in explorer, or existing paths copied as text one per line
+pub fn paths() -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    unsafe {
+        if OpenClipboard(None).is_err() {
+            return out;
+        }
+
+        if let Ok(handle) = GetClipboardData(CF_HDROP.0 as u32) {
+            let hdrop = HDROP(handle.0);
+            let count = DragQueryFileW(hdrop, u32::MAX, None);
+            let mut buf = vec![0; 4097];
+            for i in 0..count {
+                let len = DragQueryFileW(hdrop, i, Some(&mut buf));
+                out.push(PathBuf::from(OsString::from_wide(&buf[..len as usize])));
+            }
+        } else if let Ok(handle) = GetClipboardData(CF_UNICODETEXT.0 as u32) {
+            let hglobal = HGLOBAL(handle.0);
+            let ptr = GlobalLock(hglobal) as *const u16;
+            if !ptr.is_null() {
+                let mut len = 0;
+                while *ptr.add(len) != 0 {
+                    len += 1;
+                }
+                let text = String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len));
+                let _ = GlobalUnlock(hglobal);
+                out.extend(text.lines().filter_map(text_path));
+            }
+        }
+
+        let _ = CloseClipboard();
+    }
+    out
+}
+
+// `hwnd` owns the clipboard, `SetClipboardData` fails without one
+pub fn set_text(hwnd: HWND, text: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let wide: Vec<u16> = text.encode_utf16().chain([0]).collect();
+    unsafe {
+        OpenClipboard(Some(hwnd))?;
+        let res = (|| {
+            EmptyClipboard()?;
+            let hglobal = GlobalAlloc(GMEM_MOVEABLE, wide.len() * 2)?;
+            let ptr = GlobalLock(hglobal) as *mut u16;
+            if ptr.is_null() {
+                let _ = GlobalFree(Some(hglobal));
+                return Err(windows::core::Error::from_thread());
+            }
+            core::ptr::copy_nonoverlapping(wide.as_ptr(), ptr, wide.len());
+            let _ = GlobalUnlock(hglobal);
+            // the clipboard frees it once set
+            if let Err(err) = SetClipboardData(CF_UNICODETEXT.0 as u32, Some(HANDLE(hglobal.0))) {
+                let _ = GlobalFree(Some(hglobal));
+                return Err(err);
+            }
+            Ok(())
+        })();
+        let _ = CloseClipboard();
+        res?;
+    }
+    Ok(())
+}
+
+// plain or quoted paths and `file:///` links
+fn text_path(line: &str) -> Option<PathBuf> {
+    let line = line.trim().trim_matches('"');
+    let path = match line.strip_prefix("file:///") {
+        Some(url) => PathBuf::from(percent_decode(url)?),
+        None => PathBuf::from(line),
+    };
+    (!line.is_empty() && path.exists()).then_some(path)
+}
+
+fn percent_decode(url: &str) -> Option<String> {
+    let mut out = Vec::with_capacity(url.len());
+    let mut bytes = url.bytes();
+    while let Some(b) = bytes.next() {
+        if b == b'%' {
+            let hex = [bytes.next()?, bytes.next()?];
+            out.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            out.push(b);
+        }
+    }
+    String::from_utf8(out).ok()
+}
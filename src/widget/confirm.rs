@@ -0,0 +1,259 @@
+use std::any::Any;
+use std::cell::Cell;
+
+use crate::dxgi::SolidColorBrush;
+use crate::dxgi::TextFormat;
+
+use super::Control;
+use super::ControlScope;
+use super::Event;
+use super::EventKind;
+use super::KeyKind;
+use super::WidgetConfig;
+use super::theme::Theme;
+
+struct Request {
+    lines: Vec<String>,
+    ok: String,
+    // widget receiving the `Custom` events
+    owner: usize,
+    on_ok: u32,
+    on_cancel: Option<u32>,
+}
+
+// covers the whole window so nothing else takes input while it's shown
+pub struct ConfirmWidget {
+    brush: SolidColorBrush,
+    theme: Theme,
+    text_format: TextFormat,
+
+    // window size from the last `rect`
+    size: Cell<(u32, u32)>,
+
+    request: Option<Request>,
+    hovered_option: Option<usize>,
+}
+
+impl ConfirmWidget {
+    const BOX_WIDTH: u32 = 460;
+    const PADDING: u32 = 12;
+    const LINE_HEIGHT: u32 = 22;
+    const BUTTON_WIDTH: u32 = 120;
+    const MAX_LINES: usize = 12;
+
+    pub fn new(
+        brush: SolidColorBrush,
+        text_format: TextFormat,
+    ) -> Self {
+        Self {
+            brush,
+            theme: Theme::DEFAULT,
+            text_format,
+
+            size: Cell::new((0, 0)),
+
+            request: None,
+            hovered_option: None,
+        }
+    }
+
+    // asks before a destructive action, `on_ok` or `on_cancel` is sent back to
+    // the calling widget as `EventKind::Custom`
+    //
+    // `ESC` and clicking `Cancel` both cancel
+    pub fn show(
+        control: &mut ControlScope,
+        lines: Vec<String>,
+        ok: &str,
+        on_ok: u32,
+        on_cancel: Option<u32>,
+    ) {
+        let request = Request {
+            lines,
+            ok: ok.to_string(),
+            owner: control.widget,
+            on_ok,
+            on_cancel,
+        };
+        control.deliver(Control::CONFIRM_WIDGET, request);
+        control.show_widget(Control::CONFIRM_WIDGET);
+        // a new question replacing a shown one comes back to the front
+        control.raise(Control::CONFIRM_WIDGET);
+    }
+
+    fn lines(&self) -> Vec<&str> {
+        let Some(request) = &self.request else {
+            return Vec::new();
+        };
+        let mut lines: Vec<&str> = request.lines.iter()
+            .take(Self::MAX_LINES)
+            .map(String::as_str)
+            .collect();
+        if request.lines.len() > Self::MAX_LINES {
+            lines.push("...");
+        }
+        lines
+    }
+
+    fn box_rect(&self) -> [u32; 4] {
+        let (width, height) = self.size.get();
+        let lines = self.lines().len() as u32;
+        let box_height = Self::PADDING * 3 + Self::LINE_HEIGHT * (lines + 1);
+        let left = width.saturating_sub(Self::BOX_WIDTH) / 2;
+        let top = height.saturating_sub(box_height) / 2;
+        [left, top, left + Self::BOX_WIDTH, top + box_height]
+    }
+
+    // OK then Cancel, right aligned on the last row
+    fn button_rects(&self) -> [[u32; 4]; 2] {
+        let [_, _, right, bottom] = self.box_rect();
+        let top = bottom - Self::PADDING - Self::LINE_HEIGHT;
+        let cancel = right - Self::PADDING - Self::BUTTON_WIDTH;
+        let ok = cancel - Self::PADDING - Self::BUTTON_WIDTH;
+        [
+            [ok, top, ok + Self::BUTTON_WIDTH, bottom - Self::PADDING],
+            [cancel, top, cancel + Self::BUTTON_WIDTH, bottom - Self::PADDING],
+        ]
+    }
+
+    fn button_at(&self, x: i32, y: i32) -> Option<usize> {
+        let (Ok(x), Ok(y)) = (u32::try_from(x), u32::try_from(y)) else {
+            return None;
+        };
+        self.button_rects()
+            .iter()
+            .position(|[x0, y0, x1, y1]| x >= *x0 && x < *x1 && y >= *y0 && y < *y1)
+    }
+
+    fn close(&mut self, control: &mut ControlScope, ok: bool) {
+        if let Some(request) = self.request.take() {
+            let event = match ok {
+                true => Some(request.on_ok),
+                false => request.on_cancel,
+            };
+            if let Some(event) = event {
+                control.send_event(request.owner, event);
+            }
+        }
+        control.hide_widget(Control::CONFIRM_WIDGET);
+    }
+}
+
+impl super::Widget for ConfirmWidget {
+    fn config(&self) -> WidgetConfig {
+        WidgetConfig {
+            z_index: WidgetConfig::DIALOG_LAYER,
+            ..Default::default()
+        }
+    }
+
+    fn rect(&self, width: u32, height: u32) -> [u32; 4] {
+        self.size.set((width, height));
+        [0, 0, width, height]
+    }
+
+    fn handle_event(
+        &mut self,
+        control: &mut ControlScope,
+        event: Event,
+    ) {
+        match event.kind {
+            EventKind::Show => {
+                self.hovered_option = None;
+                control.capture_mouse();
+            }
+            EventKind::Hide => control.release_mouse(),
+            // `ESC` breaks the capture
+            EventKind::LostFocus => self.close(control, false),
+            EventKind::KeyDown(KeyKind::Enter) => self.close(control, true),
+            EventKind::MouseMove(_) => {
+                let hovered = self.button_at(event.x, event.y);
+                if hovered != self.hovered_option {
+                    self.hovered_option = hovered;
+                    control.redraw();
+                }
+            }
+            EventKind::MouseLeftRelease => match self.button_at(event.x, event.y) {
+                Some(0) => self.close(control, true),
+                Some(_) => self.close(control, false),
+                None => (),
+            },
+            _ => (),
+        }
+    }
+
+    fn set_theme(&mut self, theme: &Theme) {
+        self.theme = *theme;
+    }
+
+    fn recreate(&mut self, assets: &super::Assets) {
+        self.brush = assets.brush.clone();
+    }
+
+    // the `Request` from `show`
+    fn receive(&mut self, data: Box<dyn Any>) {
+        if let Ok(request) = data.downcast() {
+            self.request = Some(*request);
+        }
+    }
+
+    fn render(&mut self, context: &mut super::DrawScope) {
+        let theme = self.theme;
+        let (width, height) = self.size.get();
+        self.brush.set_color(&theme.dim);
+        context.fill_rounded_rect(
+            &self.brush,
+            [0.0, 0.0, width as f32, height as f32],
+            0.0,
+        );
+
+        let rect = self.box_rect().map(|b| b as f32);
+        self.brush.set_color(&theme.background);
+        context.fill_rounded_rect(&self.brush, rect, 2.0);
+        self.brush.set_color(&theme.border);
+        context.draw_rounded_rect(&self.brush, rect, 2.0, 2.0);
+
+        self.brush.set_color(&theme.text);
+        let [left, top, right, _] = self.box_rect();
+        let mut o = top + Self::PADDING;
+        for line in self.lines() {
+            context.draw_text(
+                line.as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[
+                    left + Self::PADDING,
+                    o,
+                    right - Self::PADDING,
+                    o + Self::LINE_HEIGHT,
+                ].map(|b| b as f32),
+            );
+            o += Self::LINE_HEIGHT;
+        }
+
+        let ok = self.request.as_ref().map_or("OK", |r| r.ok.as_str());
+        for (i, (rect, (text, color))) in self.button_rects()
+            .iter()
+            .zip([(ok, theme.error), ("Cancel", theme.text)])
+            .enumerate()
+        {
+            let rect = rect.map(|b| b as f32);
+            if Some(i) == self.hovered_option {
+                self.brush.set_color(&theme.highlight);
+                context.fill_rounded_rect(&self.brush, rect, 2.0);
+            }
+            self.brush.set_color(&theme.border);
+            context.draw_rounded_rect(&self.brush, rect, 2.0, 1.0);
+
+            self.brush.set_color(&color);
+            self.text_format.set_text_alignment(crate::dxgi::Alignment::Mid).unwrap();
+            context.draw_text(
+                text.as_ref(),
+                &self.text_format,
+                &self.brush,
+                &rect,
+            );
+            self.text_format.set_text_alignment(crate::dxgi::Alignment::Min).unwrap();
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::io;
+use std::os::windows::ffi::OsStrExt;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Storage::FileSystem::*;
+
+// changes are batched for this long before notifying
+const SETTLE: Duration = Duration::from_millis(500);
+
+// runs `notify` after changes below `path` unless `ignore` matches every changed name
+pub fn watch(
+    path: &Path,
+    ignore: fn(&str) -> bool,
+    notify: impl Fn() + Send + 'static,
+) -> io::Result<()> {
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+    let handle = unsafe {
+        CreateFileW(
+            PCWSTR(wide.as_ptr()),
+            FILE_LIST_DIRECTORY.0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+            None,
+            OPEN_EXISTING,
+            FILE_FLAG_BACKUP_SEMANTICS,
+            None,
+        )?
+    };
+
+    let handle_ = handle.0 as usize;
+    thread::spawn(move || {
+        let handle = HANDLE(handle_ as *mut _);
+        // FILE_NOTIFY_INFORMATION needs DWORD alignment
+        let mut buffer = vec![0u32; 0x4000];
+        loop {
+            let mut len = 0;
+            let res = unsafe {
+                ReadDirectoryChangesW(
+                    handle,
+                    buffer.as_mut_ptr() as _,
+                    (buffer.len() * 4) as u32,
+                    true,
+                    FILE_NOTIFY_CHANGE_FILE_NAME
+                        | FILE_NOTIFY_CHANGE_DIR_NAME
+                        | FILE_NOTIFY_CHANGE_LAST_WRITE,
+                    Some(&mut len),
+                    None,
+                    None,
+                )
+            };
+            if let Err(err) = res {
+                crate::log::log(&format!("stopped watching mods directory: {err:?}"));
+                break;
+            }
+
+            let data = unsafe {
+                std::slice::from_raw_parts(buffer.as_ptr() as *const u8, len as usize)
+            };
+            // no data means the buffer overflowed and the changes are unknown
+            if len == 0 || changed_names(data).iter().any(|name| !ignore(name)) {
+                thread::sleep(SETTLE);
+                notify();
+            }
+        }
+
+        unsafe {
+            let _ = CloseHandle(handle);
+        }
+    });
+
+    Ok(())
+}
+
+// names from a list of FILE_NOTIFY_INFORMATION records
+fn changed_names(mut data: &[u8]) -> Vec<String> {
+    let u32_at = |data: &[u8], i: usize| {
+        data.get(i..i + 4).map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+    };
+
+    let mut names = Vec::new();
+    while let (Some(next), Some(name_len)) = (u32_at(data, 0), u32_at(data, 8)) {
+        let Some(name) = data.get(12..12 + name_len as usize) else {
+            break;
+        };
+        let name: Vec<u16> = name.chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+            .collect();
+        names.push(String::from_utf16_lossy(&name));
+
+        if next == 0 || next as usize > data.len() {
+            break;
+        }
+        data = &data[next as usize..];
+    }
+    names
+}
@@ -0,0 +1,95 @@
+use std::cell::Cell;
+
+use crate::dxgi::SolidColorBrush;
+use crate::dxgi::TextFormat;
+
+use super::ControlScope;
+use super::Event;
+use super::theme::Theme;
+
+// shown by `Control` while files are dragged over the window outside the list
+pub struct DropHintWidget {
+    brush: SolidColorBrush,
+    theme: Theme,
+    text_format: TextFormat,
+
+    // window size from the last `rect`
+    size: Cell<(u32, u32)>,
+}
+
+impl DropHintWidget {
+    const TEXT: &str = "Drop mod archives on the mod list";
+    const BOX_WIDTH: u32 = 320;
+    const BOX_HEIGHT: u32 = 40;
+
+    pub fn new(
+        brush: SolidColorBrush,
+        text_format: TextFormat,
+    ) -> Self {
+        Self {
+            brush,
+            theme: Theme::DEFAULT,
+            text_format,
+
+            size: Cell::new((0, 0)),
+        }
+    }
+}
+
+impl super::Widget for DropHintWidget {
+    fn rect(&self, width: u32, height: u32) -> [u32; 4] {
+        self.size.set((width, height));
+        [0, 0, width, height]
+    }
+
+    // the list below still gets the drag
+    fn hit_test(&self, _x: u32, _y: u32) -> bool {
+        false
+    }
+
+    fn handle_event(
+        &mut self,
+        _control: &mut ControlScope,
+        _event: Event,
+    ) {
+    }
+
+    fn set_theme(&mut self, theme: &Theme) {
+        self.theme = *theme;
+    }
+
+    fn recreate(&mut self, assets: &super::Assets) {
+        self.brush = assets.brush.clone();
+    }
+
+    fn render(&mut self, context: &mut super::DrawScope) {
+        let theme = self.theme;
+        let (width, height) = self.size.get();
+        let mut dim = theme.dim;
+        dim[3] /= 2.0;
+        self.brush.set_color(&dim);
+        context.fill_rounded_rect(
+            &self.brush,
+            [0.0, 0.0, width as f32, height as f32],
+            0.0,
+        );
+
+        let left = width.saturating_sub(Self::BOX_WIDTH) / 2;
+        let top = height.saturating_sub(Self::BOX_HEIGHT) / 2;
+        let rect = [left, top, left + Self::BOX_WIDTH, top + Self::BOX_HEIGHT].map(|b| b as f32);
+        self.brush.set_color(&theme.background);
+        context.fill_rounded_rect(&self.brush, rect, 2.0);
+        self.brush.set_color(&theme.border);
+        context.draw_rounded_rect(&self.brush, rect, 2.0, 1.0);
+
+        self.brush.set_color(&theme.text);
+        self.text_format.set_text_alignment(crate::dxgi::Alignment::Mid).unwrap();
+        context.draw_text(
+            Self::TEXT.as_ref(),
+            &self.text_format,
+            &self.brush,
+            &rect,
+        );
+        self.text_format.set_text_alignment(crate::dxgi::Alignment::Min).unwrap();
+    }
+}
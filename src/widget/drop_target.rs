@@ -163,7 +163,7 @@ impl IDropTarget_Impl for DropTarget_Impl {
     fn Drop(
         &self,
         _data: Ref<'_, IDataObject>,
-        _key_state: MODIFIERKEYS_FLAGS,
+        key_state: MODIFIERKEYS_FLAGS,
         pt: &POINTL,
         effect: *mut DROPEFFECT,
     ) -> Result<()> {
@@ -173,7 +173,7 @@ impl IDropTarget_Impl for DropTarget_Impl {
                 let _ = PostMessageW(
                     Some(self.this.hwnd),
                     Control::WM_PRIV_DRAGDROP,
-                    Default::default(),
+                    WPARAM(key_state.0 as usize),
                     *(pt as *const _ as *const _),
                 );
             } else {
@@ -1,3 +1,5 @@
+use std::any::Any;
+
 use crate::dxgi::SolidColorBrush;
 use crate::dxgi::TextFormat;
 
@@ -7,23 +9,73 @@ use super::Control;
 use super::ControlScope;
 use super::Event;
 use super::EventKind;
+use super::KeyKind;
+use super::WidgetConfig;
+use super::theme::Theme;
+
+enum Item {
+    Action(&'static str, ModListEvent),
+    // opens a flyout to the right while hovered
+    Submenu(&'static str, &'static [Item]),
+}
+
+impl Item {
+    fn text(&self) -> &'static str {
+        match self {
+            Item::Action(text, _) | Item::Submenu(text, _) => text,
+        }
+    }
+}
 
-static MENU: &[&[(&str, ModListEvent)]] = &[
+static MENU: &[&[Item]] = &[
     &[
-        ("Toggle", ModListEvent::ToggleSelected),
-        ("Browse", ModListEvent::OpenSelected),
+        Item::Action("Toggle", ModListEvent::ToggleSelected),
+        Item::Action("Browse", ModListEvent::OpenSelected),
+        Item::Action("Rename", ModListEvent::RenameSelected),
+        Item::Action("Delete", ModListEvent::DeleteSelected),
+        Item::Action("Invert Selection", ModListEvent::InvertSelection),
     ],
     &[
-        ("Toggle Patch", ModListEvent::TogglePatch),
-        ("Sort Mods", ModListEvent::SortMods),
-        ("Browse Darktide", ModListEvent::BrowseDarktide),
-        ("Browse Logs", ModListEvent::BrowseLogs),
+        Item::Action("Refresh", ModListEvent::Refresh),
+        Item::Action("Enable All", ModListEvent::EnableAll),
+        Item::Action("Disable All", ModListEvent::DisableAll),
+        Item::Action("Invert Selection", ModListEvent::InvertSelection),
+        Item::Action("Toggle Patch", ModListEvent::TogglePatch),
+        Item::Action("Launch Modded", ModListEvent::LaunchModded),
+        Item::Action("Launch Vanilla Once", ModListEvent::LaunchVanilla),
+        Item::Submenu("Sort Mods", &[
+            Item::Action("Dependencies", ModListEvent::SortMods),
+            Item::Action("Stable", ModListEvent::SortModsStable),
+            Item::Action("Alphabetical", ModListEvent::SortModsAlphabetical),
+        ]),
+        Item::Action("Import Load Order", ModListEvent::ImportLoadOrder),
+        Item::Action("Export Load Order", ModListEvent::ExportLoadOrder),
+        Item::Action("Restore Load Order", ModListEvent::RestoreLoadOrder),
+        Item::Action("Undo Restore", ModListEvent::UndoRestore),
+        Item::Action("Verify Mods", ModListEvent::VerifyMods),
+        Item::Action("Browse Darktide", ModListEvent::BrowseDarktide),
+        Item::Action("Browse Logs", ModListEvent::BrowseLogs),
+        Item::Action("Settings", ModListEvent::ShowSettings),
+        Item::Action("About modtide", ModListEvent::ShowAbout),
+    ],
+    &[
+        Item::Action("Browse Folder", ModListEvent::BrowseBuiltin),
+        Item::Action("Open Project Page", ModListEvent::OpenBuiltinPage),
+        Item::Action("Show Version", ModListEvent::ShowBuiltinVersion),
     ],
 ];
 
+#[derive(Clone, Copy, Default)]
+pub struct ItemState {
+    pub checked: bool,
+    // grayed out and doesn't send its event
+    pub disabled: bool,
+}
+
 pub enum DropdownMenu {
     ModSelected = 0,
     Meta = 1,
+    Builtin = 2,
 }
 
 impl DropdownMenu {
@@ -31,6 +83,7 @@ impl DropdownMenu {
         Some(match msg {
             0 => DropdownMenu::ModSelected,
             1 => DropdownMenu::Meta,
+            2 => DropdownMenu::Builtin,
             _ => return None,
         })
     }
@@ -38,38 +91,55 @@ impl DropdownMenu {
 
 pub struct DropdownWidget {
     brush: SolidColorBrush,
+    theme: Theme,
     text_format: TextFormat,
 
+    // width of a single menu, flyouts are placed next to it
     width: u32,
+    // room for every flyout of the deepest menu
+    levels: u32,
     height: u32,
 
-    hovered_option: Option<usize>,
+    // (level, entry)
+    hovered_option: Option<(usize, usize)>,
+    // entries with their flyout open, one per level
+    open: Vec<usize>,
     menu: usize,
+    // asked from `ModListWidget` on every `Show`, also when opened from the
+    // `MODS` button
+    states: Vec<(ModListEvent, ItemState)>,
+    // opacity while opening, see `EventKind::Frame`
+    fade: f32,
 }
 
 impl DropdownWidget {
     const BORDER_SIZE: u32 = 2;
     const PADDING_Y: u32 = 2;
     const ENTRY_HEIGHT: u32 = 26;
-
-    const BACKGROUND: [f32; 4] = [0.05, 0.05, 0.05, 1.0];
-    const BORDER: [f32; 4] = [0.6, 0.6, 0.6, 1.0];
-    const TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
-    const HIGHLIGHT: [f32; 4] = [0.15, 0.15, 0.15, 1.0];
+    const FADE_STEP: f32 = 0.25;
 
     pub fn new(
         brush: SolidColorBrush,
         text_format: TextFormat,
     ) -> Self {
+        let (levels, height) = MENU.iter()
+            .map(|items| Self::extent(items, 0))
+            .fold((1, 0), |(l, h), (levels, height)| (l.max(levels), h.max(height)));
+
         Self {
             brush,
+            theme: Theme::DEFAULT,
             text_format,
 
             width: 180,
-            height: 400,
+            levels,
+            height,
 
             hovered_option: None,
+            open: Vec::new(),
             menu: 0,
+            states: Vec::new(),
+            fade: 1.0,
         }
     }
 
@@ -83,24 +153,141 @@ impl DropdownWidget {
         control.hide_widget(Control::DROPDOWN_WIDGET);
     }
 
-    fn menu(&self) -> &[(&str, ModListEvent)] {
-        MENU.get(self.menu).cloned().unwrap_or(&[])
+    fn box_height(len: usize) -> u32 {
+        (Self::BORDER_SIZE + Self::PADDING_Y) * 2 + Self::ENTRY_HEIGHT * len as u32
+    }
+
+    // levels and bottom edge with every flyout of `items` open
+    fn extent(items: &[Item], top: u32) -> (u32, u32) {
+        let mut levels = 1;
+        let mut bottom = top + Self::box_height(items.len());
+        for (i, item) in items.iter().enumerate() {
+            if let Item::Submenu(_, sub) = item {
+                let (l, b) = Self::extent(sub, top + Self::ENTRY_HEIGHT * i as u32);
+                levels = levels.max(l + 1);
+                bottom = bottom.max(b);
+            }
+        }
+        (levels, bottom)
+    }
+
+    // top edge and entries of the shown menu and each open flyout
+    fn levels(&self) -> Vec<(u32, &'static [Item])> {
+        let mut items = MENU.get(self.menu).cloned().unwrap_or(&[]);
+        let mut out = vec![(0, items)];
+        let mut top = 0;
+        for &i in &self.open {
+            let Some(Item::Submenu(_, sub)) = items.get(i) else {
+                break;
+            };
+            top += Self::ENTRY_HEIGHT * i as u32;
+            items = sub;
+            out.push((top, items));
+        }
+        out
+    }
+
+    fn level_at(&self, x: i32, y: i32) -> Option<(usize, u32, &'static [Item])> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+        let level = x as u32 / self.width;
+        let (top, items) = self.levels().get(level as usize).copied()?;
+        let y = y as u32;
+        (y >= top && y < top + Self::box_height(items.len())).then_some((level as usize, top, items))
+    }
+
+    fn option_at(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+        let (level, top, items) = self.level_at(x, y)?;
+        let offset = (y as u32 - top).checked_sub(Self::BORDER_SIZE + Self::PADDING_Y)?;
+        let i = (offset / Self::ENTRY_HEIGHT) as usize;
+        (i < items.len()).then_some((level, i))
+    }
+
+    fn state(&self, item: &Item) -> ItemState {
+        let Item::Action(_, event) = item else {
+            return ItemState::default();
+        };
+        self.states.iter()
+            .find(|(e, _)| e == event)
+            .map_or(ItemState::default(), |(_, state)| *state)
+    }
+
+    fn activate(&mut self, control: &mut ControlScope, level: usize, i: usize) {
+        let item = &self.levels()[level].1[i];
+        if self.state(item).disabled {
+            return;
+        }
+        match item {
+            Item::Action(_, event) => {
+                ModListWidget::send(control, event.clone());
+                DropdownWidget::hide(control);
+            }
+            Item::Submenu(..) => {
+                self.open.truncate(level);
+                self.open.push(i);
+                self.hovered_option = Some((level + 1, 0));
+            }
+        }
+    }
+
+    // `ESC` already closes the menu by breaking the mouse capture
+    fn key_down(&mut self, control: &mut ControlScope, key: KeyKind) {
+        let (level, i) = match self.hovered_option {
+            Some(option) => option,
+            // nothing hovered yet so the first key picks an edge of the menu
+            None => (self.open.len(), usize::MAX),
+        };
+        let len = self.levels()[level].1.len();
+        if len == 0 {
+            return;
+        }
+        match key {
+            KeyKind::Up if i >= len => self.hovered_option = Some((level, len - 1)),
+            KeyKind::Up => self.hovered_option = Some((level, (i + len - 1) % len)),
+            KeyKind::Down if i >= len => self.hovered_option = Some((level, 0)),
+            KeyKind::Down => self.hovered_option = Some((level, (i + 1) % len)),
+            KeyKind::Enter
+            | KeyKind::Right if i < len => self.activate(control, level, i),
+            KeyKind::Left if level > 0 => {
+                self.hovered_option = Some((level - 1, self.open[level - 1]));
+                self.open.truncate(level - 1);
+            }
+            _ => return,
+        }
+        // moving away from an entry closes its flyout
+        if let Some((level, _)) = self.hovered_option {
+            self.open.truncate(level);
+        }
+        control.redraw();
     }
 }
 
 impl super::Widget for DropdownWidget {
+    fn config(&self) -> WidgetConfig {
+        WidgetConfig {
+            z_index: WidgetConfig::POPUP_LAYER,
+            ..Default::default()
+        }
+    }
+
     fn rect(&self, _width: u32, _height: u32) -> [u32; 4] {
         [
             0,
             0,
-            self.width,
+            self.width * self.levels,
             self.height,
         ]
     }
 
-    fn hit_test(&self, _x: u32, y: u32) -> bool {
-        let padding = (Self::BORDER_SIZE + Self::PADDING_Y) * 2;
-        y < padding * 2 + Self::ENTRY_HEIGHT * self.menu().len() as u32
+    fn receive(&mut self, data: Box<dyn Any>) {
+        if let Ok(states) = data.downcast() {
+            self.states = *states;
+        }
+    }
+
+    fn hit_test(&self, x: u32, y: u32) -> bool {
+        self.level_at(x as i32, y as i32).is_some()
     }
 
     fn handle_event(
@@ -110,15 +297,30 @@ impl super::Widget for DropdownWidget {
     ) {
         'control: {
             match event.kind {
-                EventKind::Show => control.capture_mouse(),
+                EventKind::Show => {
+                    self.states.clear();
+                    ModListWidget::send(control, ModListEvent::MenuStates);
+                    self.fade = 0.0;
+                    control.request_animation(true);
+                    control.capture_mouse();
+                }
+                EventKind::Frame => {
+                    self.fade = (self.fade + Self::FADE_STEP).min(1.0);
+                    if self.fade >= 1.0 {
+                        control.request_animation(false);
+                    }
+                    control.redraw();
+                }
                 EventKind::Hide => {
                     self.hovered_option = None;
+                    self.open.clear();
                     control.release_mouse();
                 }
                 EventKind::LostFocus => control.hide_widget(Control::DROPDOWN_WIDGET),
                 EventKind::Custom(msg) => {
                     if let Some(menu) = DropdownMenu::from_u32(msg) {
                         self.menu = menu as usize;
+                        self.open.clear();
                     }
                 }
                 _ => break 'control,
@@ -126,112 +328,140 @@ impl super::Widget for DropdownWidget {
             return;
         }
 
-        let x = event.x;
-        let y = event.y;
-        let menu = self.menu();
-        let padding = (Self::BORDER_SIZE + Self::PADDING_Y) * 2;
-        let is_inside = y >= 0 && (y as u32) < padding + Self::ENTRY_HEIGHT * menu.len() as u32
-            && x >= 0 && x < self.width as i32;
-
+        let option = self.option_at(event.x, event.y);
         match event.kind {
-            EventKind::MouseMove(_) if !is_inside => {
-                if self.hovered_option.is_some() {
-                    self.hovered_option = None;
-                    control.redraw();
-                }
-            }
             EventKind::MouseMove(_) => {
-                let offset = y - Self::BORDER_SIZE as i32;
-                let opt = offset / Self::ENTRY_HEIGHT as i32;
-
-                let new_opt = if opt < 0 || opt >= menu.len() as i32 {
-                    None
-                } else {
-                    Some(opt as usize)
-                };
+                let mut changed = self.hovered_option != option;
+                self.hovered_option = option;
+
+                // hovering an entry closes flyouts of other entries on its level
+                if let Some((level, i)) = option {
+                    let items = self.levels()[level].1;
+                    let mut open = self.open[..level.min(self.open.len())].to_vec();
+                    if let Item::Submenu(..) = items[i] {
+                        open.push(i);
+                    }
+                    changed |= open != self.open;
+                    self.open = open;
+                }
 
-                if self.hovered_option != new_opt {
-                    self.hovered_option = new_opt;
+                if changed {
                     control.redraw();
                 }
             }
 
             EventKind::MouseLeftRelease
-            | EventKind::MouseRightRelease if is_inside => {
-                let offset = y as u32 - Self::BORDER_SIZE;
-                let opt = (offset / Self::ENTRY_HEIGHT) as usize;
-                if let Some((_, event)) = menu.get(opt) {
-                    ModListWidget::send(control, event.clone());
+            | EventKind::MouseRightRelease => if let Some((level, i)) = option {
+                // submenus are already open from hovering
+                if let Item::Action(..) = self.levels()[level].1[i] {
+                    self.activate(control, level, i);
                 }
-                DropdownWidget::hide(control);
-            }
+            },
+
+            EventKind::KeyDown(key) => self.key_down(control, key),
 
             _ => (),
         }
     }
 
-    fn render(&mut self, context: &mut super::DrawScope) {
-        let menu = self.menu();
+    fn set_theme(&mut self, theme: &Theme) {
+        self.theme = *theme;
+    }
 
+    fn recreate(&mut self, assets: &super::Assets) {
+        self.brush = assets.brush.clone();
+    }
+
+    fn opacity(&self) -> f32 {
+        self.fade
+    }
+
+    fn render(&mut self, context: &mut super::DrawScope) {
+        let theme = self.theme;
         let padding = (Self::BORDER_SIZE + Self::PADDING_Y) as f32;
         let border = Self::BORDER_SIZE as f32 / 2.0;
-        let rect = [
-            border,
-            border,
-            self.width as f32 - border,
-            (menu.len() * Self::ENTRY_HEIGHT as usize) as f32 + padding * 2.0 - border,
-        ];
-        let radius = 2.0;
-
-        self.brush.set_color(&Self::BACKGROUND);
-        context.fill_rounded_rect(
-            &self.brush,
-            rect,
-            radius,
-        );
-
-        self.brush.set_color(&Self::BORDER);
-        context.draw_rounded_rect(
-            &self.brush,
-            rect,
-            radius,
-            2.0,
-        );
-
-        let mut o = padding;
-        for (i, (text, _)) in menu.iter().enumerate() {
-            let rectf = [
-                (Self::BORDER_SIZE + 4) as f32,
-                o,
-                (self.width - Self::BORDER_SIZE - 4) as f32,
-                o + Self::ENTRY_HEIGHT as f32,
+        for (level, (top, items)) in self.levels().into_iter().enumerate() {
+            let left = (self.width * level as u32) as f32;
+            let top = top as f32;
+            let rect = [
+                left + border,
+                top + border,
+                left + self.width as f32 - border,
+                top + (items.len() * Self::ENTRY_HEIGHT as usize) as f32 + padding * 2.0 - border,
             ];
+            let radius = 2.0;
 
-            if Some(i) == self.hovered_option {
-                self.brush.set_color(&Self::HIGHLIGHT);
+            self.brush.set_color(&theme.background);
+            context.fill_rounded_rect(
+                &self.brush,
+                rect,
+                radius,
+            );
 
-                let mid = o + Self::ENTRY_HEIGHT as f32 / 2.0;
-                let from = [
-                    4.0,
-                    mid,
-                ];
-                let to = [
-                    (self.width - 4) as f32,
-                    mid,
+            self.brush.set_color(&theme.border);
+            context.draw_rounded_rect(
+                &self.brush,
+                rect,
+                radius,
+                2.0,
+            );
+
+            let mut o = top + padding;
+            for (i, item) in items.iter().enumerate() {
+                let rectf = [
+                    left + (Self::BORDER_SIZE + 4) as f32,
+                    o,
+                    left + (self.width - Self::BORDER_SIZE - 4) as f32,
+                    o + Self::ENTRY_HEIGHT as f32,
                 ];
-                context.draw_line(from, to, &self.brush, (Self::ENTRY_HEIGHT - 4) as f32);
-            }
 
-            self.brush.set_color(&Self::TEXT_COLOR);
+                if Some((level, i)) == self.hovered_option || self.open.get(level) == Some(&i) {
+                    self.brush.set_color(&theme.highlight);
+
+                    let mid = o + Self::ENTRY_HEIGHT as f32 / 2.0;
+                    let from = [
+                        left + 4.0,
+                        mid,
+                    ];
+                    let to = [
+                        left + (self.width - 4) as f32,
+                        mid,
+                    ];
+                    context.draw_line(from, to, &self.brush, (Self::ENTRY_HEIGHT - 4) as f32);
+                }
 
-            context.draw_text(
-                text.as_ref(),
-                &self.text_format,
-                &self.brush,
-                &rectf,
-            );
+                let state = self.state(item);
+                if state.disabled {
+                    self.brush.set_color(&theme.disabled);
+                } else {
+                    self.brush.set_color(&theme.text);
+                }
 
-            o += Self::ENTRY_HEIGHT as f32;
+                context.draw_text(
+                    item.text().as_ref(),
+                    &self.text_format,
+                    &self.brush,
+                    &rectf,
+                );
+
+                let mark = match item {
+                    Item::Submenu(..) => Some("\u{25b6}"),
+                    Item::Action(..) if state.checked => Some("\u{2713}"),
+                    Item::Action(..) => None,
+                };
+                if let Some(mark) = mark {
+                    self.text_format.set_text_alignment(crate::dxgi::Alignment::Max).unwrap();
+                    context.draw_text(
+                        mark.as_ref(),
+                        &self.text_format,
+                        &self.brush,
+                        &rectf,
+                    );
+                    self.text_format.set_text_alignment(crate::dxgi::Alignment::Min).unwrap();
+                }
+
+                o += Self::ENTRY_HEIGHT as f32;
+            }
         }
     }
 }
@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use windows::core::Interface;
+use windows::core::Result;
+use windows::core::w;
+use windows::Win32::System::Com::*;
+use windows::Win32::UI::Shell::*;
+use windows::Win32::UI::Shell::Common::COMDLG_FILTERSPEC;
+
+// blocks until a `.txt` file is picked, so call it from a worker thread
+pub fn pick_text_file(save: bool) -> Result<Option<PathBuf>> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+        let dialog: IFileDialog = if save {
+            let dialog: IFileSaveDialog = CoCreateInstance(&FileSaveDialog, None, CLSCTX_INPROC_SERVER)?;
+            dialog.SetFileName(w!("mod_load_order.txt"))?;
+            dialog.cast()?
+        } else {
+            let dialog: IFileOpenDialog = CoCreateInstance(&FileOpenDialog, None, CLSCTX_INPROC_SERVER)?;
+            dialog.cast()?
+        };
+        dialog.SetFileTypes(&[COMDLG_FILTERSPEC {
+            pszName: w!("Load order"),
+            pszSpec: w!("*.txt"),
+        }])?;
+        dialog.SetDefaultExtension(w!("txt"))?;
+
+        // also fails when the dialog is cancelled
+        if dialog.Show(None).is_err() {
+            return Ok(None);
+        }
+
+        let name = dialog.GetResult()?.GetDisplayName(SIGDN_FILESYSPATH)?;
+        let path = name.to_string();
+        CoTaskMemFree(Some(name.0 as _));
+        Ok(Some(PathBuf::from(path?)))
+    }
+}
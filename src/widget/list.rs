@@ -1,48 +1,164 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fmt::Write;
 use std::path::Path;
 use std::path::PathBuf;
 use std::io;
+use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
 
 use windows::Win32::Graphics::Direct2D::ID2D1Bitmap;
 use crate::dxgi::SolidColorBrush;
 use crate::dxgi::TextFormat;
 
+use crate::config::Config;
+use crate::manifest::Change;
+use crate::mod_engine::FileConflict;
+use crate::mod_engine::ModFiles;
+use crate::mod_engine::compare_versions;
 use crate::mod_engine::ModEngine;
 use crate::mod_engine::ModState;
+use crate::mod_engine::SortMode;
+use crate::mod_engine::SortPreview;
+use crate::mod_engine::VersionMismatch;
 use crate::archive::Archive;
 use crate::archive::ArchiveList;
 use crate::archive::ArchiveView;
+use crate::archive::CopyPolicy;
 use crate::archive::Prefix;
+use crate::archive::TreeRow;
+use crate::package::Package;
 use super::Control;
 use super::WidgetConfig;
 use super::button;
 use super::button::ButtonWidget;
+use super::confirm::ConfirmWidget;
+use super::theme::Theme;
 use super::dropdown::DropdownMenu;
 use super::dropdown::DropdownWidget;
+use super::dropdown::ItemState;
 use super::Event;
 use super::EventKind;
 use super::KeyKind;
 
-fn check_archive(_path: &Path, list: &ArchiveList) -> io::Result<Prefix> {
+fn check_archive(_path: &Path, list: &ArchiveList, package: Option<&Package>) -> io::Result<Prefix> {
+    // a packaged manifest says where everything goes
+    if let Some(package) = package {
+        return Ok(package.prefix());
+    }
+
     if list.list("mods").is_some()
         || list.list("binaries").is_some()
     {
         return Ok(Prefix::None);
+    }
+
+    // find the shallowest `mods/`, `binaries/` or `<NAME>/<NAME>.mod` and strip
+    // any wrapper folders above it
+    let depth = |wrapper: &str| if wrapper.is_empty() {
+        0
     } else {
-        let mut parent = None;
-        for (path, _ty, depth) in list.iter() {
-            if depth == 0 {
-                parent = Some(path);
-            } else if depth == 1
-                && let Some(name) = path.strip_suffix(".mod")
-                && Some(name) == parent
-            {
-                return Ok(Prefix::Mods);
+        wrapper.split('/').count()
+    };
+    let mut found: Option<(&str, bool)> = None;
+    for (path, ty) in list.walk("") {
+        let (parent, name) = path.rsplit_once('/').unwrap_or(("", path));
+        let candidate = if ty.is_dir() && (name == "mods" || name == "binaries") {
+            (parent, false)
+        } else if ty.is_file()
+            && let Some(stem) = name.strip_suffix(".mod")
+            && let (wrapper, dir) = parent.rsplit_once('/').unwrap_or(("", parent))
+            && dir == stem
+        {
+            (wrapper, true)
+        } else {
+            continue;
+        };
+
+        let is_better = match found {
+            None => true,
+            Some((wrapper, mods)) => {
+                let ord = depth(candidate.0).cmp(&depth(wrapper));
+                ord.is_lt() || (ord.is_eq() && mods && !candidate.1)
             }
+        };
+        if is_better {
+            found = Some(candidate);
+        }
+    }
+
+    match found {
+        Some(("", false)) => Ok(Prefix::None),
+        Some(("", true)) => Ok(Prefix::Mods),
+        Some((wrapper, mods)) => Ok(Prefix::Strip {
+            wrapper: wrapper.to_string(),
+            mods,
+        }),
+        None => Err(io::Error::other("unknown layout from dragdrop archive")),
+    }
+}
+
+// writes to a temp file next to `path` and renames it over the original
+fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+
+    let res = std::fs::File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(data)?;
+            file.sync_all()
+        })
+        .and_then(|()| std::fs::rename(&temp, path));
+    if res.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    res
+}
+
+fn format_size(out: &mut String, size: u64) {
+    const UNITS: &[&str] = &["KB", "MB", "GB"];
+
+    if size < 1024 {
+        let _ = write!(out, "{size} B");
+        return;
+    }
+
+    let mut size = size as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
         }
+        size /= 1024.0;
+        unit = next;
     }
-    Err(io::Error::other("unknown layout from dragdrop archive"))
+    let _ = write!(out, "{size:.1} {unit}");
+}
+
+// `YYYY-MM-DD` in UTC
+fn format_date(out: &mut String, time: SystemTime) {
+    let Ok(since) = time.duration_since(SystemTime::UNIX_EPOCH) else {
+        return;
+    };
+
+    // civil_from_days from http://howardhinnant.github.io/date_algorithms.html
+    let z = (since.as_secs() / 86400) as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = era * 400 + yoe + (month <= 2) as i64;
+    let _ = write!(out, "{year}-{month:02}-{day:02}");
 }
 
 struct Mailbox<T: Send>(Mutex<(u64, Option<T>)>);
@@ -73,8 +189,23 @@ impl<T: Send> Mailbox<T> {
     }
 }
 
+// (changed mods, mods without a manifest)
+type VerifyResult = (Vec<(String, Vec<Change>)>, Vec<String>);
+
+static VERIFY_MAILBOX: Mailbox<VerifyResult> = Mailbox::new();
+
+// files of each installed mod for conflicts, tagged by `ModListWidget::scan_tag`
+static FILES_MAILBOX: Mailbox<ModFiles> = Mailbox::new();
+
+// (export, path) picked from the file dialog
+static LOAD_ORDER_MAILBOX: Mailbox<(bool, PathBuf)> = Mailbox::new();
+
+// (copied, total) bytes of the running install, tagged like `DragDrop::mailbox`
+static COPY_PROGRESS_MAILBOX: Mailbox<(u64, u64)> = Mailbox::new();
+
 enum DragDropEvent {
     Error(String),
+    Preview(ArchiveList),
     List(ArchiveView),
     Copy,
 }
@@ -84,6 +215,9 @@ enum DragDropState {
     None,
     Listing,
     Dragging,
+    Selecting,
+    // waiting on the overwrite confirmation
+    Confirming,
     Copying,
     Copied,
 }
@@ -94,9 +228,22 @@ struct DragDrop {
     tag: u64,
     mailbox: &'static Mailbox<DragDropEvent>,
     archive: Option<Archive>,
+    preview: Option<ArchiveList>,
     view: Option<ArchiveView>,
-    complete: Option<Box<dyn FnOnce() + Send + Sync>>,
+    conflicts: Vec<String>,
+    skipped: Vec<String>,
+    policy: CopyPolicy,
+    selection: Vec<(String, bool)>,
+    // folders expanded or collapsed from their default in the preview tree,
+    // only top level folders start expanded
+    toggled: HashSet<String>,
+    // rows of the preview tree scrolled past
+    tree_scroll: usize,
+    progress: Option<(u64, u64)>,
+    notify: Option<Arc<dyn Fn() + Send + Sync>>,
     error: Option<String>,
+    // bytes, from `Config::extract_limit`
+    extract_limit: u64,
 }
 
 impl DragDrop {
@@ -115,9 +262,18 @@ impl DragDrop {
             tag,
             mailbox: &DRAG_DROP_MAILBOX,
             archive: None,
+            preview: None,
             view: None,
-            complete: None,
+            conflicts: Vec::new(),
+            skipped: Vec::new(),
+            policy: CopyPolicy::Overwrite,
+            selection: Vec::new(),
+            toggled: HashSet::new(),
+            tree_scroll: 0,
+            progress: None,
+            notify: None,
             error: None,
+            extract_limit: Config::default().extract_limit(),
         }
     }
 
@@ -129,11 +285,34 @@ impl DragDrop {
         self.mailbox.clear(self.tag);
         self.state = DragDropState::None;
         self.archive = None;
+        self.preview = None;
         self.view = None;
+        self.conflicts.clear();
+        // a new drop must not inherit mods skipped from the last one
+        self.skipped.clear();
+        self.selection.clear();
+        self.toggled.clear();
+        self.tree_scroll = 0;
+        self.progress = None;
         redraw
     }
 
     fn poll(&mut self) -> bool {
+        let progress = self.poll_progress();
+        self.poll_event() || progress
+    }
+
+    fn poll_progress(&mut self) -> bool {
+        match COPY_PROGRESS_MAILBOX.recv() {
+            Some((tag, progress)) if tag == self.tag && self.state == DragDropState::Copying => {
+                self.progress = Some(progress);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn poll_event(&mut self) -> bool {
         let mailbox = self.mailbox;
         if let Some((tag, event)) = mailbox.recv() {
             if tag != self.tag {
@@ -146,7 +325,13 @@ impl DragDrop {
                     self.error = Some(err);
                     DragDropState::None
                 }
+                DragDropEvent::Preview(list) => {
+                    self.preview = Some(list);
+                    return true;
+                }
                 DragDropEvent::List(view) => {
+                    self.preview = None;
+                    self.conflicts = self.find_conflicts(&view);
                     self.view = Some(view);
                     if self.state == DragDropState::Copying {
                         self.state
@@ -194,22 +379,53 @@ impl DragDrop {
         }
     }
 
+    fn mod_names(view: &ArchiveView) -> Vec<String> {
+        let mut names = Vec::new();
+        if let Some(mods) = view.list().get("mods") {
+            for (name, ty, depth, _size) in mods.iter() {
+                if depth == 0 && ty.is_dir() {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names
+    }
+
+    // installed mods that would be overwritten by the dropped archive
+    fn find_conflicts(&self, view: &ArchiveView) -> Vec<String> {
+        let mods_path = self.root.join("mods");
+        let mut conflicts = Self::mod_names(view);
+        conflicts.retain(|name| mods_path.join(name).exists());
+        conflicts
+    }
+
     fn copy(&mut self) {
         if self.view.is_none() {
             self.state = DragDropState::None;
-        } else if self.is_dragging()
+        } else if (self.is_dragging() || self.state == DragDropState::Selecting)
             && let Some(view) = self.view.as_mut()
         {
-            let complete = self.complete.take().unwrap();
+            let notify = self.notify.take().unwrap();
+            let notify_progress = notify.clone();
             let tag = self.tag;
             let mailbox = self.mailbox;
-            view.copy(&self.root, move |count| {
+            let exclude = self.skipped.iter()
+                .map(|name| format!("mods/{name}"))
+                .collect();
+            COPY_PROGRESS_MAILBOX.clear(tag);
+            view.copy(&self.root, exclude, self.policy, move |copied, total| {
+                COPY_PROGRESS_MAILBOX.send(tag, (copied, total));
+                notify_progress();
+            }, move |count| {
                 match count {
                     Ok(_count) => mailbox.send(tag, DragDropEvent::Copy),
                     Err(err) => mailbox.send(tag, DragDropEvent::Error(Self::format_error(&err))),
                 }
-                complete();
+                notify();
             });
+
+            self.progress = None;
+            self.state = DragDropState::Copying;
         }
     }
 
@@ -220,7 +436,7 @@ impl DragDrop {
     fn mouse_enter(
         &mut self,
         files: &[PathBuf],
-        complete: impl FnOnce() + Send + Sync + 'static,
+        notify: impl Fn() + Send + Sync + 'static,
     ) {
         self.clear();
         // see DragDrop::mouse_leave
@@ -229,17 +445,22 @@ impl DragDrop {
         self.tag += 1;
         self.mailbox.clear(self.tag);
 
-        match Archive::new(files, check_archive) {
+        match Archive::new(files, self.extract_limit, check_archive) {
             Ok(archive) => {
                 let tag = self.tag;
                 let mailbox = self.mailbox;
-                archive.view(move |view| {
+                let notify = Arc::new(notify);
+                let notify_preview = notify.clone();
+                archive.view(move |list| {
+                    mailbox.send(tag, DragDropEvent::Preview(list));
+                    notify_preview();
+                }, move |view| {
                     match view {
                         Ok(view) => mailbox.send(tag, DragDropEvent::List(view)),
                         Err(err) if err.kind() == io::ErrorKind::WouldBlock => return,
                         Err(err) => mailbox.send(tag, DragDropEvent::Error(Self::format_error(&err))),
                     }
-                    complete();
+                    notify();
                 });
                 self.state = DragDropState::Listing;
                 self.archive = Some(archive);
@@ -262,16 +483,130 @@ impl DragDrop {
     //    }
     //}
 
+    // SHIFT skips installed mods, CTRL keeps existing files and CTRL+SHIFT only
+    // replaces files with newer versions
+    //
+    // replacing installed mods waits in `Confirming` until `start` is called
     fn drag_drop(
         &mut self,
-        complete: impl FnOnce() + Send + Sync + 'static,
+        ctrl: bool,
+        shift: bool,
+        notify: impl Fn() + Send + Sync + 'static,
     ) {
-        self.complete = Some(Box::new(complete));
+        self.skipped.clear();
+        self.policy = match (ctrl, shift) {
+            (true, true) => CopyPolicy::MergeNewOnly,
+            (true, false) => CopyPolicy::SkipExisting,
+            (false, _) => CopyPolicy::Overwrite,
+        };
+        if shift && !ctrl {
+            self.skipped.append(&mut self.conflicts);
+        }
+        self.notify = Some(Arc::new(notify));
+
+        if self.is_dragging() && self.policy == CopyPolicy::Overwrite && !self.conflicts.is_empty() {
+            self.state = DragDropState::Confirming;
+        } else {
+            self.start();
+        }
+    }
+
+    fn start(&mut self) {
+        if self.state == DragDropState::Confirming {
+            self.state = DragDropState::Dragging;
+        }
+
+        // let the user pick which mods to install from archives with several mods
+        if self.is_dragging()
+            && let Some(view) = &self.view
+            && let names = Self::mod_names(view)
+            && names.len() > 1
+        {
+            self.selection = names.into_iter()
+                .map(|name| {
+                    let checked = !self.skipped.contains(&name);
+                    (name, checked)
+                })
+                .collect();
+            self.state = DragDropState::Selecting;
+        } else {
+            self.copy();
+        }
+    }
+
+    fn is_expanded(&self, path: &str) -> bool {
+        let top_level = !path.contains('/');
+        top_level != self.toggled.contains(path)
+    }
+
+    fn toggle_folder(&mut self, path: &str) {
+        if !self.toggled.remove(path) {
+            self.toggled.insert(path.to_string());
+        }
+    }
+
+    fn tree<'a>(&self, list: &'a ArchiveList) -> Vec<TreeRow<'a>> {
+        list.tree(|path| self.is_expanded(path))
+    }
+
+    // the mod folders being selected from and whatever is expanded below them
+    fn selection_tree(&self) -> Vec<TreeRow<'_>> {
+        let Some(view) = &self.view else {
+            return Vec::new();
+        };
+        let mut rows = self.tree(view.list());
+        rows.retain(|row| row.depth > 0 && row.path.starts_with("mods/"));
+        rows
+    }
+
+    // bytes written when installing the checked mods
+    fn selection_size(&self) -> u64 {
+        let Some(view) = &self.view else {
+            return 0;
+        };
+        let list = view.list();
+        let skipped: u64 = self.selection.iter()
+            .filter(|(_, checked)| !checked)
+            .filter_map(|(name, _)| list.get(&format!("mods/{name}")))
+            .map(|mods| mods.size())
+            .sum();
+        list.size() - skipped
+    }
+
+    fn scroll_tree(&mut self, delta: i32, rows: usize) -> bool {
+        let scroll = match delta < 0 {
+            true => (self.tree_scroll + 1).min(rows.saturating_sub(1)),
+            false => self.tree_scroll.saturating_sub(1),
+        };
+        let changed = scroll != self.tree_scroll;
+        self.tree_scroll = scroll;
+        changed
+    }
+
+    fn toggle_selection(&mut self, row: usize) -> bool {
+        if let Some((_, checked)) = self.selection.get_mut(row) {
+            *checked = !*checked;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn install_selection(&mut self) {
+        if self.selection.iter().all(|(_, checked)| !checked) {
+            self.clear();
+            return;
+        }
+
+        self.skipped = self.selection.drain(..)
+            .filter(|(_, checked)| !checked)
+            .map(|(name, _)| name)
+            .collect();
         self.copy();
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum ModListEvent {
     ToggleSelected = 0,
     OpenSelected = 1,
@@ -280,6 +615,41 @@ pub enum ModListEvent {
     TogglePatch  = 4,
     BrowseDarktide = 5,
     BrowseLogs = 6,
+    SortModsStable = 7,
+    ImportLoadOrder = 8,
+    ExportLoadOrder = 9,
+    LoadOrderFile = 10,
+    VerifyMods = 11,
+    VerifyDone = 12,
+    ModsChanged = 13,
+    Refresh = 14,
+    RenameSelected = 15,
+    EnableAll = 16,
+    DisableAll = 17,
+    InvertSelection = 18,
+    SortModsAlphabetical = 19,
+    RestoreLoadOrder = 20,
+    DeleteSelected = 21,
+    DeleteConfirmed = 22,
+    RestoreConfirmed = 23,
+    TogglePatchConfirmed = 24,
+    InstallConfirmed = 25,
+    InstallCanceled = 26,
+    ShowSettings = 27,
+    ConfigChanged = 28,
+    RetryMount = 29,
+    RetrySave = 30,
+    BrowseBuiltin = 31,
+    OpenBuiltinPage = 32,
+    ShowBuiltinVersion = 33,
+    ShowAbout = 34,
+    CopyAbout = 35,
+    LaunchModded = 36,
+    LaunchVanilla = 37,
+    FilesScanned = 38,
+    UndoRestore = 39,
+    MenuStates = 40,
+    LaunchVanillaConfirmed = 41,
 }
 
 impl ModListEvent {
@@ -292,11 +662,69 @@ impl ModListEvent {
             4 => ModListEvent::TogglePatch,
             5 => ModListEvent::BrowseDarktide,
             6 => ModListEvent::BrowseLogs,
+            7 => ModListEvent::SortModsStable,
+            8 => ModListEvent::ImportLoadOrder,
+            9 => ModListEvent::ExportLoadOrder,
+            10 => ModListEvent::LoadOrderFile,
+            11 => ModListEvent::VerifyMods,
+            12 => ModListEvent::VerifyDone,
+            13 => ModListEvent::ModsChanged,
+            14 => ModListEvent::Refresh,
+            15 => ModListEvent::RenameSelected,
+            16 => ModListEvent::EnableAll,
+            17 => ModListEvent::DisableAll,
+            18 => ModListEvent::InvertSelection,
+            19 => ModListEvent::SortModsAlphabetical,
+            20 => ModListEvent::RestoreLoadOrder,
+            21 => ModListEvent::DeleteSelected,
+            22 => ModListEvent::DeleteConfirmed,
+            23 => ModListEvent::RestoreConfirmed,
+            24 => ModListEvent::TogglePatchConfirmed,
+            25 => ModListEvent::InstallConfirmed,
+            26 => ModListEvent::InstallCanceled,
+            27 => ModListEvent::ShowSettings,
+            28 => ModListEvent::ConfigChanged,
+            29 => ModListEvent::RetryMount,
+            30 => ModListEvent::RetrySave,
+            31 => ModListEvent::BrowseBuiltin,
+            32 => ModListEvent::OpenBuiltinPage,
+            33 => ModListEvent::ShowBuiltinVersion,
+            34 => ModListEvent::ShowAbout,
+            35 => ModListEvent::CopyAbout,
+            36 => ModListEvent::LaunchModded,
+            37 => ModListEvent::LaunchVanilla,
+            38 => ModListEvent::FilesScanned,
+            39 => ModListEvent::UndoRestore,
+            40 => ModListEvent::MenuStates,
+            41 => ModListEvent::LaunchVanillaConfirmed,
             _ => return None,
         })
     }
 }
 
+// result of a mod list action shown in the preview pane, install errors
+// stay with `DragDrop`
+struct Notice {
+    text: String,
+    error: bool,
+}
+
+impl Notice {
+    fn info(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            error: false,
+        }
+    }
+
+    fn error(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            error: true,
+        }
+    }
+}
+
 pub struct ModListWidget {
     background: ID2D1Bitmap,
     brush: SolidColorBrush,
@@ -305,20 +733,79 @@ pub struct ModListWidget {
     root: PathBuf,
     mods_path: PathBuf,
     lorder: ModEngine,
-    builtins: Vec<&'static str>,
+    version_errors: Vec<VersionMismatch>,
+    file_conflicts: Vec<FileConflict>,
+    // mods with files changed since install, from the last verify
+    integrity: Vec<(String, Vec<Change>)>,
+    sort_preview: Option<SortPreview>,
+    // disabled mods and the enabled mods still requiring them
+    disable_prompt: Option<(Vec<String>, Vec<String>)>,
+    // (folder, name) of `_NAME` folders, offered once per session
+    normalize_prompt: Option<Vec<(String, String)>>,
+    // names of the mods to delete once confirmed
+    pending_delete: Vec<String>,
+    // mount or save failure waiting to be shown with `Retry`, the event
+    // is what retries it
+    pending_error: Option<(ModListEvent, String)>,
+    notice: Option<Notice>,
+    // posts events from background threads, set on the first event
+    dispatcher: Option<Arc<dyn Fn(u32) + Send + Sync>>,
+    scan_tag: u64,
+    normalize_offered: bool,
+    // name and version parsed from the loader or framework files
+    builtins: Vec<(&'static str, Option<String>)>,
     is_patched: bool,
+    // last `is_patched` delivered to the button
+    patched_sent: Option<bool>,
+    // (mod index, new name) while a mod name is being edited
+    rename: Option<(usize, String)>,
+    // frame of the animation shown while listing a dropped archive
+    spinner: usize,
+    // only mods containing `filter` are listed, typed while `filter_focus` is set
+    filter: String,
+    filter_focus: bool,
+    // typed prefix for jumping to a mod and when the last character was typed
+    jump: (String, Instant),
+    // column the list is sorted by and whether it's descending, only changes
+    // the view and never the load order
+    view_sort: Option<(Column, bool)>,
+    // builtins, group headers and mods as listed, see `update_rows`
+    rows: Vec<Entry>,
+    // manifest times by mod name with the formatted date, read on mount
+    installed: HashMap<String, (SystemTime, String)>,
+    // `mods/` is watched for changes made outside the launcher once shown
+    watching: bool,
+    config: Config,
+    theme: Theme,
+    // reloaded with the config and not yet handed to the other widgets
+    theme_changed: bool,
 
     scroll: i32,
+    // where smooth scrolling is heading, see `EventKind::Frame`
+    scroll_target: Option<i32>,
+    // hover highlight opacity, fades in when a new row is hovered
+    hover_fade: f32,
     item_height: i32,
     active_mod: usize,
     clicked_mod: Option<usize>,
+    // anchor in list content coordinates and the selection kept with CTRL
+    // or SHIFT while dragging a box over empty space
+    box_select: Option<((i32, i32), Vec<usize>)>,
+    // keyboard focus came from `TAB`, a ring is drawn around the list
+    focus_ring: bool,
     mouse_pos: (i32, i32),
     can_drag: bool,
     can_hover: bool,
+    // entry the last tooltip was requested for
+    tooltip_entry: Entry,
     selected: Vec<usize>,
     selected_pivot: usize,
     select_defer: Option<bool>,
     dropdown_defer: bool,
+    // builtin row the builtin menu was opened on
+    builtin_menu: usize,
+    // pasted files are dropped once they're listed
+    paste_pending: bool,
 
     drag_drop: DragDrop,
 }
@@ -336,34 +823,36 @@ impl ModListWidget {
     const MARGIN_TOP: u32 = button::EXIT_X_OFFSET + button::EXIT_Y_OFFSET + button::EXIT_HEIGHT - 10;
     const WIDTH_INNER: u32 = 700;
     const HEIGHT_INNER: u32 = 496;
+    // typing after this long starts a new prefix
+    const JUMP_RESET: Duration = Duration::from_millis(1000);
 
     const ITEM_HEIGHT: u32 = 22;
+    const HOVER_FADE_STEP: f32 = 0.2;
+    // column headers take the first row of the list
+    const LIST_TOP: u32 = Self::MARGIN_Y + Self::ITEM_HEIGHT;
+    const LIST_HEIGHT: u32 = Self::HEIGHT_INNER - Self::ITEM_HEIGHT;
 
-    const FALLBACK_BACKGROUND: [f32; 4] = [0.0, 0.0, 0.0, 0.8];
-    const FALLBACK_BORDER: [f32; 4] = [0.6, 0.6, 0.6, 1.0];
 
-    const MOD_BUILTIN_GOLD: [f32; 4] = [
-        220.0 / 255.0,
-        190.0 / 255.0,
-        60.0 / 255.0,
-        1.0,
-    ];
-    const MOD_ENABLED_BLUE: [f32; 4] = [
-        71.0 / 255.0,
-        196.0 / 255.0,
-        208.0 / 255.0,
-        1.0,
+    // in `MANIFEST_DIR` after "Launch Vanilla Once"
+    const VANILLA_ONCE: &str = "vanilla_once";
+
+    // (version file, name, project page)
+    const BUILTINS: [(&str, &str, &str); 2] = [
+        ("base/mod_manager.lua", "Darktide Mod Loader", "https://github.com/Darktide-Mod-Framework/Darktide-Mod-Loader"),
+        ("dmf/dmf.mod", "Darktide Mod Framework", "https://github.com/Darktide-Mod-Framework/Darktide-Mod-Framework"),
     ];
-    const MOD_DISABLED_GRAY: [f32; 4] = [
-        102.0 / 255.0,
-        102.0 / 255.0,
-        102.0 / 255.0,
-        1.0,
+
+    const MOD_ENTRY_LENGTH: f32 = 430.0;
+    // archive tree in the preview, `[x]` is only drawn for mod folders
+    const TREE_CHECK_WIDTH: u32 = 28;
+    const TREE_INDENT: u32 = 14;
+    // right edge of each column from the left of the list
+    const COLUMNS: [(Column, &str, f32); 4] = [
+        (Column::Name, "name", 190.0),
+        (Column::Version, "version", 260.0),
+        (Column::State, "state", 336.0),
+        (Column::Installed, "installed", Self::MOD_ENTRY_LENGTH - 4.0),
     ];
-    const MOD_MISSING_ENTRY_ORANGE: [f32; 4] = [0.8, 0.5, 0.0, 1.0];
-    const MOD_NOT_INSTALLED_RED: [f32; 4] = [0.6, 0.2, 0.2, 1.0];
-    const MOD_HIGHLIGHT: [f32; 4] = [0.2, 0.2, 0.2, 0.5];
-    const MOD_ENTRY_LENGTH: f32 = 320.0;
 
     pub fn new(
         mods_path: impl Into<PathBuf>,
@@ -384,20 +873,52 @@ impl ModListWidget {
             root,
             mods_path,
             lorder: ModEngine::new(),
+            version_errors: Vec::new(),
+            file_conflicts: Vec::new(),
+            integrity: Vec::new(),
+            sort_preview: None,
+            disable_prompt: None,
+            normalize_prompt: None,
+            pending_delete: Vec::new(),
+            pending_error: None,
+            notice: None,
+            dispatcher: None,
+            scan_tag: 0,
+            normalize_offered: false,
             builtins: Vec::new(),
             is_patched: false,
+            patched_sent: None,
+            watching: false,
+            config: Config::default(),
+            theme: Theme::DEFAULT,
+            theme_changed: false,
+            rename: None,
+            spinner: 0,
+            filter: String::new(),
+            filter_focus: false,
+            jump: (String::new(), Instant::now()),
+            view_sort: None,
+            rows: Vec::new(),
+            installed: HashMap::new(),
 
             scroll: 0,
+            scroll_target: None,
+            hover_fade: 1.0,
             item_height: Self::ITEM_HEIGHT as i32,
             active_mod: usize::MAX,
             clicked_mod: None,
+            box_select: None,
+            focus_ring: false,
             mouse_pos: (-1, -1),
             can_drag: false,
             can_hover: false,
+            tooltip_entry: Entry::None,
             selected: Vec::new(),
             selected_pivot: 0,
             select_defer: None,
             dropdown_defer: false,
+            builtin_menu: 0,
+            paste_pending: false,
 
             drag_drop,
         }
@@ -406,6 +927,7 @@ impl ModListWidget {
     pub fn fallback(
         context: &mut super::DrawScope,
         brush: &SolidColorBrush,
+        theme: &Theme,
     ) {
         let rect = [
             (Self::MARGIN_X - 2) as f32,
@@ -415,14 +937,14 @@ impl ModListWidget {
         ];
         let radius = 8.0;
 
-        brush.set_color(&Self::FALLBACK_BACKGROUND);
+        brush.set_color(&theme.panel);
         context.fill_rounded_rect(
             brush,
             rect,
             radius,
         );
 
-        brush.set_color(&Self::FALLBACK_BORDER);
+        brush.set_color(&theme.border);
         context.draw_rounded_rect(
             brush,
             rect,
@@ -431,24 +953,83 @@ impl ModListWidget {
         );
     }
 
-    pub fn mount(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        self.builtins.clear();
+    pub fn show_on_start(&self) -> bool {
+        self.config.show_on_start
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.config.scale()
+    }
+
+    pub fn toggle_key(&self) -> Option<u16> {
+        self.config.toggle_vk()
+    }
+
+    pub fn theme(&self) -> Theme {
+        self.theme
+    }
+
+    pub fn is_patched(&self) -> bool {
+        self.is_patched
+    }
+
+    // other widgets only get colors through `ControlScope::set_theme`, mounts
+    // reload the config outside of events so it's sent with the next one
+    fn send_theme(&mut self, control: &mut super::ControlScope) {
+        if std::mem::take(&mut self.theme_changed) {
+            control.set_theme(self.theme);
+        }
+    }
+
+    // for bug reports, copied as text from the about panel
+    fn about(&self) -> Vec<String> {
+        let build = match cfg!(debug_assertions) {
+            true => "debug",
+            false => "release",
+        };
+        let hook = match crate::hook::is_active() {
+            true => "active",
+            false => "disabled",
+        };
+        let patch = match self.is_patched {
+            true => "applied",
+            false => "missing",
+        };
+        vec![
+            format!(
+                "modtide {} ({}, {build})",
+                env!("CARGO_PKG_VERSION"),
+                option_env!("MODTIDE_GIT_HASH").unwrap_or("unknown commit"),
+            ),
+            format!("launcher hook: {hook}"),
+            format!("mod loader patch: {patch}"),
+            format!("mods folder: {}", self.mods_path.display()),
+        ]
+    }
 
-        self.mods_path.push("base/mod_manager.lua");
-        if self.mods_path.exists() {
-            self.builtins.push("Darktide Mod Loader");
+    fn load_config(&mut self) {
+        match Config::load(&self.mods_path) {
+            Ok(config) => self.config = config,
+            Err(err) => crate::log::log(&format!("failed to load settings: {err}")),
         }
-        self.mods_path.pop();
-        self.mods_path.pop();
+        self.theme = Theme::from_config(&self.config);
+        self.theme_changed = true;
+        self.drag_drop.extract_limit = self.config.extract_limit();
+    }
 
-        self.mods_path.push("dmf/dmf.mod");
-        if self.mods_path.exists() {
-            self.builtins.push("Darktide Mod Framework");
+    pub fn mount(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.load_config();
+        self.builtins.clear();
+        for (path, name, _) in Self::BUILTINS {
+            let path = self.mods_path.join(path);
+            if path.exists() {
+                let version = std::fs::read_to_string(&path).ok()
+                    .and_then(|file| crate::mod_engine::builtin_version(&file));
+                self.builtins.push((name, version));
+            }
         }
-        self.mods_path.pop();
-        self.mods_path.pop();
 
-        let data = match std::fs::read_to_string(self.mods_path.join("mod_load_order.txt")) {
+        let data = match std::fs::read_to_string(self.mods_path.join(crate::backup::LOAD_ORDER)) {
             Ok(s) => s,
             Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
             Err(err) => return Err(err.into()),
@@ -463,15 +1044,67 @@ impl ModListWidget {
             &data
         };
 
-        let paths = ModEngine::scan(&self.mods_path)?;
+        // indices change when mods are added or removed so the selection is
+        // kept by name
+        let name = |i: usize| self.lorder.mods.get(i).map(|m| m.name().to_string());
+        let selected: Vec<String> = self.selected.iter().filter_map(|&i| name(i)).collect();
+        let pivot = name(self.selected_pivot);
+
+        let paths = ModEngine::scan(&self.mods_path, self.config.scan_depth as usize)?;
         self.lorder.load(load_order, paths)?;
+        self.scan_files();
+
+        let index = |name: &str| self.lorder.mods.iter().position(|m| m.name() == name);
+        self.selected = selected.iter().filter_map(|name| index(name)).collect();
+        if let Some(i) = pivot.and_then(|name| index(&name)) {
+            self.selected_pivot = i;
+        }
+        self.version_errors = self.lorder.validate();
+        let folders = self.lorder.prefixed_folders();
+        if !folders.is_empty() && !self.normalize_offered {
+            self.normalize_offered = true;
+            self.normalize_prompt = Some(folders);
+        }
+
+        self.installed.clear();
+        for m in &self.lorder.mods {
+            if let Some(folder) = m.meta.folder()
+                && let Some(time) = crate::manifest::installed(&self.mods_path, folder)
+            {
+                let mut date = String::new();
+                format_date(&mut date, time);
+                self.installed.insert(m.name().to_string(), (time, date));
+            }
+        }
 
         self.is_patched = crate::patch::is_patched(&self.root);
 
+        self.update_rows();
+        // keeps the scroll unless the list got shorter
+        let max_scroll = self.rows().len() as i32 * self.item_height - Self::LIST_HEIGHT as i32;
+        if self.scroll > max_scroll.max(0) {
+            self.set_scroll(max_scroll.max(0));
+        }
+
         Ok(())
     }
 
-    fn update_mod_lorder(&self) {
+    // check marks and grayed out entries for the next dropdown
+    // checked and disabled entries of the dropdown menus, see `MenuStates`
+    fn menu_states(&self) -> Vec<(ModListEvent, ItemState)> {
+        let can_enable = self.lorder.mods.iter()
+            .any(|m| matches!(m.state, ModState::Disabled | ModState::MissingEntry));
+        let can_disable = self.lorder.mods.iter()
+            .any(|m| matches!(m.state, ModState::Enabled | ModState::MissingEntry));
+        vec![
+            (ModListEvent::TogglePatch, ItemState { checked: self.is_patched, ..Default::default() }),
+            (ModListEvent::RenameSelected, ItemState { disabled: self.selected.len() != 1, ..Default::default() }),
+            (ModListEvent::EnableAll, ItemState { disabled: !can_enable, ..Default::default() }),
+            (ModListEvent::DisableAll, ItemState { disabled: !can_disable, ..Default::default() }),
+        ]
+    }
+
+    fn update_mod_lorder(&mut self) -> io::Result<()> {
         let mut out = String::new();
         out.push_str(Self::MODTIDE_HEADER_PREFIX);
         let res;
@@ -483,10 +1116,290 @@ impl ModListWidget {
             );
         }
         out.push('\n');
+        self.update_rows();
+
+        if res.is_err() || self.lorder.generate(&mut out).is_err() {
+            return Err(io::Error::other("failed to format load order"));
+        }
+
+        let path = self.mods_path.join(crate::backup::LOAD_ORDER);
+        write_atomic(&path, out.as_bytes())
+            .map_err(|err| io::Error::new(err.kind(), format!("failed to write {}: {err}", path.display())))
+    }
+
+    // local time for backup names, sorts by time and has no `:`
+    fn backup_stamp() -> String {
+        let time = unsafe { windows::Win32::System::SystemInformation::GetLocalTime() };
+        format!("{}{:02}{:02}T{:02}{:02}{:02}",
+            time.wYear, time.wMonth, time.wDay,
+            time.wHour, time.wMinute, time.wSecond,
+        )
+    }
+
+    // only before changes that rewrite much of the order, saving every toggle
+    // would push useful backups out
+    fn backup_mod_lorder(&self) {
+        if let Err(err) = crate::backup::backup(&self.mods_path, &Self::backup_stamp(), crate::backup::KEEP) {
+            crate::log::log(&format!("failed to back up load order: {err}"));
+        }
+    }
+
+    // for changes from the ui, a failed write is offered a retry
+    fn save_mod_lorder(&mut self) {
+        if let Err(err) = self.update_mod_lorder() {
+            let text = err.to_string();
+            crate::log::log(&text);
+            self.pending_error = Some((ModListEvent::RetrySave, text));
+        }
+    }
+
+    // renames `_NAME` folders to `NAME` after writing them as disabled entries
+    fn normalize_folders(
+        &mut self,
+        folders: &[(String, String)],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.backup_mod_lorder();
+        self.update_mod_lorder()?;
+
+        let mut res = Ok(());
+        for (folder, name) in folders {
+            let to = self.mods_path.join(Path::new(folder).with_file_name(name));
+            if to.exists() {
+                res = Err(format!("{name} already exists").into());
+            } else if let Err(err) = std::fs::rename(self.mods_path.join(folder), to) {
+                res = Err(err.into());
+            }
+        }
+
+        self.selected.clear();
+        self.mount()?;
+        res
+    }
+
+    // renames the mod folder and its load order entry
+    fn rename_mod(&mut self, i: usize, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(m) = self.lorder.mods.get(i) else {
+            return Ok(());
+        };
+        let old = m.name().to_string();
+        if old == name {
+            return Ok(());
+        }
+        let folder = m.meta.folder().ok_or("mod isn't installed")?.to_string();
+
+        crate::mod_engine::rename_mod_folder(&self.mods_path, &folder, &old, name)?;
+        let renamed = format!("{}{name}", &folder[..folder.len() - old.len()]);
+        let _ = crate::manifest::rename(&self.mods_path, &folder, &renamed);
+        self.lorder.rename_entry(&old, name);
+        self.update_mod_lorder()?;
+        self.mount()?;
+        Ok(())
+    }
+
+    // removes the mod folders and their load order entries
+    //
+    // mods that fail to delete keep their entry, the load order is saved and
+    // mounted either way so it matches what is left on disk
+    fn delete_mods(&mut self, names: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        self.backup_mod_lorder();
+        let mut failed = Vec::new();
+        for name in names {
+            let Some(m) = self.lorder.mods.iter().find(|m| m.name() == name) else {
+                continue;
+            };
+            if m.state != ModState::NotInstalled
+                && let Some(folder) = m.meta.folder()
+            {
+                if let Err(err) = std::fs::remove_dir_all(self.mods_path.join(folder)) {
+                    failed.push(format!("{name}: {err}"));
+                    continue;
+                }
+                let _ = crate::manifest::remove(&self.mods_path, folder);
+            }
+            self.lorder.remove_entry(name);
+        }
+        self.selected.clear();
+        let saved = self.update_mod_lorder();
+        self.mount()?;
+        saved?;
+        if !failed.is_empty() {
+            return Err(failed.join("\n").into());
+        }
+        Ok(())
+    }
+
+    // `BUILTINS` entry of a builtin row, only installed builtins have rows
+    fn builtin_info(&self, i: usize) -> Option<(&'static str, &'static str, &'static str)> {
+        let (name, _) = self.builtins.get(i)?;
+        Self::BUILTINS.into_iter().find(|(_, n, _)| n == name)
+    }
+
+    fn drop_files(&mut self, control: &mut super::ControlScope, ctrl: bool, shift: bool) {
+        let notify = control.dispatcher();
+        self.drag_drop.drag_drop(ctrl, shift, move || {
+            notify(ModListEvent::DragDropPoll as u32);
+        });
+        if self.drag_drop.state == DragDropState::Confirming {
+            let mut lines = vec!["Replace these installed mods?".to_string()];
+            lines.extend(self.drag_drop.conflicts.iter().cloned());
+            ConfirmWidget::show(
+                control,
+                lines,
+                "Overwrite",
+                ModListEvent::InstallConfirmed as u32,
+                Some(ModListEvent::InstallCanceled as u32),
+            );
+        }
+    }
+
+    // installs copied files like dropping them, once they're listed
+    fn paste(&mut self, control: &mut super::ControlScope) {
+        if !matches!(self.drag_drop.state, DragDropState::None | DragDropState::Dragging) {
+            return;
+        }
+
+        let paths = super::clipboard::paths();
+        if paths.is_empty() {
+            self.drag_drop.error = Some("no files in the clipboard (links have to be downloaded first)".into());
+            control.redraw();
+            return;
+        }
+
+        let notify = control.dispatcher();
+        self.drag_drop.mouse_enter(&paths, move || {
+            notify(ModListEvent::DragDropPoll as u32);
+        });
+        if self.drag_drop.state == DragDropState::Listing {
+            self.paste_pending = true;
+            self.spinner = 0;
+            control.tick(true);
+        }
+        control.redraw();
+    }
+
+    // re-reads the load order and mods folder
+    pub fn refresh(&mut self) {
+        self.rename = None;
+        if let Err(err) = self.mount() {
+            let text = format!("failed mod list mount: {err}");
+            crate::log::log(&text);
+            self.pending_error = Some((ModListEvent::RetryMount, text.clone()));
+            self.notice = Some(Notice::error(text));
+        }
+        self.selected.retain(|i| *i < self.lorder.mods.len());
+    }
+
+    // walks the mod folders off the ui thread, `FilesScanned` picks up the
+    // result
+    fn scan_files(&mut self) {
+        let Some(notify) = self.dispatcher.clone() else {
+            return;
+        };
+        self.scan_tag += 1;
+        let tag = self.scan_tag;
+        FILES_MAILBOX.clear(tag);
+
+        let mods_path = self.mods_path.clone();
+        let metas: Vec<_> = self.lorder.mods.iter()
+            .filter(|m| m.state != ModState::NotInstalled)
+            .map(|m| m.meta.clone())
+            .collect();
+        std::thread::spawn(move || {
+            match ModEngine::scan_files(&mods_path, &metas) {
+                Ok(files) => {
+                    FILES_MAILBOX.send(tag, files);
+                    notify(ModListEvent::FilesScanned as u32);
+                }
+                Err(err) => crate::log::log(&format!("failed to scan mod files: {err}")),
+            }
+        });
+    }
+
+    // errors can happen before there's a `ControlScope` so they wait for the
+    // next event
+    fn show_error(&mut self, control: &mut super::ControlScope) {
+        let Some((retry, text)) = self.pending_error.take() else {
+            return;
+        };
+        let lines = vec![
+            text,
+            "".into(),
+            "Close programs using the mods folder and retry.".into(),
+        ];
+        ConfirmWidget::show(control, lines, "Retry", retry as u32, None);
+    }
+
+    // install errors take the place of the last notice
+    fn message(&self) -> Option<(&str, [f32; 4])> {
+        if let Some(text) = &self.drag_drop.error {
+            return Some((text, self.theme.error));
+        }
+        let notice = self.notice.as_ref()?;
+        let color = if notice.error { self.theme.error } else { self.theme.builtin };
+        Some((&notice.text, color))
+    }
+
+    // changes modtide makes itself
+    fn ignore_change(name: &str) -> bool {
+        name.starts_with(crate::backup::LOAD_ORDER)
+            || name.starts_with(crate::manifest::MANIFEST_DIR)
+    }
+
+    // records file hashes of the mods just installed for `VerifyMods`
+    fn write_manifests(&mut self) {
+        let Some(view) = &self.drag_drop.view else {
+            return;
+        };
+        let names: Vec<String> = DragDrop::mod_names(view)
+            .into_iter()
+            .filter(|name| !self.drag_drop.skipped.contains(name))
+            .collect();
+        self.integrity.retain(|(n, _)| !names.contains(n));
+        // keyed by folder like `VerifyMods`, the install was just mounted
+        let folders: Vec<String> = names.iter()
+            .map(|name| {
+                self.lorder.mods.iter()
+                    .find(|m| m.name() == name)
+                    .and_then(|m| m.meta.folder())
+                    .unwrap_or(name)
+                    .to_string()
+            })
+            .collect();
+
+        // hashing large mods takes a while
+        let mods_path = self.mods_path.clone();
+        std::thread::spawn(move || {
+            for folder in folders {
+                if let Err(err) = crate::manifest::write(&mods_path, &folder) {
+                    crate::log::log(&format!("failed to write manifest for {folder}: {err}"));
+                }
+            }
+        });
+    }
 
-        if res.is_ok() && self.lorder.generate(&mut out).is_ok() {
-            std::fs::write(self.mods_path.join("mod_load_order.txt"), out).unwrap();
+    // returns a message listing mods from an imported order that aren't installed
+    fn load_order_file(
+        &mut self,
+        export: bool,
+        path: &Path,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        if export {
+            let mut out = String::new();
+            self.lorder.export(&mut out)?;
+            std::fs::write(path, out)?;
+            return Ok(None);
         }
+
+        let data = std::fs::read_to_string(path)?;
+        self.backup_mod_lorder();
+        let missing = self.lorder.import(&data)?;
+        self.selected.clear();
+        self.sort_preview = None;
+        self.disable_prompt = None;
+        self.version_errors = self.lorder.validate();
+        self.update_mod_lorder()?;
+
+        Ok((!missing.is_empty()).then(|| format!("missing mods: {}", missing.join(", "))))
     }
 
     fn toggle_mod(&mut self, entry: usize, enable: Option<bool>) -> bool {
@@ -517,79 +1430,341 @@ impl ModListWidget {
     fn get_entry(&self, pos: (i32, i32)) -> Entry {
         let (x, y) = pos;
         let left = Self::MARGIN_X as i32;
-        let top = Self::MARGIN_Y as i32;
+        let top = Self::LIST_TOP as i32;
         let offset = y - top;
         if offset < 0
-            || offset > Self::HEIGHT_INNER as i32
+            || offset > Self::LIST_HEIGHT as i32
             || x < left
             || x - left > Self::MOD_ENTRY_LENGTH as i32
         {
             Entry::None
         } else {
             let offset = self.scroll + offset;
-            let entry = (offset / self.item_height) as usize;
-            if let Some(_builtin) = self.builtins.get(entry) {
-                Entry::Builtin(entry)
-            } else {
-                Entry::Mod(entry - self.builtins.len())
-            }
+            let row = (offset / self.item_height) as usize;
+            self.rows().get(row).copied().unwrap_or(Entry::None)
         }
     }
 
-    fn get_slot(&self, pos: (i32, i32)) -> (usize, u32) {
-        let y = pos.1;
-        let mut min_offset = self.builtins.len() as i32 * self.item_height;
-        let mut max_offset = (self.builtins.len() + self.lorder.mods.len()) as i32 * self.item_height;
+    // group headers take a row above the first mod of the group
+    fn rows(&self) -> &[Entry] {
+        &self.rows
+    }
 
-        if self.scroll > min_offset {
-            min_offset = self.scroll;
-            let diff = min_offset % self.item_height;
-            if diff != 0 {
-                min_offset += self.item_height - diff;
+    // rebuilds the rows after the mods, their order, the filter or the view
+    // sort changed
+    fn update_rows(&mut self) {
+        self.rows = self.build_rows();
+    }
+
+    fn build_rows(&self) -> Vec<Entry> {
+        let mut rows: Vec<Entry> = (0..self.builtins.len()).map(Entry::Builtin).collect();
+        if self.view_sort.is_some() {
+            // groups only make sense in load order
+            rows.extend(self.view_order().into_iter().map(Entry::Mod));
+            return rows;
+        }
+        for (i, m) in self.lorder.mods.iter().enumerate() {
+            if !self.filter.is_empty() {
+                // groups aren't shown while filtering
+                if self.is_visible(i) {
+                    rows.push(Entry::Mod(i));
+                }
+                continue;
             }
+            if m.group().is_some() {
+                rows.push(Entry::Group(i));
+            }
+            rows.push(Entry::Mod(i));
         }
+        rows
+    }
 
-        if self.scroll + (Self::HEIGHT_INNER as i32) < max_offset {
-            max_offset = self.scroll + Self::HEIGHT_INNER as i32;
-            max_offset -= max_offset % self.item_height;
-        }
+    // visible mods sorted by `view_sort`, ties keep their load order
+    fn view_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.lorder.mods.len())
+            .filter(|i| self.filter.is_empty() || self.is_visible(*i))
+            .collect();
+        let Some((column, descending)) = self.view_sort else {
+            return order;
+        };
 
-        let mut start = self.scroll;
-        let diff = start % self.item_height;
-        if diff != 0 {
-            start += self.item_height - diff;
+        let mods = &self.lorder.mods;
+        order.sort_by(|&i, &j| {
+            let (a, b) = (&mods[i], &mods[j]);
+            let ord = match column {
+                Column::Name => a.display_name().to_lowercase().cmp(&b.display_name().to_lowercase()),
+                // mods without a version go last
+                Column::Version => a.version().is_none().cmp(&b.version().is_none())
+                    .then_with(|| compare_versions(a.version().unwrap_or(""), b.version().unwrap_or(""))),
+                Column::State => self.state_rank(i).cmp(&self.state_rank(j)),
+                Column::Installed => {
+                    let time = |name| self.installed.get(name).map(|(time, _)| time);
+                    let (a, b) = (time(a.name()), time(b.name()));
+                    a.is_none().cmp(&b.is_none()).then(a.cmp(&b))
+                }
+            };
+            if descending { ord.reverse() } else { ord }
+        });
+        order
+    }
+
+    // mods listed from `a` to `b`, used for shift selecting
+    fn view_range(&self, a: usize, b: usize) -> Vec<usize> {
+        let order = self.view_order();
+        let pos = |i| order.iter().position(|&m| m == i);
+        match (pos(a), pos(b)) {
+            (Some(a), Some(b)) => order[a.min(b)..=a.max(b)].to_vec(),
+            _ => vec![b],
         }
-        start = start.max(min_offset);
+    }
 
-        let mut end = self.scroll + Self::HEIGHT_INNER as i32;
-        end -= end % self.item_height;
-        end = end.min(max_offset);
+    // first ascending, then descending, then back to the load order
+    fn sort_view(&mut self, column: Column) {
+        self.view_sort = match self.view_sort {
+            Some((c, false)) if c == column => Some((column, true)),
+            Some((c, true)) if c == column => None,
+            _ => Some((column, false)),
+        };
+        self.update_rows();
+        self.set_scroll(0);
+    }
 
-        let mut offset = self.scroll + y - Self::MARGIN_Y as i32;
-        offset += self.item_height / 2;
-        offset -= offset % self.item_height;
+    fn column_at(x: i32) -> Option<Column> {
+        let x = (x - Self::MARGIN_X as i32) as f32;
+        if x < 0.0 {
+            return None;
+        }
+        Self::COLUMNS.iter()
+            .find(|(_, _, right)| x < *right)
+            .map(|(column, ..)| *column)
+    }
+
+    // left and right edge of the column text
+    fn column_span(column: Column) -> (f32, f32) {
+        let left = Self::MARGIN_X as f32;
+        let mut from = Self::TEXT_PADDING as f32;
+        for (c, _, right) in Self::COLUMNS {
+            if c == column {
+                return (left + from, left + right);
+            }
+            from = right + 4.0;
+        }
+        unreachable!()
+    }
+
+    // problems first, then by load order state
+    fn state_rank(&self, i: usize) -> u8 {
+        let m = &self.lorder.mods[i];
+        if self.version_errors.iter().any(|e| e.mod_name == m.name())
+            || self.integrity.iter().any(|(name, _)| name == m.name())
+            || self.file_conflicts.iter().any(|c| c.mods.iter().any(|n| n == m.name()))
+        {
+            return 0;
+        }
+        match m.state {
+            ModState::Enabled => 1,
+            ModState::Disabled => 2,
+            ModState::MissingEntry => 3,
+            ModState::NotInstalled => 4,
+        }
+    }
+
+    // case insensitive match of the filter against the folder or display name
+    fn is_visible(&self, i: usize) -> bool {
+        let filter = self.filter.to_lowercase();
+        self.lorder.mods.get(i).is_some_and(|m| {
+            m.name().to_lowercase().contains(&filter)
+                || m.display_name().to_lowercase().contains(&filter)
+        })
+    }
+
+    const FILTER_RECT: [i32; 4] = [
+        Self::MARGIN_X as i32,
+        (Self::MARGIN_Y - Self::ITEM_HEIGHT - 6) as i32,
+        Self::MARGIN_X as i32 + Self::MOD_ENTRY_LENGTH as i32,
+        Self::MARGIN_Y as i32 - 6,
+    ];
+
+    fn render_filter(&self, context: &mut super::DrawScope) {
+        let [left, top, right, bottom] = Self::FILTER_RECT;
+        let (text, color) = if self.filter_focus {
+            (format!("filter: {}_", self.filter), self.theme.enabled)
+        } else if !self.filter.is_empty() {
+            (format!("filter: {}", self.filter), self.theme.enabled)
+        } else {
+            ("filter (ctrl+f)".to_string(), self.theme.disabled)
+        };
+
+        self.brush.set_color(&color);
+        context.draw_text(
+            text.as_ref(),
+            &self.text_format,
+            &self.brush,
+            &[
+                (left + Self::TEXT_PADDING as i32) as f32,
+                top as f32,
+                right as f32,
+                bottom as f32,
+            ],
+        );
+    }
+
+    // below the list like the filter is above it
+    fn render_legend(&self, context: &mut super::DrawScope) {
+        const STEP: u32 = 110;
+
+        let top = Self::MARGIN_Y + Self::HEIGHT_INNER + 6;
+        let mut left = Self::MARGIN_X + Self::TEXT_PADDING;
+        for (text, color) in [
+            ("enabled", self.theme.enabled),
+            ("disabled", self.theme.disabled),
+            ("unlisted", self.theme.missing_entry),
+            ("missing", self.theme.not_installed),
+            ("builtin", self.theme.builtin),
+        ] {
+            self.brush.set_color(&color);
+            context.draw_text(
+                format!("\u{25a0} {text}").as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[left, top, left + STEP, top + Self::ITEM_HEIGHT].map(|b| b as f32),
+            );
+            left += STEP;
+        }
+    }
+
+    // enabled mods that won't load, the banner takes the legend's place
+    fn patch_missing(&self) -> bool {
+        !self.is_patched
+            && self.lorder.mods.iter().any(|m| m.state == ModState::Enabled)
+    }
+
+    fn patch_button_rect() -> [i32; 4] {
+        const WIDTH: u32 = 100;
+
+        let top = Self::MARGIN_Y + Self::HEIGHT_INNER + 6;
+        let right = Self::MARGIN_X + Self::WIDTH_INNER;
+        [right - WIDTH, top, right, top + Self::ITEM_HEIGHT].map(|b| b as i32)
+    }
+
+    fn render_patch_banner(&self, context: &mut super::DrawScope) {
+        let top = Self::MARGIN_Y + Self::HEIGHT_INNER + 6;
+        let rect = [
+            Self::MARGIN_X,
+            top,
+            Self::MARGIN_X + Self::WIDTH_INNER,
+            top + Self::ITEM_HEIGHT,
+        ].map(|b| b as f32);
+        self.brush.set_color(&self.theme.background);
+        context.fill_rounded_rect(&self.brush, rect, 2.0);
+        self.brush.set_color(&self.theme.not_installed);
+        context.draw_rounded_rect(&self.brush, rect, 2.0, 1.0);
+        context.draw_text(
+            "Mods will not load \u{2014} patch missing".as_ref(),
+            &self.text_format,
+            &self.brush,
+            &[rect[0] + Self::TEXT_PADDING as f32, rect[1], rect[2], rect[3]],
+        );
+
+        let button = Self::patch_button_rect().map(|b| b as f32);
+        self.brush.set_color(&self.theme.border);
+        context.draw_rounded_rect(&self.brush, button, 2.0, 1.0);
+        self.brush.set_color(&self.theme.text);
+        self.text_format.set_text_alignment(crate::dxgi::Alignment::Mid).unwrap();
+        context.draw_text(
+            "Patch now".as_ref(),
+            &self.text_format,
+            &self.brush,
+            &button,
+        );
+        self.text_format.set_text_alignment(crate::dxgi::Alignment::Min).unwrap();
+    }
+
+    fn set_filter(&mut self, filter: String) {
+        self.filter = filter;
+        self.update_rows();
+        self.set_scroll(0);
+        let selected = std::mem::take(&mut self.selected);
+        self.selected = selected.into_iter().filter(|&i| self.is_visible(i)).collect();
+    }
+
+    // mod index to insert at and whether that is right below a group header
+    fn get_slot(&self, pos: (i32, i32)) -> (usize, u32, bool) {
+        let (row, offset) = self.get_slot_row(pos);
+        let rows = self.rows();
+        let entry = rows[..row].iter()
+            .filter(|r| matches!(r, Entry::Mod(_)))
+            .count();
+        let in_group = row > 0 && matches!(rows[row - 1], Entry::Group(_));
+        (entry, offset, in_group)
+    }
+
+    fn get_slot_row(&self, pos: (i32, i32)) -> (usize, u32) {
+        let y = pos.1;
+        let mut min_offset = self.builtins.len() as i32 * self.item_height;
+        let mut max_offset = self.rows().len() as i32 * self.item_height;
+
+        if self.scroll > min_offset {
+            min_offset = self.scroll;
+            let diff = min_offset % self.item_height;
+            if diff != 0 {
+                min_offset += self.item_height - diff;
+            }
+        }
+
+        if self.scroll + (Self::LIST_HEIGHT as i32) < max_offset {
+            max_offset = self.scroll + Self::LIST_HEIGHT as i32;
+            max_offset -= max_offset % self.item_height;
+        }
+
+        let mut start = self.scroll;
+        let diff = start % self.item_height;
+        if diff != 0 {
+            start += self.item_height - diff;
+        }
+        start = start.max(min_offset);
+
+        let mut end = self.scroll + Self::LIST_HEIGHT as i32;
+        end -= end % self.item_height;
+        end = end.min(max_offset);
+
+        let mut offset = self.scroll + y - Self::LIST_TOP as i32;
+        offset += self.item_height / 2;
+        offset -= offset % self.item_height;
 
         let slot = offset.min(end).max(start);
-        let mut entry = slot / self.item_height;
-        entry = entry.saturating_sub(self.builtins.len() as i32);
-        let entry = entry as usize;
+        let row = (slot / self.item_height) as usize;
 
-        let offset = slot - self.scroll + Self::MARGIN_Y as i32;
+        let offset = slot - self.scroll + Self::LIST_TOP as i32;
         let offset = offset
-            .min((Self::MARGIN_Y + Self::HEIGHT_INNER) as i32)
+            .min((Self::LIST_TOP + Self::LIST_HEIGHT) as i32)
             .max(0);
 
         assert!(slot >= 0);
         assert!(slot % self.item_height == 0);
-        assert!(entry <= self.lorder.mods.len());
-        (entry, offset as u32)
+        (row, offset as u32)
     }
 
     fn move_selected(
         &mut self,
         to: usize,
+        in_group: bool,
     ) -> bool {
         self.selected.sort();
+
+        // group headers stay with the unselected rest of their group, a fully
+        // selected group moves with its header
+        for k in 0..self.selected.len() {
+            let i = self.selected[k];
+            if self.lorder.mods[i].group().is_none() {
+                continue;
+            }
+            let rest = self.lorder.group_range(i).find(|j| !self.selected.contains(j));
+            if let Some(j) = rest {
+                self.lorder.move_group(i, j);
+            }
+        }
+
         let mods = &mut self.lorder.mods;
 
         debug_assert!(!self.selected.is_empty());
@@ -617,10 +1792,115 @@ impl ModListWidget {
             self.selected.push(to + i);
         }
 
+        if in_group {
+            self.lorder.move_group(to + len, to);
+        }
+        self.update_rows();
+
         // we don't check if redraw is necessary yet
         true
     }
 
+    // moves the selection one row up or down, returns false if it can't move
+    //
+    // rows are used instead of entries so a mod steps into or out of a group
+    // before passing its header
+    fn step_selected(&mut self, up: bool) -> bool {
+        if self.selected.is_empty()
+            || !self.filter.is_empty()
+            || self.view_sort.is_some()
+            || self.clicked_mod.is_some()
+        {
+            return false;
+        }
+
+        let rows = self.rows();
+        let mut selected_rows = rows.iter()
+            .enumerate()
+            .filter(|(_, r)| matches!(r, Entry::Mod(i) if self.selected.contains(i)))
+            .map(|(row, _)| row);
+        let slot = if up {
+            // the first mod row sits right below the builtins
+            match selected_rows.next() {
+                Some(first) if first > self.builtins.len() => first - 1,
+                _ => return false,
+            }
+        } else {
+            match selected_rows.next_back() {
+                Some(last) if last + 1 < rows.len() => last + 2,
+                _ => return false,
+            }
+        };
+        let to = rows[..slot].iter()
+            .filter(|r| matches!(r, Entry::Mod(_)))
+            .count();
+        let in_group = matches!(rows[..slot].last(), Some(Entry::Group(_)));
+
+        self.move_selected(to, in_group);
+        self.selected_pivot = self.selected[0];
+
+        // keep the moved mods in view
+        let edge = match up {
+            true => self.selected[0],
+            false => self.selected[self.selected.len() - 1],
+        };
+        self.scroll_to(edge);
+        true
+    }
+
+    fn scroll_to(&mut self, i: usize) {
+        if let Some(row) = self.rows().iter().position(|r| *r == Entry::Mod(i)) {
+            let top = row as i32 * self.item_height;
+            let bottom = top + self.item_height;
+            if top < self.scroll {
+                self.set_scroll(top);
+            } else if bottom > self.scroll + Self::LIST_HEIGHT as i32 {
+                self.set_scroll(bottom - Self::LIST_HEIGHT as i32);
+            }
+        }
+    }
+
+    // selects the next mod starting with the typed prefix, like explorer
+    //
+    // repeating the first character cycles through mods starting with it
+    fn jump_to(&mut self, c: char) -> bool {
+        let order = self.view_order();
+        let (prefix, time) = &mut self.jump;
+        if time.elapsed() > Self::JUMP_RESET {
+            prefix.clear();
+        }
+        *time = Instant::now();
+        prefix.extend(c.to_lowercase());
+
+        let repeat = prefix.chars().all(|p| c.to_lowercase().eq([p]));
+        // position in the listed order
+        let current = self.selected.first()
+            .and_then(|_| order.iter().position(|&i| i == self.selected_pivot));
+        let (needle, start) = match (repeat, current) {
+            (true, Some(p)) => (c.to_lowercase().collect(), p + 1),
+            (false, Some(p)) => (prefix.clone(), p),
+            (_, None) => (prefix.clone(), 0),
+        };
+
+        let len = order.len();
+        let found = (start..start + len)
+            .map(|p| order[p % len])
+            .find(|i| {
+                let m = &self.lorder.mods[*i];
+                m.name().to_lowercase().starts_with(&needle)
+                    || m.display_name().to_lowercase().starts_with(&needle)
+            });
+        let Some(i) = found else {
+            return false;
+        };
+
+        self.selected.clear();
+        self.selected.push(i);
+        self.selected_pivot = i;
+        self.scroll_to(i);
+        true
+    }
+
     fn toggle_selected(&mut self) -> bool {
         if !self.selected.is_empty() {
             let mods = &mut self.lorder.mods;
@@ -636,10 +1916,14 @@ impl ModListWidget {
                 }
             }
 
+            let mut disabled = Vec::new();
             for i in &self.selected {
                 if let Some(m) = mods.get_mut(*i) {
                     match (all_enabled, m.state.clone()) {
-                        (true, ModState::Enabled) => m.state = ModState::Disabled,
+                        (true, ModState::Enabled) => {
+                            m.state = ModState::Disabled;
+                            disabled.push(m.name().to_string());
+                        }
                         (false, ModState::Disabled | ModState::MissingEntry)
                             => m.state = ModState::Enabled,
                         _ => (),
@@ -647,13 +1931,24 @@ impl ModListWidget {
                 }
             }
 
+            let names: Vec<&str> = disabled.iter().map(|n| n.as_str()).collect();
+            let dependents = self.lorder.enabled_dependents(&names);
+            if !dependents.is_empty() {
+                crate::log::log(&format!(
+                    "disabled {} still required by {}",
+                    disabled.join(", "),
+                    dependents.join(", "),
+                ));
+                self.disable_prompt = Some((disabled, dependents));
+            }
+
             true
         } else {
             false
         }
     }
 
-    fn open(path: &Path) {
+    pub(super) fn open(path: &Path) {
         use std::os::windows::ffi::OsStrExt;
 
         use windows::Win32::Foundation::CloseHandle;
@@ -722,11 +2017,13 @@ impl ModListWidget {
         selected: bool,
     ) {
         let left = Self::MARGIN_X;
-        let top = Self::MARGIN_Y as i32;
+        let top = Self::LIST_TOP as i32;
         let item_height = self.item_height;
 
         if hovered {
-            self.brush.set_color(&Self::MOD_HIGHLIGHT);
+            let mut color = self.theme.hover;
+            color[3] *= self.hover_fade;
+            self.brush.set_color(&color);
 
             let mid = (top + o + item_height / 2) as f32;
             let from = [
@@ -745,15 +2042,17 @@ impl ModListWidget {
         let rect = [
             (left + Self::TEXT_PADDING) as f32,
             (top + o) as f32,
-            left as f32 + Self::MOD_ENTRY_LENGTH,
+            Self::column_span(Column::Name).1,
             (top + o + item_height) as f32,
         ];
-        context.draw_text(
+        context.push_axis_aligned_clip(&rect);
+        context.draw_text_layout(
             text.as_ref(),
             &self.text_format,
             &self.brush,
             &rect,
         );
+        context.pop_axis_aligned_clip();
 
         if selected {
             self.brush.set_color(&color);
@@ -771,6 +2070,176 @@ impl ModListWidget {
         }
     }
 
+    // text clipped to the column so long versions don't run into the next one
+    fn draw_column(
+        &self,
+        context: &mut super::DrawScope,
+        column: Column,
+        text: &str,
+        color: [f32; 4],
+        o: i32,
+    ) {
+        let top = Self::LIST_TOP as i32;
+        let (left, right) = Self::column_span(column);
+        let rect = [
+            left,
+            (top + o) as f32,
+            right,
+            (top + o + self.item_height) as f32,
+        ];
+
+        self.brush.set_color(&color);
+        context.push_axis_aligned_clip(&rect);
+        context.draw_text_layout(
+            text.as_ref(),
+            &self.text_format,
+            &self.brush,
+            &rect,
+        );
+        context.pop_axis_aligned_clip();
+    }
+
+    fn render_mod(&self, context: &mut super::DrawScope, i: usize, offset: i32, hovered: bool) {
+        let m = &self.lorder.mods[i];
+        let color = match m.state {
+            ModState::Enabled => self.theme.enabled,
+            ModState::Disabled => self.theme.disabled,
+            ModState::MissingEntry => self.theme.missing_entry,
+            ModState::NotInstalled => self.theme.not_installed,
+        };
+
+        // rows are drawn every frame so only the rename allocates
+        let name = match &self.rename {
+            Some((r, text)) if *r == i => Cow::Owned(format!("{text}_")),
+            _ => Cow::Borrowed(m.display_name()),
+        };
+        self.draw_mod(
+            context,
+            &name,
+            color,
+            offset,
+            hovered,
+            self.selected.contains(&i),
+        );
+        if let Some(version) = m.version() {
+            self.draw_column(context, Column::Version, version, self.theme.disabled, offset);
+        }
+
+        // problems take the place of the load order state, the tooltip has the details
+        let (state, state_color) = if let Some(err) = self.version_errors.iter().find(|e| e.mod_name == m.name()) {
+            (Cow::Owned(format!("needs {}", err.require)), self.theme.not_installed)
+        } else if self.integrity.iter().any(|(name, _)| name == m.name()) {
+            (Cow::Borrowed("modified"), self.theme.not_installed)
+        } else if self.file_conflicts.iter().any(|c| c.mods.iter().any(|n| n == m.name())) {
+            (Cow::Borrowed("conflict"), self.theme.missing_entry)
+        } else {
+            let text = match m.state {
+                ModState::Enabled => "enabled",
+                ModState::Disabled => "disabled",
+                ModState::MissingEntry => "unlisted",
+                ModState::NotInstalled => "missing",
+            };
+            (Cow::Borrowed(text), self.theme.disabled)
+        };
+        self.draw_column(context, Column::State, &state, state_color, offset);
+
+        if let Some((_, date)) = self.installed.get(m.name()) {
+            self.draw_column(context, Column::Installed, date, self.theme.disabled, offset);
+        }
+    }
+
+    fn render_header(&self, context: &mut super::DrawScope) {
+        let top = Self::MARGIN_Y as f32;
+        for (column, title, _) in Self::COLUMNS {
+            let (left, right) = Self::column_span(column);
+            let (text, color) = match self.view_sort {
+                Some((c, descending)) if c == column => {
+                    let arrow = if descending { "\u{25bc}" } else { "\u{25b2}" };
+                    (format!("{title} {arrow}"), self.theme.builtin)
+                }
+                _ => (title.to_string(), self.theme.disabled),
+            };
+            self.brush.set_color(&color);
+            context.draw_text(
+                text.as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[left, top, right, top + Self::ITEM_HEIGHT as f32],
+            );
+        }
+
+        // the load order is what the game reads so make it clear this isn't it
+        if let Some((column, _)) = self.view_sort
+            && self.drag_drop.state == DragDropState::None
+        {
+            let title = Self::COLUMNS.iter()
+                .find(|(c, ..)| *c == column)
+                .map_or("", |(_, title, _)| *title);
+            let [left, _, right, _] = self.preview_rect();
+            self.brush.set_color(&self.theme.missing_entry);
+            context.draw_text(
+                format!("by {title}, view only, not the load order").as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[left as f32, top, right as f32, top + Self::ITEM_HEIGHT as f32],
+            );
+        }
+    }
+
+    fn tooltip_lines(&self, entry: Entry) -> Vec<String> {
+        let i = match entry {
+            Entry::Builtin(0) if !self.is_patched => {
+                return vec!["Not patched, double click to patch".into()];
+            }
+            Entry::Mod(i) => i,
+            _ => return Vec::new(),
+        };
+        let Some(m) = self.lorder.mods.get(i) else {
+            return Vec::new();
+        };
+
+        let state = match m.state {
+            ModState::Enabled => "Enabled",
+            ModState::Disabled => "Disabled",
+            ModState::MissingEntry => "Installed but missing from mod_load_order.txt",
+            ModState::NotInstalled => "In mod_load_order.txt but not installed",
+        };
+        let mut lines = vec![format!("mods/{}", m.path())];
+        if let Some(author) = m.meta.author() {
+            lines.push(format!("By {author}"));
+        }
+        if let Some(description) = m.meta.description() {
+            lines.extend(description.lines().map(str::to_string));
+        }
+        lines.push(state.to_string());
+        // orange and red rows come with a way out
+        match m.state {
+            ModState::MissingEntry => lines.push("Double click or SPACE to enable it and add it to the load order".into()),
+            ModState::NotInstalled => lines.push("Drop the mod archive here to install it, or DELETE to remove the entry".into()),
+            ModState::Enabled | ModState::Disabled => (),
+        }
+        for err in self.version_errors.iter().filter(|e| e.mod_name == m.name()) {
+            lines.push(format!(
+                "Needs {} {}, found {}",
+                err.require,
+                err.constraint,
+                err.found.as_deref().unwrap_or("no version"),
+            ));
+        }
+        // requirements that aren't in the list at all (like dmf) are left to the loader
+        for name in m.meta.require() {
+            let state = self.lorder.mods.iter()
+                .find(|r| r.name() == name)
+                .map(|r| &r.state);
+            match state {
+                None | Some(ModState::Enabled) => (),
+                Some(ModState::NotInstalled) => lines.push(format!("Requires {name}, not installed")),
+                Some(_) => lines.push(format!("Requires {name}, not enabled")),
+            }
+        }
+        lines
+    }
+
     fn update_mouse(
         &mut self,
         pos: (i32, i32),
@@ -785,14 +2254,15 @@ impl ModListWidget {
                     && (entry != Entry::Mod(clicked) || entry == Entry::None)
                 {
                     self.can_hover = false;
-                    self.can_drag = true;
+                    // slots don't line up with the load order while filtering or sorting
+                    self.can_drag = self.filter.is_empty() && self.view_sort.is_none();
                     return true;
                 } else if self.get_entry(pos) != self.get_entry(old_pos) {
                     return true;
                 }
             } else if self.can_drag {
-                let (_, slot1) = self.get_slot(pos);
-                let (_, slot2) = self.get_slot(old_pos);
+                let (_, slot1) = self.get_slot_row(pos);
+                let (_, slot2) = self.get_slot_row(old_pos);
                 if slot1 != slot2 {
                     return true;
                 }
@@ -802,42 +2272,529 @@ impl ModListWidget {
         false
     }
 
-    fn update_scroll(
-        &mut self,
+    fn content_y(&self, y: i32) -> i32 {
+        let top = Self::LIST_TOP as i32;
+        y.clamp(top, top + Self::LIST_HEIGHT as i32) - top + self.scroll
+    }
+
+    // selects every mod row the box touches
+    fn update_box_select(&mut self) {
+        let Some(((_, anchor), base)) = &self.box_select else {
+            return;
+        };
+        let y = self.content_y(self.mouse_pos.1);
+        let first = (*anchor).min(y) / self.item_height;
+        let last = (*anchor).max(y) / self.item_height;
+
+        let mut selected = base.clone();
+        for &entry in self.rows().iter().take(last as usize + 1).skip(first as usize) {
+            if let Entry::Mod(i) = entry
+                && !selected.contains(&i)
+            {
+                selected.push(i);
+            }
+        }
+        self.selected = selected;
+    }
+
+    // translucent names of the dragged mods next to the cursor
+    fn render_drag_ghost(&self, context: &mut super::DrawScope) {
+        const MAX_LINES: usize = 4;
+        const WIDTH: f32 = 200.0;
+        const ALPHA: f32 = 0.6;
+
+        let mut selected = self.selected.clone();
+        selected.sort();
+        let mut lines: Vec<String> = selected.iter()
+            .take(MAX_LINES)
+            .map(|&i| self.lorder.mods[i].display_name().to_string())
+            .collect();
+        if selected.len() > MAX_LINES {
+            lines.push(format!("+{} more", selected.len() - MAX_LINES));
+        }
+
+        let (x, y) = self.mouse_pos;
+        let (x, y) = (x as f32 + 12.0, y as f32 + 4.0);
+        let height = (lines.len() as i32 * self.item_height) as f32;
+        let faded = context.push_layer(ALPHA, [x, y, x + WIDTH, y + height]).is_ok();
+        self.brush.set_color(&self.theme.background);
+        context.fill_rounded_rect(&self.brush, [x, y, x + WIDTH, y + height], 2.0);
+
+        self.brush.set_color(&self.theme.text);
+        context.push_axis_aligned_clip(&[x, y, x + WIDTH - 6.0, y + height]);
+        let mut o = y;
+        for line in lines {
+            context.draw_text(
+                line.as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[x + 6.0, o, x + WIDTH - 6.0, o + self.item_height as f32],
+            );
+            o += self.item_height as f32;
+        }
+        context.pop_axis_aligned_clip();
+        if faded {
+            context.pop_layer();
+        }
+    }
+
+    fn render_box_select(&self, context: &mut super::DrawScope) {
+        let Some(((x, y), _)) = self.box_select else {
+            return;
+        };
+        let y = y - self.scroll + Self::LIST_TOP as i32;
+        let (mx, my) = self.mouse_pos;
+        let rect = [x.min(mx), y.min(my), x.max(mx), y.max(my)].map(|b| b as f32);
+        self.brush.set_color(&self.theme.hover);
+        context.fill_rounded_rect(&self.brush, rect, 0.0);
+        self.brush.set_color(&self.theme.border);
+        context.draw_rounded_rect(&self.brush, rect, 0.0, 1.0);
+    }
+
+    // rows per wheel notch, the system setting times `scroll_speed` or a page
+    // with SHIFT held
+    fn wheel_rows(&self, page: bool) -> u32 {
+        use windows::Win32::UI::WindowsAndMessaging::*;
+        // `WHEEL_PAGESCROLL` isn't exported
+        const PAGE_SCROLL: u32 = u32::MAX;
+
+        let page_rows = (Self::LIST_HEIGHT / self.item_height as u32).saturating_sub(1).max(1);
+        let mut lines = 3u32;
+        unsafe {
+            let _ = SystemParametersInfoW(
+                SPI_GETWHEELSCROLLLINES,
+                0,
+                Some(&mut lines as *mut u32 as *mut _),
+                Default::default(),
+            );
+        }
+        if page || lines == PAGE_SCROLL {
+            page_rows
+        } else {
+            lines.saturating_mul(self.config.scroll_speed).min(page_rows)
+        }
+    }
+
+    // also stops smooth scrolling
+    fn set_scroll(&mut self, scroll: i32) {
+        self.scroll = scroll;
+        self.scroll_target = None;
+    }
+
+    // next scroll offset from `from`, `None` past either end of the list
+    fn scroll_step(
+        &self,
+        from: i32,
         delta: i32,
         align: bool,
-    ) -> bool {
-        let mut scroll = self.scroll;
+    ) -> Option<i32> {
+        let mut scroll = from;
         if align {
             if delta < 0 {
-                let bottom = self.scroll + Self::HEIGHT_INNER as i32;
+                let bottom = from + Self::LIST_HEIGHT as i32;
                 scroll += self.item_height;
                 let diff = bottom % self.item_height;
                 if diff != 0 {
                     scroll += self.item_height - diff;
                 }
             } else {
-                scroll = scroll.saturating_sub(self.item_height + self.scroll % self.item_height);
+                scroll = scroll.saturating_sub(self.item_height + from % self.item_height);
             }
         } else {
             scroll += delta;
         }
 
-        let bottom_item = (scroll + Self::HEIGHT_INNER as i32 + self.item_height - 1) / self.item_height;
-        let max_item = i32::try_from(self.builtins.len() + self.lorder.mods.len()).unwrap();
-        if scroll >= 0 && scroll != self.scroll && bottom_item <= max_item {
-            self.scroll = scroll;
-            true
+        let bottom_item = (scroll + Self::LIST_HEIGHT as i32 + self.item_height - 1) / self.item_height;
+        let max_item = i32::try_from(self.rows().len()).unwrap();
+        (scroll >= 0 && scroll != from && bottom_item <= max_item).then_some(scroll)
+    }
+
+    fn update_scroll(
+        &mut self,
+        delta: i32,
+        align: bool,
+    ) -> bool {
+        match self.scroll_step(self.scroll, delta, align) {
+            Some(scroll) => {
+                self.set_scroll(scroll);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // patching is harmless but removing the patch stops mods from loading
+    fn request_toggle_patch(&mut self, control: &mut super::ControlScope) {
+        if self.is_patched {
+            ConfirmWidget::show(
+                control,
+                vec![
+                    "Remove the mod loader patch?".into(),
+                    "Mods won't load until the game is patched again.".into(),
+                ],
+                "Unpatch",
+                ModListEvent::TogglePatchConfirmed as u32,
+                None,
+            );
         } else {
-            false
+            self.toggle_patch(control);
         }
     }
 
-    fn toggle_patch(&mut self) {
+    fn toggle_patch(&mut self, control: &mut super::ControlScope) {
         if let Err(err) = crate::patch::toggle_patch(&self.root, !self.is_patched) {
             crate::log::log(&format!("error while toggling patch: {err:?}"));
         }
-        self.mount().unwrap();
+        self.refresh();
+        self.deliver_patched(control);
+    }
+
+    // the button shows the patch state, mounts outside of events have it
+    // sent with the next one
+    fn deliver_patched(&mut self, control: &mut super::ControlScope) {
+        if self.patched_sent != Some(self.is_patched) {
+            self.patched_sent = Some(self.is_patched);
+            control.deliver(Control::BUTTON_WIDGET, self.is_patched);
+        }
+    }
+
+    fn vanilla_marker(&self) -> PathBuf {
+        self.mods_path.join(crate::manifest::MANIFEST_DIR).join(Self::VANILLA_ONCE)
+    }
+
+    // sets the patch for the next game start, the launcher's `Play` still
+    // has to start it
+    //
+    // vanilla leaves a marker so `restore_patch` patches again on the next
+    // launcher start
+    fn prepare_launch(&mut self, control: &mut super::ControlScope, modded: bool) {
+        if self.is_patched != modded {
+            self.toggle_patch(control);
+        }
+        let marker = self.vanilla_marker();
+        let res = match modded {
+            false if !self.is_patched => std::fs::create_dir_all(marker.parent().unwrap())
+                .and_then(|()| std::fs::write(&marker, b"")),
+            _ => match std::fs::remove_file(&marker) {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+                res => res,
+            },
+        };
+        if let Err(err) = res {
+            crate::log::log(&format!("failed to update {}: {err}", marker.display()));
+        }
+
+        self.notice = Some(match (modded, self.is_patched) {
+            (true, true) => Notice::info("Mods will load, press Play to start the game"),
+            (false, false) => Notice::info("Mods are off for the next start, the patch returns when the launcher opens again"),
+            _ => Notice::error("failed to change the mod loader patch, see the log"),
+        });
+    }
+
+    // undoes `prepare_launch(false)`, only called on start since a refresh
+    // right after would patch again before the game is started
+    pub fn restore_patch(&mut self) {
+        let marker = self.vanilla_marker();
+        if !marker.exists() {
+            return;
+        }
+        if !crate::patch::is_patched(&self.root)
+            && let Err(err) = crate::patch::toggle_patch(&self.root, true)
+        {
+            crate::log::log(&format!("failed to restore patch after vanilla launch: {err}"));
+            return;
+        }
+        if let Err(err) = std::fs::remove_file(&marker) {
+            crate::log::log(&format!("failed to remove {}: {err}", marker.display()));
+        }
+    }
+
+    fn preview_rect(&self) -> [u32; 4] {
+        let item_height = self.item_height as u32;
+        [
+            Self::MARGIN_X + Self::MOD_ENTRY_LENGTH as u32 + 16,
+            Self::MARGIN_Y + item_height,
+            Self::MARGIN_X + Self::WIDTH_INNER - 8,
+            Self::MARGIN_Y + Self::HEIGHT_INNER - item_height,
+        ]
+    }
+
+    fn preview_row(&self, pos: (i32, i32), rows: usize) -> Option<usize> {
+        let (x, y) = pos;
+        let [left, top, right, bottom] = self.preview_rect().map(|b| b as i32);
+        if x < left || x >= right || y < top || y >= bottom {
+            return None;
+        }
+
+        let row = ((y - top) / self.item_height) as usize;
+        (row < rows).then_some(row)
+    }
+
+    // header above the pane, then clickable `options` rows followed by `lines`
+    fn render_prompt(
+        &self,
+        context: &mut super::DrawScope,
+        header: (&str, [f32; 4]),
+        options: &[(&str, [f32; 4])],
+        lines: &[String],
+    ) {
+        let item_height = self.item_height as u32;
+        let [left, top, right, bottom] = self.preview_rect();
+
+        self.brush.set_color(&header.1);
+        context.draw_text(
+            header.0.as_ref(),
+            &self.text_format,
+            &self.brush,
+            &[left, top - item_height, right, top].map(|b| b as f32),
+        );
+
+        context.push_axis_aligned_clip(&[
+            left as f32,
+            top as f32,
+            right as f32,
+            bottom as f32,
+        ]);
+
+        let mut offset = top;
+        for (text, color) in options {
+            self.brush.set_color(color);
+            context.draw_text(
+                text.as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[left, offset, right, offset + item_height].map(|b| b as f32),
+            );
+            offset += item_height;
+        }
+
+        self.brush.set_color(&self.theme.enabled);
+        for text in lines {
+            if offset >= bottom {
+                break;
+            }
+
+            context.draw_text(
+                text.as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[left, offset, right, offset + item_height].map(|b| b as f32),
+            );
+            offset += item_height;
+        }
+
+        context.pop_axis_aligned_clip();
+    }
+
+    // changed files and file conflicts of the selected mods
+    fn render_details(&self, context: &mut super::DrawScope) {
+        let selected: Vec<&str> = self.selected.iter()
+            .filter_map(|i| self.lorder.mods.get(*i))
+            .map(|m| m.name())
+            .collect();
+
+        let mut lines = Vec::new();
+        for (name, changes) in &self.integrity {
+            if !selected.contains(&name.as_str()) {
+                continue;
+            }
+            for change in changes {
+                let kind = match change {
+                    Change::Modified(_) => "modified",
+                    Change::Missing(_) => "missing",
+                };
+                lines.push(format!("{kind} {}  ({name})", change.path()));
+            }
+        }
+        let changed = lines.len();
+
+        for conflict in &self.file_conflicts {
+            if conflict.mods.iter().any(|n| selected.contains(&n.as_str())) {
+                lines.push(format!("{}  ({})", conflict.path, conflict.mods.join(", ")));
+            }
+        }
+        let conflicts = lines.len() - changed;
+
+        let header = match (changed, conflicts) {
+            (0, 0) => return,
+            (0, _) => format!("{conflicts} file conflicts"),
+            (_, 0) => format!("{changed} changed files"),
+            _ => format!("{changed} changed files, {conflicts} file conflicts"),
+        };
+        self.render_prompt(context, (&header, self.theme.missing_entry), &[], &lines);
+    }
+
+    // `+` or `-` before folders and the size on the right, `[left, top, right]`
+    // is where depth 0 starts
+    fn draw_tree_row(
+        &self,
+        context: &mut super::DrawScope,
+        row: &TreeRow,
+        depth: usize,
+        [left, top, right]: [u32; 3],
+    ) {
+        let left = left + depth as u32 * Self::TREE_INDENT;
+        let bottom = top + self.item_height as u32;
+        if row.kind.is_dir() {
+            context.draw_text(
+                (if row.expanded { "-" } else { "+" }).as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[left, top, right, bottom].map(|b| b as f32),
+            );
+        }
+
+        let text = match row.kind.is_dir() {
+            true => format!("{}/", row.name),
+            false => row.name.to_string(),
+        };
+        context.draw_text(
+            text.as_ref(),
+            &self.text_format,
+            &self.brush,
+            &[left + Self::TREE_INDENT, top, right, bottom].map(|b| b as f32),
+        );
+
+        let mut size = String::new();
+        format_size(&mut size, row.size);
+        self.text_format.set_text_alignment(crate::dxgi::Alignment::Max).unwrap();
+        context.draw_text(
+            size.as_ref(),
+            &self.text_format,
+            &self.brush,
+            &[left, top, right, bottom].map(|b| b as f32),
+        );
+        self.text_format.set_text_alignment(crate::dxgi::Alignment::Min).unwrap();
+    }
+
+    // help in the middle of the mod column when nothing is installed yet
+    fn render_empty(&self, context: &mut super::DrawScope) {
+        let item_height = self.item_height as u32;
+        let left = Self::MARGIN_X;
+        let right = left + Self::MOD_ENTRY_LENGTH as u32;
+        let mut top = Self::LIST_TOP + (Self::LIST_HEIGHT - item_height * 2) / 2;
+
+        self.text_format.set_text_alignment(crate::dxgi::Alignment::Mid).unwrap();
+        for (text, color) in [
+            ("Drag .zip mod archives here to install", self.theme.text),
+            ("get mods from Nexus Mods", self.theme.secondary_text),
+        ] {
+            self.brush.set_color(&color);
+            context.draw_text(
+                text.as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[left, top, right, top + item_height].map(|b| b as f32),
+            );
+            top += item_height;
+        }
+        self.text_format.set_text_alignment(crate::dxgi::Alignment::Min).unwrap();
+    }
+
+    fn render_selection(&self, context: &mut super::DrawScope) {
+        let item_height = self.item_height as u32;
+        let [left, top, right, bottom] = self.preview_rect();
+
+        let mut text = String::from("select mods to install (");
+        format_size(&mut text, self.drag_drop.selection_size());
+        text.push(')');
+        self.brush.set_color(&self.theme.builtin);
+        context.draw_text(
+            text.as_ref(),
+            &self.text_format,
+            &self.brush,
+            &[left, top - item_height, right, top].map(|b| b as f32),
+        );
+
+        context.push_axis_aligned_clip(&[
+            left as f32,
+            top as f32,
+            right as f32,
+            bottom as f32,
+        ]);
+
+        let mut offset = top;
+        for row in self.drag_drop.selection_tree().iter().skip(self.drag_drop.tree_scroll) {
+            if offset >= bottom {
+                break;
+            }
+
+            let checked = self.drag_drop.selection.iter()
+                .find(|(name, _)| row.depth == 1 && name == row.name)
+                .map(|(_, checked)| *checked);
+            let color = match checked {
+                Some(true) => self.theme.enabled,
+                Some(false) => self.theme.disabled,
+                None => self.theme.secondary_text,
+            };
+            self.brush.set_color(&color);
+            if let Some(checked) = checked {
+                context.draw_text(
+                    (if checked { "[x]" } else { "[  ]" }).as_ref(),
+                    &self.text_format,
+                    &self.brush,
+                    &[left, offset, right, offset + item_height].map(|b| b as f32),
+                );
+            }
+            self.draw_tree_row(context, row, row.depth - 1, [left + Self::TREE_CHECK_WIDTH, offset, right]);
+            offset += item_height;
+        }
+
+        for (text, color) in [
+            ("Install", self.theme.builtin),
+            ("Cancel", self.theme.disabled),
+        ] {
+            self.brush.set_color(&color);
+            context.draw_text(
+                text.as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[left, offset, right, offset + item_height].map(|b| b as f32),
+            );
+            offset += item_height;
+        }
+
+        context.pop_axis_aligned_clip();
+    }
+
+    fn render_progress(&self, context: &mut super::DrawScope) {
+        let item_height = self.item_height as u32;
+        let [left, top, right, _] = self.preview_rect();
+
+        // nothing reported until the first percent is copied
+        let (copied, total) = self.drag_drop.progress.unwrap_or((0, 0));
+        let fraction = if total == 0 { 0.0 } else { copied as f32 / total as f32 };
+
+        let mut text = format!("installing {:.0}%  ", fraction * 100.0);
+        format_size(&mut text, copied);
+        text.push_str(" / ");
+        format_size(&mut text, total);
+        self.brush.set_color(&self.theme.builtin);
+        context.draw_text(
+            text.as_ref(),
+            &self.text_format,
+            &self.brush,
+            &[left, top - item_height, right, top].map(|b| b as f32),
+        );
+
+        let rect = [
+            left as f32,
+            (top + 4) as f32,
+            right as f32,
+            (top + item_height - 4) as f32,
+        ];
+        self.brush.set_color(&self.theme.disabled);
+        context.draw_rounded_rect(&self.brush, rect, 2.0, 1.0);
+        if fraction > 0.0 {
+            let fill = [
+                rect[0] + 2.0,
+                rect[1] + 2.0,
+                rect[0] + 2.0 + (rect[2] - rect[0] - 4.0) * fraction,
+                rect[3] - 2.0,
+            ];
+            self.brush.set_color(&self.theme.builtin);
+            context.fill_rounded_rect(&self.brush, fill, 1.0);
+        }
     }
 
     pub fn send(
@@ -848,10 +2805,20 @@ impl ModListWidget {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, PartialEq)]
+enum Column {
+    Name,
+    Version,
+    State,
+    Installed,
+}
+
+#[derive(Clone, Copy, PartialEq)]
 enum Entry {
     Mod(usize),
     Builtin(usize),
+    // header above the first mod of a group
+    Group(usize),
     None,
 }
 
@@ -859,46 +2826,85 @@ impl super::Widget for ModListWidget {
     fn config(&self) -> WidgetConfig {
         WidgetConfig {
             listen_double_click: true,
+            focusable: true,
+            blur_behind: true,
+            ..Default::default()
         }
     }
 
     fn rect(&self, width: u32, _height: u32) -> [u32; 4] {
         let size = unsafe { self.background.GetPixelSize() };
+        // large scales can leave less room than the list needs
+        let right = (width + Self::MARGIN_X)
+            .saturating_sub(Self::MARGIN_RIGHT)
+            .max(size.width);
         [
-            width + Self::MARGIN_X - Self::MARGIN_RIGHT - size.width,
+            right - size.width,
             Self::MARGIN_TOP,
-            width + Self::MARGIN_X - Self::MARGIN_RIGHT,
+            right,
             Self::MARGIN_TOP + size.height,
         ]
     }
 
+    // renaming, filtering and installs are cancelled first
+    fn takes_escape(&self) -> bool {
+        self.rename.is_some()
+            || self.filter_focus
+            || self.drag_drop.state != DragDropState::None
+            || self.drag_drop.error.is_some()
+            || self.notice.is_some()
+    }
+
     fn handle_event(
         &mut self,
         control: &mut super::ControlScope,
         event: Event,
     ) {
+        if event.kind != EventKind::Hide {
+            self.show_error(control);
+        }
+        self.send_theme(control);
+        self.deliver_patched(control);
+        if self.dispatcher.is_none() {
+            // mounts before the first event couldn't scan
+            self.dispatcher = Some(Arc::from(control.dispatcher()));
+            self.scan_files();
+        }
+
         if let EventKind::Custom(custom) = event.kind {
             if let Some(event) = ModListEvent::from_u32(custom) {
                 match event {
                     ModListEvent::ToggleSelected => {
                         if self.toggle_selected() {
-                            self.update_mod_lorder();
+                            self.save_mod_lorder();
                             control.redraw();
                         }
                     }
                     ModListEvent::OpenSelected => self.open_selected(),
                     ModListEvent::DragDropPoll => {
                         if self.drag_drop.poll() {
+                            if self.paste_pending && self.drag_drop.state != DragDropState::Listing {
+                                self.paste_pending = false;
+                                if self.drag_drop.state == DragDropState::Dragging
+                                    && self.drag_drop.view.is_some()
+                                {
+                                    self.drop_files(control, false, false);
+                                }
+                            }
+
                             if self.drag_drop.state == DragDropState::Copied {
-                                self.selected.clear();
-                                self.mount().unwrap();
+                                self.refresh();
+                                self.write_manifests();
 
-                                if let Some(view) = &self.drag_drop.view
+                                if self.config.auto_enable
+                                    && let Some(view) = &self.drag_drop.view
                                     && let Some(mods) = view.list().list("mods")
                                 {
                                     let mut enable = Vec::new();
-                                    for (name, ty, depth) in mods.iter() {
-                                        if depth == 0 && ty.is_dir() {
+                                    for (name, ty, depth, _size) in mods.iter() {
+                                        if depth == 0 && ty.is_dir()
+                                            && !self.drag_drop.skipped.iter().any(|s| s == name)
+                                        {
                                             let res = self.lorder.mods.iter()
                                                 .enumerate()
                                                 .find(|(_, m)| m.name() == name && m.state == ModState::Disabled);
@@ -912,33 +2918,349 @@ impl super::Widget for ModListWidget {
                                         self.toggle_mod(*i, Some(true));
                                     }
                                     if !enable.is_empty() {
-                                        self.update_mod_lorder();
+                                        self.save_mod_lorder();
                                     }
                                 }
                             }
-
+
+                            control.redraw();
+                        }
+                    }
+                    ModListEvent::SortMods
+                    | ModListEvent::SortModsStable
+                    | ModListEvent::SortModsAlphabetical => {
+                        let mode = match event {
+                            ModListEvent::SortModsStable => SortMode::Stable,
+                            ModListEvent::SortModsAlphabetical => SortMode::Alphabetical,
+                            _ => SortMode::Full,
+                        };
+                        self.sort_preview = None;
+                        match self.lorder.sort_preview(mode) {
+                            Err(cycles) => {
+                                let mut text = String::from("circular dependencies found:");
+                                for cycle in &cycles {
+                                    text.push_str("\n  ");
+                                    for name in cycle {
+                                        text.push_str(name);
+                                        text.push_str(" -> ");
+                                    }
+                                    text.push_str(&cycle[0]);
+                                }
+                                crate::log::log(&text);
+                                self.notice = Some(Notice::error(text));
+                            }
+                            Ok(preview) => {
+                                for (mod_name, requires) in &preview.missing {
+                                    crate::log::log(&format!(
+                                        "mod {mod_name} missing dependency \"{requires}\""));
+                                }
+                                // confirmed from the preview pane
+                                if !preview.moved.is_empty() {
+                                    self.sort_preview = Some(preview);
+                                }
+                            }
+                        }
+                        for err in &self.version_errors {
+                            crate::log::log(&format!(
+                                "mod {} requires {} {} but found {}",
+                                err.mod_name,
+                                err.require,
+                                err.constraint,
+                                err.found.as_deref().unwrap_or("no version"),
+                            ));
+                        }
+                        control.redraw();
+                    }
+                    ModListEvent::ImportLoadOrder
+                    | ModListEvent::ExportLoadOrder => {
+                        let export = matches!(event, ModListEvent::ExportLoadOrder);
+                        let notify = control.dispatcher();
+                        std::thread::spawn(move || {
+                            match super::file_dialog::pick_text_file(export) {
+                                Ok(Some(path)) => {
+                                    LOAD_ORDER_MAILBOX.send(0, (export, path));
+                                    notify(ModListEvent::LoadOrderFile as u32);
+                                }
+                                Ok(None) => (),
+                                Err(err) => crate::log::log(&format!("file dialog failed: {err:?}")),
+                            }
+                        });
+                    }
+                    ModListEvent::LoadOrderFile => {
+                        if let Some((_, (export, path))) = LOAD_ORDER_MAILBOX.recv() {
+                            self.notice = match self.load_order_file(export, &path) {
+                                Ok(msg) => msg.map(Notice::error),
+                                Err(err) => {
+                                    let text = format!("failed to read/write {}: {err}", path.display());
+                                    crate::log::log(&text);
+                                    Some(Notice::error(text))
+                                }
+                            };
+                            control.redraw();
+                        }
+                    }
+                    ModListEvent::RestoreLoadOrder => {
+                        ConfirmWidget::show(
+                            control,
+                            vec![
+                                "Replace mod_load_order.txt with its newest backup?".into(),
+                                "Restoring again steps further back.".into(),
+                            ],
+                            "Restore",
+                            ModListEvent::RestoreConfirmed as u32,
+                            None,
+                        );
+                    }
+                    ModListEvent::RestoreConfirmed
+                    | ModListEvent::UndoRestore => {
+                        let stamp = Self::backup_stamp();
+                        let res = match event {
+                            ModListEvent::UndoRestore => crate::backup::undo_restore(&self.mods_path, &stamp),
+                            _ => crate::backup::restore(&self.mods_path, &stamp),
+                        };
+                        self.notice = match (res, event) {
+                            (Ok(true), ModListEvent::UndoRestore) => Some(Notice::info("restore undone")),
+                            (Ok(true), _) => Some(Notice::info("restored the newest backup, Undo Restore puts the replaced load order back")),
+                            (Ok(false), ModListEvent::UndoRestore) => Some(Notice::info("no restore to undo")),
+                            (Ok(false), _) => Some(Notice::info("no load order backups to restore")),
+                            (Err(err), _) => {
+                                let text = format!("failed to restore load order: {err}");
+                                crate::log::log(&text);
+                                Some(Notice::error(text))
+                            }
+                        };
+                        self.selected.clear();
+                        self.sort_preview = None;
+                        self.disable_prompt = None;
+                        self.refresh();
+                        control.redraw();
+                    }
+                    ModListEvent::VerifyMods => {
+                        let mods_path = self.mods_path.clone();
+                        let folders: Vec<(String, String)> = self.lorder.mods.iter()
+                            .filter(|m| m.state != ModState::NotInstalled)
+                            .filter_map(|m| Some((m.name().to_string(), m.meta.folder()?.to_string())))
+                            .collect();
+                        let notify = control.dispatcher();
+                        std::thread::spawn(move || {
+                            let mut out = Vec::new();
+                            let mut unknown = Vec::new();
+                            for (name, folder) in folders {
+                                match crate::manifest::verify(&mods_path, &folder) {
+                                    Ok(Some(changes)) if !changes.is_empty() => out.push((name, changes)),
+                                    Ok(Some(_)) => (),
+                                    // installed outside modtide, there's nothing to compare with
+                                    Ok(None) => unknown.push(name),
+                                    Err(err) => crate::log::log(&format!("failed to verify {name}: {err}")),
+                                }
+                            }
+                            VERIFY_MAILBOX.send(0, (out, unknown));
+                            notify(ModListEvent::VerifyDone as u32);
+                        });
+                    }
+                    ModListEvent::MenuStates => {
+                        control.deliver(Control::DROPDOWN_WIDGET, self.menu_states());
+                    }
+                    ModListEvent::FilesScanned => {
+                        if let Some((_, files)) = FILES_MAILBOX.recv() {
+                            self.file_conflicts = self.lorder.conflicts(&files);
+                            // may be sorted by state
+                            self.update_rows();
+                            control.redraw();
+                        }
+                    }
+                    ModListEvent::VerifyDone => {
+                        if let Some((_, (integrity, unknown))) = VERIFY_MAILBOX.recv() {
+                            for (name, changes) in &integrity {
+                                crate::log::log(&format!("mod {name} has {} changed files", changes.len()));
+                            }
+                            let mut lines = Vec::new();
+                            if !integrity.is_empty() {
+                                lines.push(format!(
+                                    "{} mods have modified or missing files, select one for details",
+                                    integrity.len(),
+                                ));
+                            }
+                            if !unknown.is_empty() {
+                                lines.push(format!(
+                                    "{} mods weren't installed with modtide and can't be verified: {}",
+                                    unknown.len(),
+                                    unknown.join(", "),
+                                ));
+                            }
+                            self.notice = Some(if lines.is_empty() {
+                                Notice::info("no modified files found")
+                            } else if integrity.is_empty() {
+                                Notice::info(lines.join("\n"))
+                            } else {
+                                Notice::error(lines.join("\n"))
+                            });
+                            self.integrity = integrity;
+                            self.update_rows();
+                            control.redraw();
+                        }
+                    }
+                    // installs finish with their own mount
+                    ModListEvent::ModsChanged if matches!(
+                        self.drag_drop.state,
+                        DragDropState::None | DragDropState::Dragging | DragDropState::Listing,
+                    ) => {
+                        self.refresh();
+                        control.redraw();
+                    }
+                    ModListEvent::ModsChanged => (),
+                    ModListEvent::RenameSelected => {
+                        if let [i] = self.selected[..]
+                            && let Some(m) = self.lorder.mods.get(i)
+                            && m.state != ModState::NotInstalled
+                        {
+                            self.rename = Some((i, m.name().to_string()));
+                            control.redraw();
+                        }
+                    }
+                    ModListEvent::DeleteSelected => {
+                        if !self.selected.is_empty() {
+                            self.selected.sort();
+                            self.pending_delete = self.selected.iter()
+                                .filter_map(|i| self.lorder.mods.get(*i))
+                                .map(|m| m.name().to_string())
+                                .collect();
+                            let mut lines = vec![format!(
+                                "Delete {} mods from the mods folder?",
+                                self.pending_delete.len(),
+                            )];
+                            lines.extend(self.pending_delete.iter().cloned());
+                            ConfirmWidget::show(control, lines, "Delete", ModListEvent::DeleteConfirmed as u32, None);
+                        }
+                    }
+                    ModListEvent::DeleteConfirmed => {
+                        let names = core::mem::take(&mut self.pending_delete);
+                        if let Err(err) = self.delete_mods(&names) {
+                            let text = format!("failed to delete mods: {err}");
+                            crate::log::log(&text);
+                            self.notice = Some(Notice::error(text));
+                        }
+                        control.redraw();
+                    }
+                    ModListEvent::EnableAll
+                    | ModListEvent::DisableAll => {
+                        let enable = matches!(event, ModListEvent::EnableAll);
+                        self.backup_mod_lorder();
+                        let mut changed = false;
+                        for i in 0..self.lorder.mods.len() {
+                            changed |= self.toggle_mod(i, Some(enable));
+                        }
+                        if changed {
+                            self.save_mod_lorder();
+                            control.redraw();
+                        }
+                    }
+                    ModListEvent::InvertSelection => {
+                        self.selected = (0..self.lorder.mods.len())
+                            .filter(|i| !self.selected.contains(i))
+                            .collect();
+                        control.redraw();
+                    }
+                    ModListEvent::Refresh => {
+                        self.refresh();
+                        control.redraw();
+                    }
+                    ModListEvent::TogglePatch => {
+                        self.request_toggle_patch(control);
+                        control.redraw();
+                    }
+                    ModListEvent::LaunchVanilla if self.is_patched => {
+                        // unpatching asks first like `request_toggle_patch`
+                        ConfirmWidget::show(
+                            control,
+                            vec![
+                                "Remove the mod loader patch for the next start?".into(),
+                                "It's patched again when the launcher opens.".into(),
+                            ],
+                            "Unpatch",
+                            ModListEvent::LaunchVanillaConfirmed as u32,
+                            None,
+                        );
+                        control.redraw();
+                    }
+                    ModListEvent::LaunchModded
+                    | ModListEvent::LaunchVanilla
+                    | ModListEvent::LaunchVanillaConfirmed => {
+                        self.prepare_launch(control, event == ModListEvent::LaunchModded);
+                        control.redraw();
+                    }
+                    ModListEvent::TogglePatchConfirmed => {
+                        self.toggle_patch(control);
+                        control.redraw();
+                    }
+                    ModListEvent::InstallConfirmed => {
+                        if self.drag_drop.state == DragDropState::Confirming {
+                            self.drag_drop.start();
+                        }
+                        control.redraw();
+                    }
+                    ModListEvent::InstallCanceled => {
+                        if self.drag_drop.state == DragDropState::Confirming {
+                            self.drag_drop.clear();
+                        }
+                        control.redraw();
+                    }
+                    ModListEvent::BrowseDarktide => Self::open(&self.root),
+                    ModListEvent::BrowseBuiltin | ModListEvent::OpenBuiltinPage => {
+                        if let Some((path, _, url)) = self.builtin_info(self.builtin_menu) {
+                            match event {
+                                ModListEvent::BrowseBuiltin => {
+                                    let path = self.mods_path.join(path);
+                                    Self::open(path.parent().unwrap());
+                                }
+                                _ => Self::open(Path::new(url)),
+                            }
+                        }
+                    }
+                    ModListEvent::ShowBuiltinVersion => {
+                        if let Some((name, version)) = self.builtins.get(self.builtin_menu) {
+                            self.notice = Some(Notice::info(match version {
+                                Some(version) => format!("{name} version {version}"),
+                                None => format!("{name} has no version"),
+                            }));
                             control.redraw();
                         }
                     }
-                    ModListEvent::SortMods => {
-                        match self.lorder.sort() {
-                            None => crate::log::log("circular dependencies found"),
-                            Some(missing) if !missing.is_empty() => {
-                                for (mod_name, requires) in missing {
-                                    crate::log::log(&format!(
-                                        "mod {mod_name} missing dependency \"{requires}\""));
-                                }
-                            }
-                            _ => (),
+                    ModListEvent::ShowSettings => {
+                        control.show_widget(Control::SETTINGS_WIDGET);
+                        // a retry prompt from `show_error` stays in front
+                        control.lower(Control::SETTINGS_WIDGET);
+                    }
+                    ModListEvent::ShowAbout => {
+                        ConfirmWidget::show(control, self.about(), "Copy", ModListEvent::CopyAbout as u32, None);
+                    }
+                    ModListEvent::CopyAbout => {
+                        if let Err(err) = super::clipboard::set_text(control.hwnd, &self.about().join("\r\n")) {
+                            crate::log::log(&format!("failed to copy about info: {err}"));
+                        }
+                    }
+                    ModListEvent::ConfigChanged => {
+                        let depth = self.config.scan_depth;
+                        self.load_config();
+                        if self.config.scan_depth != depth {
+                            self.refresh();
                         }
-                        self.update_mod_lorder();
+                        control.set_scale(self.scale());
+                        control.set_toggle_key(self.toggle_key());
+                        self.send_theme(control);
                         control.redraw();
                     }
-                    ModListEvent::TogglePatch => {
-                        self.toggle_patch();
+                    ModListEvent::RetryMount => {
+                        self.notice = None;
+                        self.refresh();
+                        self.show_error(control);
+                        control.redraw();
+                    }
+                    ModListEvent::RetrySave => {
+                        self.save_mod_lorder();
+                        self.show_error(control);
                         control.redraw();
                     }
-                    ModListEvent::BrowseDarktide => Self::open(&self.root),
                     ModListEvent::BrowseLogs => {
                         // TODO: error reporting
                         if let Some(appdata) = std::env::var_os("APPDATA")
@@ -974,21 +3296,49 @@ impl super::Widget for ModListWidget {
                 self.drag_drop.mouse_enter(drag_files, move || {
                     notify(ModListEvent::DragDropPoll as u32);
                 });
+                if self.drag_drop.state == DragDropState::Listing {
+                    self.spinner = 0;
+                    control.tick(true);
+                }
                 control.redraw();
             }
             EventKind::MouseEnter(false) => {
-                if self.drag_drop.state != DragDropState::None {
+                if !matches!(
+                    self.drag_drop.state,
+                    DragDropState::None
+                        | DragDropState::Selecting
+                        | DragDropState::Confirming
+                        | DragDropState::Copying,
+                ) {
                     self.drag_drop.clear();
                     control.redraw();
                 }
             }
 
             EventKind::MouseLeave => {
+                self.tooltip_entry = Entry::None;
                 if self.update_mouse(self.mouse_pos) {
                     control.redraw();
                 }
             }
 
+            EventKind::MouseMove(_) if self.box_select.is_some() => {
+                self.mouse_pos = (x, y);
+                // scrolls past the edges like dragging mods
+                let delta = if y < Self::LIST_TOP as i32 + self.item_height {
+                    -1
+                } else if y > bottom - self.item_height {
+                    1
+                } else {
+                    0
+                };
+                if delta != 0 {
+                    self.update_scroll(delta, false);
+                }
+                self.update_box_select();
+                control.redraw();
+            }
+
             EventKind::MouseMove(is_dragging) => {
                 if !self.can_drag {
                     self.can_hover = !is_dragging;
@@ -1010,8 +3360,31 @@ impl super::Widget for ModListWidget {
                 if self.update_mouse((x, y)) {
                     control.redraw();
                 }
+
+                let entry = match self.can_hover {
+                    true => self.get_entry((x, y)),
+                    false => Entry::None,
+                };
+                if entry != self.tooltip_entry {
+                    self.tooltip_entry = entry;
+                    control.tooltip(self.tooltip_lines(entry));
+                    if entry != Entry::None {
+                        self.hover_fade = 0.0;
+                        control.request_animation(true);
+                    }
+                }
             }
 
+            EventKind::MouseLeftRelease if self.box_select.is_some() => {
+                self.box_select = None;
+                control.release_mouse();
+                control.redraw();
+            }
+            // `ESC` breaks the capture
+            EventKind::LostFocus if self.box_select.is_some() => {
+                self.box_select = None;
+                control.redraw();
+            }
             EventKind::MouseLeftRelease if self.dropdown_defer => (),
             EventKind::MouseLeftRelease
             | EventKind::MouseRightRelease => {
@@ -1040,8 +3413,8 @@ impl super::Widget for ModListWidget {
                             control.redraw();
                         }
                     } else if self.can_drag {
-                        let (swap_to, _) = self.get_slot((x, y));
-                        if self.move_selected(swap_to) {
+                        let (swap_to, _, in_group) = self.get_slot((x, y));
+                        if self.move_selected(swap_to, in_group) {
                             if clicked < swap_to {
                                 self.selected_pivot = swap_to - 1;
                             } else {
@@ -1050,7 +3423,7 @@ impl super::Widget for ModListWidget {
                             if is_inside {
                                 self.can_hover = true;
                             }
-                            self.update_mod_lorder();
+                            self.save_mod_lorder();
                             control.redraw();
                         }
                     }
@@ -1058,7 +3431,10 @@ impl super::Widget for ModListWidget {
 
                 if is_right && self.dropdown_defer {
                     self.can_hover = true;
-                    if self.selected.is_empty() {
+                    if let Entry::Builtin(i) = self.get_entry((x, y)) {
+                        self.builtin_menu = i;
+                        DropdownWidget::show(control, x, y, DropdownMenu::Builtin);
+                    } else if self.selected.is_empty() {
                         DropdownWidget::show(control, x, y, DropdownMenu::Meta);
                     } else {
                         DropdownWidget::show(control, x, y, DropdownMenu::ModSelected);
@@ -1084,10 +3460,162 @@ impl super::Widget for ModListWidget {
             //    self.mouse_hover_mod = None;
             //}
 
+            EventKind::MouseLeftPress if self.drag_drop.state == DragDropState::Selecting => {
+                // (folder path, index into the selection for mod folders)
+                let rows: Vec<(Option<String>, Option<usize>)> = self.drag_drop.selection_tree()
+                    .iter()
+                    .skip(self.drag_drop.tree_scroll)
+                    .map(|row| (
+                        row.kind.is_dir().then(|| row.path.to_string()),
+                        self.drag_drop.selection.iter()
+                            .position(|(name, _)| row.depth == 1 && name == row.name),
+                    ))
+                    .collect();
+                let len = rows.len();
+                let [left, ..] = self.preview_rect();
+                match self.preview_row((x, y), len + 2) {
+                    Some(row) if row < len => {
+                        // the `+` of mod folders expands them, the rest of the
+                        // row toggles them
+                        let marker = left + Self::TREE_CHECK_WIDTH;
+                        let on_marker = x >= marker as i32 && x < (marker + Self::TREE_INDENT) as i32;
+                        match &rows[row] {
+                            (_, Some(i)) if !on_marker => self.drag_drop.toggle_selection(*i),
+                            (Some(path), _) => {
+                                self.drag_drop.toggle_folder(path);
+                                true
+                            }
+                            _ => false,
+                        };
+                        control.redraw();
+                    }
+                    Some(row) if row == len => {
+                        self.drag_drop.install_selection();
+                        control.redraw();
+                    }
+                    Some(row) if row == len + 1 => {
+                        self.drag_drop.clear();
+                        control.redraw();
+                    }
+                    _ => (),
+                }
+            }
+
+            EventKind::MouseLeftPress if self.sort_preview.is_some() => {
+                match self.preview_row((x, y), 2) {
+                    Some(0) => {
+                        let preview = self.sort_preview.take().unwrap();
+                        self.backup_mod_lorder();
+                        self.lorder.apply_sort(&preview);
+                        self.save_mod_lorder();
+                        control.redraw();
+                    }
+                    Some(1) => {
+                        self.sort_preview = None;
+                        control.redraw();
+                    }
+                    _ => (),
+                }
+            }
+
+            EventKind::MouseLeftPress if self.disable_prompt.is_some() => {
+                match self.preview_row((x, y), 2) {
+                    Some(0) => {
+                        let (_, dependents) = self.disable_prompt.take().unwrap();
+                        for name in dependents {
+                            if let Some(i) = self.lorder.mods.iter().position(|m| m.name() == name) {
+                                self.toggle_mod(i, Some(false));
+                            }
+                        }
+                        self.save_mod_lorder();
+                        control.redraw();
+                    }
+                    Some(1) => {
+                        self.disable_prompt = None;
+                        control.redraw();
+                    }
+                    _ => (),
+                }
+            }
+
+            EventKind::MouseLeftPress if self.normalize_prompt.is_some() => {
+                match self.preview_row((x, y), 2) {
+                    Some(0) => {
+                        let folders = self.normalize_prompt.take().unwrap();
+                        if let Err(err) = self.normalize_folders(&folders) {
+                            let text = format!("failed to rename mod folders: {err}");
+                            crate::log::log(&text);
+                            self.notice = Some(Notice::error(text));
+                        }
+                        control.redraw();
+                    }
+                    Some(1) => {
+                        self.normalize_prompt = None;
+                        control.redraw();
+                    }
+                    _ => (),
+                }
+            }
+
             EventKind::MouseLeftPress if self.dropdown_defer => (),
+            EventKind::MouseLeftPress if y >= top
+                && y < Self::LIST_TOP as i32
+                && let Some(column) = Self::column_at(x) =>
+            {
+                self.sort_view(column);
+                control.redraw();
+            }
+            // patching is harmless so there's no confirm like unpatching
+            EventKind::MouseLeftPress if self.patch_missing() && {
+                let [left, top, right, bottom] = Self::patch_button_rect();
+                x >= left && x < right && y >= top && y < bottom
+            } => {
+                self.toggle_patch(control);
+                control.redraw();
+            }
+            EventKind::MouseLeftPress if {
+                let [left, top, right, bottom] = Self::FILTER_RECT;
+                x >= left && x < right && y >= top && y < bottom
+            } => {
+                self.filter_focus = true;
+                self.rename = None;
+                control.redraw();
+            }
+            // selects the group so it can be dragged as a unit
+            EventKind::MouseLeftPress if is_inside
+                && let Entry::Group(i) = self.get_entry((x, y)) =>
+            {
+                self.selected = self.lorder.group_range(i).collect();
+                self.selected_pivot = i;
+                self.select_defer = None;
+                self.active_mod = i;
+                self.clicked_mod = Some(i);
+                control.capture_mouse();
+                control.redraw();
+            }
+            // empty space below the last row starts a box selection
+            EventKind::MouseLeftPress if is_inside
+                && y >= Self::LIST_TOP as i32
+                && self.get_entry((x, y)) == Entry::None =>
+            {
+                self.filter_focus = false;
+                let base = match event.ctrl || event.shift {
+                    true => self.selected.clone(),
+                    false => Vec::new(),
+                };
+                self.selected = base.clone();
+                self.mouse_pos = (x, y);
+                self.box_select = Some(((x, self.content_y(y)), base));
+                control.capture_mouse();
+                control.redraw();
+            }
             EventKind::MouseLeftPress
             | EventKind::MouseRightPress => {
                 let is_right = event.kind == EventKind::MouseRightPress;
+                if self.filter_focus {
+                    self.filter_focus = false;
+                    control.redraw();
+                }
                 if is_inside {
                     self.dropdown_defer |= is_right;
                     self.clicked_mod = if let Entry::Mod(clicked) = self.get_entry((x, y)) {
@@ -1117,24 +3645,20 @@ impl super::Widget for ModListWidget {
                         //    DropdownWidget::show(control, x, y, DropdownMenu::ModSelected);
                         //    control.redraw();
                         } else if event.shift {
-                            let min = self.selected_pivot.min(clicked);
-                            let max = self.selected_pivot.max(clicked);
+                            let range = self.view_range(self.selected_pivot, clicked);
 
                             if event.ctrl {
                                 if !self.selected.contains(&self.selected_pivot) {
-                                    self.selected.retain(|&i| i < min || i > max);
+                                    self.selected.retain(|i| !range.contains(i));
                                 } else {
-                                    for i in min..=max {
+                                    for i in range {
                                         if !self.selected.contains(&i) {
                                             self.selected.push(i);
                                         }
                                     }
                                 }
                             } else {
-                                self.selected.clear();
-                                for i in min..=max {
-                                    self.selected.push(i);
-                                }
+                                self.selected = range;
                             }
                         } else {
                             self.selected_pivot = clicked;
@@ -1170,29 +3694,133 @@ impl super::Widget for ModListWidget {
                         && !self.selected.is_empty()
                     {
                         self.toggle_selected();
-                        self.update_mod_lorder();
+                        self.save_mod_lorder();
                         control.redraw();
                     } else if Entry::Builtin(0) == entry {
-                        self.toggle_patch();
+                        self.request_toggle_patch(control);
                         control.redraw();
                     }
                 }
             }
 
+            EventKind::MouseScroll(delta) if delta != 0
+                && !event.ctrl
+                && self.drag_drop.state == DragDropState::Selecting =>
+            {
+                let rows = self.drag_drop.selection_tree().len();
+                if self.drag_drop.scroll_tree(delta, rows) {
+                    control.redraw();
+                }
+            }
+
+            EventKind::MouseScroll(delta) if delta != 0 && event.ctrl => {
+                if !self.config.step_ui_scale(delta > 0) {
+                    return;
+                }
+                if let Err(err) = self.config.save(&self.mods_path) {
+                    crate::log::log(&format!("failed to save settings: {err}"));
+                }
+                control.set_scale(self.scale());
+            }
+
             EventKind::MouseScroll(delta) if delta != 0 => {
-                if self.update_scroll(delta, true) {
+                let mut scroll = self.scroll_target.unwrap_or(self.scroll);
+                for _ in 0..self.wheel_rows(event.shift) {
+                    if let Some(next) = self.scroll_step(scroll, delta, true) {
+                        scroll = next;
+                    }
+                }
+                if scroll == self.scroll_target.unwrap_or(self.scroll) {
+                    return;
+                }
+
+                if self.config.smooth_scroll {
+                    self.scroll_target = Some(scroll);
+                    control.request_animation(true);
+                } else {
+                    self.set_scroll(scroll);
                     control.redraw();
                 }
             }
 
-            EventKind::KeyDown(key) => {
+            EventKind::KeyDown(key) if self.rename.is_some() => {
                 match key {
-                    KeyKind::Space => {
-                        if self.toggle_selected() {
-                            self.update_mod_lorder();
-                            control.redraw();
+                    KeyKind::Enter => {
+                        let (i, name) = self.rename.take().unwrap();
+                        if let Err(err) = self.rename_mod(i, &name) {
+                            let text = format!("failed to rename mod: {err}");
+                            crate::log::log(&text);
+                            self.notice = Some(Notice::error(text));
                         }
                     }
+                    KeyKind::Escape => self.rename = None,
+                    KeyKind::Backspace => {
+                        self.rename.as_mut().unwrap().1.pop();
+                    }
+                    // `SPACE` and letters also arrive as a `Char`
+                    _ => (),
+                }
+                control.redraw();
+            }
+
+            EventKind::Char(c) if self.rename.is_some() => {
+                self.rename.as_mut().unwrap().1.push(c);
+                control.redraw();
+            }
+
+            EventKind::KeyDown(key) if self.filter_focus => {
+                match key {
+                    KeyKind::Enter => self.filter_focus = false,
+                    KeyKind::Escape => {
+                        self.filter_focus = false;
+                        self.set_filter(String::new());
+                    }
+                    KeyKind::Backspace => {
+                        let mut filter = self.filter.clone();
+                        filter.pop();
+                        self.set_filter(filter);
+                    }
+                    // `SPACE` and letters also arrive as a `Char`
+                    _ => (),
+                }
+                control.redraw();
+            }
+
+            EventKind::Char(c) if self.filter_focus => {
+                let filter = format!("{}{c}", self.filter);
+                self.set_filter(filter);
+                control.redraw();
+            }
+
+            // `SPACE` toggles the selection instead
+            EventKind::Char(c) if !c.is_whitespace()
+                && self.clicked_mod.is_none()
+                && self.jump_to(c) => control.redraw(),
+
+            EventKind::KeyDown(key) => {
+                match key {
+                    KeyKind::Space if self.toggle_selected() => {
+                        self.save_mod_lorder();
+                        control.redraw();
+                    }
+                    KeyKind::F if event.ctrl => {
+                        self.filter_focus = true;
+                        control.redraw();
+                    }
+                    KeyKind::Up
+                    | KeyKind::Down if event.ctrl && self.step_selected(key == KeyKind::Up) => {
+                        self.save_mod_lorder();
+                        control.redraw();
+                    }
+                    KeyKind::A if event.ctrl => {
+                        self.selected = (0..self.lorder.mods.len())
+                            .filter(|i| self.filter.is_empty() || self.is_visible(*i))
+                            .collect();
+                        self.selected_pivot = self.selected.first().copied().unwrap_or(0);
+                        control.redraw();
+                    }
+                    KeyKind::V if event.ctrl => self.paste(control),
+                    KeyKind::Delete => control.send_event(Control::MOD_LIST_WIDGET, ModListEvent::DeleteSelected as u32),
                     KeyKind::Escape => {
                         self.dropdown_defer = false;
                         self.clicked_mod = None;
@@ -1201,18 +3829,72 @@ impl super::Widget for ModListWidget {
                         self.select_defer = None;
                         self.drag_drop.clear();
                         self.drag_drop.error = None;
+                        self.notice = None;
                         control.redraw();
                     }
+                    _ => (),
+                }
+            }
+
+            EventKind::Show if !self.watching => {
+                self.watching = true;
+                let notify = control.dispatcher();
+                let res = super::dir_watch::watch(&self.mods_path, Self::ignore_change, move || {
+                    notify(ModListEvent::ModsChanged as u32);
+                });
+                if let Err(err) = res {
+                    crate::log::log(&format!("failed to watch mods directory: {err:?}"));
                 }
             }
 
             EventKind::Hide => DropdownWidget::hide(control),
 
+            EventKind::KeyFocus(ring) => {
+                self.focus_ring = ring;
+                control.redraw();
+            }
+            EventKind::KeyBlur => {
+                self.focus_ring = false;
+                control.redraw();
+            }
+
+            EventKind::Tick => {
+                if self.drag_drop.state == DragDropState::Listing {
+                    self.spinner += 1;
+                } else {
+                    control.tick(false);
+                }
+                control.redraw();
+            }
+
+            EventKind::Frame => {
+                let mut animating = false;
+                // eases toward the target, a third of the way every frame
+                match self.scroll_target {
+                    Some(target) if target != self.scroll => {
+                        let diff = target - self.scroll;
+                        self.scroll += match diff / 3 {
+                            0 => diff.signum(),
+                            step => step,
+                        };
+                        animating = true;
+                    }
+                    _ => self.scroll_target = None,
+                }
+                if self.hover_fade < 1.0 {
+                    self.hover_fade = (self.hover_fade + Self::HOVER_FADE_STEP).min(1.0);
+                    animating = true;
+                }
+
+                if animating {
+                    control.redraw();
+                } else {
+                    control.request_animation(false);
+                }
+            }
+
             EventKind::DragDrop => {
-                let notify = control.dispatcher();
-                self.drag_drop.drag_drop(move || {
-                    notify(ModListEvent::DragDropPoll as u32);
-                });
+                self.drop_files(control, event.ctrl, event.shift);
                 control.redraw();
             }
 
@@ -1220,8 +3902,17 @@ impl super::Widget for ModListWidget {
         }
     }
 
+    fn recreate(&mut self, assets: &super::Assets) {
+        self.background = assets.background.clone();
+        self.brush = assets.brush.clone();
+    }
+
     fn render(&mut self, context: &mut super::DrawScope) {
-        context.draw_bitmap(&self.background, None, None);
+        if self.theme.high_contrast {
+            Self::fallback(context, &self.brush, &self.theme);
+        } else {
+            context.draw_bitmap(&self.background, None, None);
+        }
 
         self.text_format.set_word_wrapping(crate::dxgi::WordWrapping::NoWrap).unwrap();
 
@@ -1231,69 +3922,89 @@ impl super::Widget for ModListWidget {
         let bottom = Self::MARGIN_Y + Self::HEIGHT_INNER;
         context.push_axis_aligned_clip(&[
             left as f32,
-            top as f32,
+            Self::LIST_TOP as f32,
             right as f32,
             bottom as f32,
         ]);
 
         let start = self.scroll / self.item_height;
-        let mut start = usize::try_from(start).unwrap();
+        let start = usize::try_from(start).unwrap();
         let mut offset = -(self.scroll % self.item_height);
+        let hovered = self.can_hover.then(|| self.get_entry(self.mouse_pos));
 
-        if start < self.builtins.len() {
-            for (i, builtin) in self.builtins[start..].iter().enumerate() {
-                let i = i + start;
-
-                let color = if i == 0 && !self.is_patched {
-                    Self::MOD_NOT_INSTALLED_RED
-                } else {
-                    Self::MOD_BUILTIN_GOLD
-                };
-
-                self.draw_mod(
-                    context,
-                    builtin,
-                    color,
-                    offset,
-                    Some(Entry::Builtin(i)) == self.can_hover.then(|| self.get_entry(self.mouse_pos)),
-                    false,
-                );
-                offset += self.item_height;
+        for &entry in self.rows().iter().skip(start) {
+            if offset >= Self::LIST_HEIGHT as i32 {
+                break;
             }
-        }
-        start = start.saturating_sub(self.builtins.len());
-
-        let mods = &self.lorder.mods;
-        if mods.len() > start {
-            for (i, m) in mods[start..].iter().enumerate() {
-                let i = i + start;
-                if offset >= Self::HEIGHT_INNER as i32 {
-                    break;
-                }
 
-                let color = match m.state {
-                    ModState::Enabled => Self::MOD_ENABLED_BLUE,
-                    ModState::Disabled => Self::MOD_DISABLED_GRAY,
-                    ModState::MissingEntry => Self::MOD_MISSING_ENTRY_ORANGE,
-                    ModState::NotInstalled => Self::MOD_NOT_INSTALLED_RED,
-                };
+            match entry {
+                Entry::Builtin(i) => {
+                    let color = if i == 0 && !self.is_patched {
+                        self.theme.not_installed
+                    } else {
+                        self.theme.builtin
+                    };
 
-                self.draw_mod(
-                    context,
-                    m.name(),
-                    color,
-                    offset,
-                    Some(Entry::Mod(i)) == self.can_hover.then(|| self.get_entry(self.mouse_pos)),
-                    self.selected.contains(&i),
-                );
-                offset += self.item_height;
+                    let (name, version) = &self.builtins[i];
+                    self.draw_mod(
+                        context,
+                        name,
+                        color,
+                        offset,
+                        Some(entry) == hovered,
+                        false,
+                    );
+                    if let Some(version) = version {
+                        self.draw_column(context, Column::Version, version, self.theme.disabled, offset);
+                    }
+                }
+                Entry::Group(i) => {
+                    let name = self.lorder.mods[i].group().unwrap_or_default();
+                    self.draw_mod(
+                        context,
+                        &format!("[{name}]"),
+                        self.theme.builtin,
+                        offset,
+                        Some(entry) == hovered,
+                        false,
+                    );
+                }
+                Entry::Mod(i) => self.render_mod(context, i, offset, Some(entry) == hovered),
+                Entry::None => (),
             }
+            offset += self.item_height;
         }
 
+        self.render_box_select(context);
         context.pop_axis_aligned_clip();
+        if self.lorder.mods.is_empty() {
+            self.render_empty(context);
+        }
+        self.render_header(context);
+        self.render_filter(context);
+        if self.patch_missing() {
+            self.render_patch_banner(context);
+        } else if self.config.show_legend {
+            self.render_legend(context);
+        }
+        if self.focus_ring {
+            self.brush.set_color(&self.theme.enabled);
+            context.draw_rounded_rect(
+                &self.brush,
+                [left, top, right, bottom].map(|b| b as f32),
+                2.0,
+                1.0,
+            );
+        }
 
-        if self.drag_drop.is_dragging() {
-            self.brush.set_color(&[0.0, 0.0, 0.0, 0.5]);
+        if self.drag_drop.is_dragging()
+            || self.drag_drop.state == DragDropState::Selecting
+            || self.drag_drop.state == DragDropState::Copying
+            || self.sort_preview.is_some()
+            || self.disable_prompt.is_some()
+            || self.normalize_prompt.is_some()
+        {
+            self.brush.set_color(&self.theme.dim);
             context.fill_rounded_rect(
                 &self.brush,
                 [left, top, right, bottom].map(|b| b as f32),
@@ -1302,9 +4013,9 @@ impl super::Widget for ModListWidget {
         }
 
         if self.can_drag {
-            self.brush.set_color(&Self::MOD_BUILTIN_GOLD);
+            self.brush.set_color(&self.theme.builtin);
 
-            let (_, draw_y) = self.get_slot(self.mouse_pos);
+            let (_, draw_y) = self.get_slot_row(self.mouse_pos);
             let from = [
                 Self::MARGIN_X as f32,
                 draw_y as f32,
@@ -1314,14 +4025,63 @@ impl super::Widget for ModListWidget {
                 draw_y as f32,
             ];
             context.draw_line(from, to, &self.brush, 3.0);
+            self.render_drag_ghost(context);
         }
 
-        if let Some(view) = &self.drag_drop.view {
+        let list = self.drag_drop.view.as_ref()
+            .map(ArchiveView::list)
+            .or(self.drag_drop.preview.as_ref());
+        if self.drag_drop.state == DragDropState::Selecting {
+            self.render_selection(context);
+        } else if self.drag_drop.state == DragDropState::Copying {
+            self.render_progress(context);
+        } else if let Some(list) = list {
             let item_height = self.item_height as u32;
             let left = left + Self::MOD_ENTRY_LENGTH as u32 + 16;
+
+            if self.drag_drop.is_dragging() && !self.drag_drop.conflicts.is_empty() {
+                let mut text = String::from("overwrites ");
+                for (i, name) in self.drag_drop.conflicts.iter().enumerate() {
+                    if i > 0 {
+                        text.push_str(", ");
+                    }
+                    text.push_str(name);
+                }
+                text.push_str(" (SHIFT skip, CTRL keep files, CTRL+SHIFT newer only)");
+
+                self.brush.set_color(&self.theme.missing_entry);
+                context.draw_text(
+                    text.as_ref(),
+                    &self.text_format,
+                    &self.brush,
+                    &[left, top, right - 8, top + item_height].map(|b| b as f32),
+                );
+            }
+
+            // warnings are drawn in the rows below the tree
+            let mut warnings = Vec::new();
+            if let Some(view) = &self.drag_drop.view {
+                for (path, err) in view.failed() {
+                    let name = path.file_name().unwrap_or(path.as_os_str());
+                    warnings.push(format!("failed {}: {err}", name.to_string_lossy()));
+                }
+
+                if !view.executables().is_empty() {
+                    let mut text = String::from("contains executables: ");
+                    for (i, path) in view.executables().iter().enumerate() {
+                        if i > 0 {
+                            text.push_str(", ");
+                        }
+                        text.push_str(path.rsplit('/').next().unwrap());
+                    }
+                    warnings.push(text);
+                }
+            }
+
             let top = top + item_height;
             let right = right - 8;
-            let bottom = bottom - item_height;
+            // the total is drawn in the first row below the tree
+            let bottom = bottom - item_height * (warnings.len() + 1) as u32;
 
             context.push_axis_aligned_clip(&[
                 left as f32,
@@ -1330,57 +4090,79 @@ impl super::Widget for ModListWidget {
                 bottom as f32,
             ]);
 
-            self.brush.set_color(&[0.7, 0.7, 0.7, 1.0]);
+            self.brush.set_color(&self.theme.secondary_text);
 
             let mut offset = top;
-            let mut in_mods = false;
-            let mut text = String::new();
-            for (name, ty, depth) in view.list().iter() {
+            for row in self.drag_drop.tree(list).iter().skip(self.drag_drop.tree_scroll) {
                 if offset >= bottom {
                     break;
                 }
 
-                if depth == 0 {
-                    in_mods = name == "mods";
-                } else if in_mods && depth > 1 {
-                    continue;
-                }
+                self.draw_tree_row(context, row, row.depth, [left, offset, right]);
+                offset += item_height;
+            }
 
-                let text = if (in_mods && depth > 0) || !ty.is_dir() {
-                    name
-                } else {
-                    text.clear();
-                    text.push_str(name);
-                    text.push('/');
-                    &text
-                };
+            context.pop_axis_aligned_clip();
 
-                let depth = depth as u32 * 8;
+            let files = list.walk("").filter(|(_, ty)| ty.is_file()).count();
+            let mut text = String::from("writes ");
+            format_size(&mut text, list.size());
+            let _ = write!(text, " in {files} files");
+            self.brush.set_color(&self.theme.builtin);
+            context.draw_text(
+                text.as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[left, bottom, right, bottom + item_height].map(|b| b as f32),
+            );
 
-                let rect = [
-                    (left + depth) as f32,
-                    offset as f32,
-                    right as f32,
-                    (offset + item_height) as f32,
-                ];
+            self.brush.set_color(&self.theme.error);
+            for (i, text) in warnings.iter().enumerate() {
+                let offset = bottom + item_height * (i + 1) as u32;
                 context.draw_text(
                     text.as_ref(),
                     &self.text_format,
                     &self.brush,
-                    &rect,
+                    &[left, offset, right, offset + item_height].map(|b| b as f32),
                 );
-                offset += item_height;
             }
-
-            context.pop_axis_aligned_clip();
-        } else if let Some(text) = &self.drag_drop.error {
+        } else if self.drag_drop.state == DragDropState::Listing {
+            // nothing listed yet, only the spinner is drawn
+        } else if let Some(preview) = &self.sort_preview {
+            let lines: Vec<String> = preview.moved.iter()
+                .map(|(name, from, to)| format!("{name}  {} -> {}", from + 1, to + 1))
+                .collect();
+            self.render_prompt(
+                context,
+                (&format!("sorting moves {} mods", preview.moved.len()), self.theme.builtin),
+                &[("Apply", self.theme.builtin), ("Cancel", self.theme.disabled)],
+                &lines,
+            );
+        } else if let Some((disabled, dependents)) = &self.disable_prompt {
+            self.render_prompt(
+                context,
+                (&format!("{} is required by", disabled.join(", ")), self.theme.error),
+                &[("Disable them too", self.theme.builtin), ("Keep enabled", self.theme.disabled)],
+                dependents,
+            );
+        } else if let Some(folders) = &self.normalize_prompt {
+            let lines: Vec<String> = folders.iter()
+                .map(|(folder, name)| format!("{folder} -> {name}"))
+                .collect();
+            self.render_prompt(
+                context,
+                (&format!("{} mods disabled by folder name", folders.len()), self.theme.missing_entry),
+                &[("Rename and disable in load order", self.theme.builtin), ("Ignore", self.theme.disabled)],
+                &lines,
+            );
+        } else if let Some((text, color)) = self.message() {
             let item_height = self.item_height as u32;
             let left = left + Self::MOD_ENTRY_LENGTH as u32 + 16;
             let top = top + item_height;
             let right = right - 8;
             let bottom = bottom - item_height;
 
-            self.brush.set_color(&[0.8, 0.2, 0.2, 1.0]);
+            self.brush.set_color(&color);
             self.text_format.set_word_wrapping(crate::dxgi::WordWrapping::Wrap).unwrap();
 
             context.draw_text(
@@ -1389,6 +4171,21 @@ impl super::Widget for ModListWidget {
                 &self.brush,
                 &[left, top, right, bottom].map(|b| b as f32),
             );
+        } else {
+            self.render_details(context);
+        }
+
+        if self.drag_drop.state == DragDropState::Listing {
+            let item_height = self.item_height as u32;
+            let [left, top, right, _] = self.preview_rect();
+            let text = format!("reading {}", ".".repeat(self.spinner % 4));
+            self.brush.set_color(&self.theme.builtin);
+            context.draw_text(
+                text.as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[left, top - item_height, right, top].map(|b| b as f32),
+            );
         }
     }
 }
@@ -1,3 +1,4 @@
+use std::any::Any;
 use std::sync::Mutex;
 use std::time::Duration;
 use std::time::Instant;
@@ -5,15 +6,30 @@ use std::path::PathBuf;
 
 use windows::core::w;
 use windows::Win32::Foundation::*;
+use windows::Win32::System::SystemServices::MK_CONTROL;
+use windows::Win32::System::SystemServices::MK_SHIFT;
+use windows::Win32::UI::HiDpi::GetDpiForWindow;
 use windows::Win32::UI::WindowsAndMessaging::*;
 use windows::Win32::UI::Input::KeyboardAndMouse::*;
 
+use windows::Win32::Graphics::Direct2D::ID2D1Bitmap;
+
+use crate::dxgi::CachedTarget;
 use crate::dxgi::DrawScope;
+use crate::dxgi::SolidColorBrush;
 
 pub mod button;
 pub mod list;
 pub mod dropdown;
+mod dir_watch;
 mod drop_target;
+mod file_dialog;
+mod clipboard;
+pub mod tooltip;
+pub mod confirm;
+pub mod settings;
+pub mod drop_hint;
+pub mod theme;
 
 pub trait Widget: Send + 'static {
     fn config(&self) -> WidgetConfig {
@@ -33,17 +49,70 @@ pub trait Widget: Send + 'static {
     );
 
     fn render(&mut self, context: &mut DrawScope);
+
+    // `ESC` with nothing captured closes the mod list unless a shown widget
+    // still has something to cancel
+    fn takes_escape(&self) -> bool {
+        false
+    }
+
+    // device dependent resources made again after the GPU was reset
+    fn recreate(&mut self, _assets: &Assets) {}
+
+    // the whole widget is blended with this, for fading in and out
+    fn opacity(&self) -> f32 {
+        1.0
+    }
+
+    // data from `ControlScope::deliver`, widgets downcast what they expect
+    fn receive(&mut self, _data: Box<dyn Any>) {}
+
+    // colors from the mod list's config, see `ControlScope::set_theme`
+    fn set_theme(&mut self, _theme: &theme::Theme) {}
+}
+
+// brush and bitmaps created by `DxgiContext`, see `Control::recreate`
+pub struct Assets {
+    pub brush: SolidColorBrush,
+    pub button_active: ID2D1Bitmap,
+    pub button_idle: ID2D1Bitmap,
+    pub background: ID2D1Bitmap,
 }
 
 #[derive(Default)]
 pub struct WidgetConfig {
     listen_double_click: bool,
+    // can take keyboard focus from a click or `TAB`
+    focusable: bool,
+    // higher layers draw on top and get the mouse first, widgets in the same
+    // layer keep the order they were added or raised in
+    z_index: i32,
+    // what's drawn below the widget's rect is blurred first
+    blur_behind: bool,
+}
+
+impl WidgetConfig {
+    const DIALOG_LAYER: i32 = 1;
+    const POPUP_LAYER: i32 = 2;
+    const TOOLTIP_LAYER: i32 = 3;
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum KeyKind {
     Space,
     Escape,
+    Enter,
+    Backspace,
+    F,
+    Up,
+    Down,
+    Left,
+    Right,
+    A,
+    Delete,
+    // handled by `Control` to move the keyboard focus
+    Tab,
+    V,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -58,10 +127,18 @@ pub enum EventKind {
     MouseEnter(bool),
     MouseLeave,
     KeyDown(KeyKind),
+    Char(char),
     LostFocus,
+    // keyboard focus, `true` when it came from `TAB` and should be drawn
+    KeyFocus(bool),
+    KeyBlur,
     Show,
     Hide,
     DragDrop,
+    // sent every `Control::TICK_MSEC` to widgets that asked for ticks
+    Tick,
+    // sent every `Control::FRAME_MSEC` to widgets that asked for frames
+    Frame,
     Custom(u32),
     None,
 }
@@ -103,10 +180,27 @@ impl Event {
                 let kind = match VIRTUAL_KEY(key) {
                     VK_SPACE => KeyKind::Space,
                     VK_ESCAPE => KeyKind::Escape,
+                    VK_RETURN => KeyKind::Enter,
+                    VK_BACK => KeyKind::Backspace,
+                    VK_F => KeyKind::F,
+                    VK_UP => KeyKind::Up,
+                    VK_DOWN => KeyKind::Down,
+                    VK_LEFT => KeyKind::Left,
+                    VK_RIGHT => KeyKind::Right,
+                    VK_A => KeyKind::A,
+                    VK_DELETE => KeyKind::Delete,
+                    VK_TAB => KeyKind::Tab,
+                    VK_V => KeyKind::V,
                     _ => return None,
                 };
                 EventKind::KeyDown(kind)
             }
+            WM_CHAR => {
+                match char::from_u32(w_param as u32) {
+                    Some(c) if !c.is_control() => EventKind::Char(c),
+                    _ => return None,
+                }
+            }
             _ => return None,
         };
 
@@ -115,8 +209,13 @@ impl Event {
         if kind == EventKind::MouseLeftPress
             || kind == EventKind::MouseRightPress
         {
-            ctrl = w_param & 0x0008 /*MK_CONTROL*/ != 0;
-            shift = w_param & 0x0004 /*MK_SHIFT*/ != 0;
+            ctrl = w_param & MK_CONTROL.0 as usize != 0;
+            shift = w_param & MK_SHIFT.0 as usize != 0;
+        } else if let EventKind::KeyDown(_) | EventKind::MouseScroll(_) = kind {
+            unsafe {
+                ctrl = GetKeyState(VK_CONTROL.0 as i32) < 0;
+                shift = GetKeyState(VK_SHIFT.0 as i32) < 0;
+            }
         }
 
         let mut pt = POINT {
@@ -162,6 +261,13 @@ impl Event {
         })
     }
 
+    // window to widget coordinates, `-1` stays off the window
+    fn unscale(mut self, scale: f32) -> Self {
+        self.x = (self.x as f32 / scale).floor() as i32;
+        self.y = (self.y as f32 / scale).floor() as i32;
+        self
+    }
+
     fn scope(&self, rect: [u32; 4]) -> Self {
         let mut out = self.clone();
         out.x -= rect[0] as i32;
@@ -188,9 +294,19 @@ enum WidgetEvent {
     Show(usize),
     Move(usize, usize, i32, i32),
     Resize(usize, u32, u32),
+    // `true` moves the widget to the top of its layer, `false` to the bottom
+    Raise(usize, bool),
     CaptureMouse(Option<usize>),
     SendEvent(usize, u32),
-    Redraw,
+    Deliver(usize, Box<dyn Any>),
+    // empty lines cancel the widget's tooltip
+    Tooltip(usize, Vec<String>),
+    Tick(usize, bool),
+    Frame(usize, bool),
+    Scale(f32),
+    Theme(Box<theme::Theme>),
+    ToggleKey(Option<u16>),
+    Redraw(usize),
 }
 
 struct WidgetState {
@@ -198,6 +314,11 @@ struct WidgetState {
     config: WidgetConfig,
     rect: [u32; 4],
     visible: bool,
+    // last render, reused for launcher frames and redrawn into the same
+    // target while its size holds
+    cache: Option<CachedTarget>,
+    // `cache` doesn't show the widget anymore, set by its own `redraw`
+    stale: bool,
 }
 
 impl WidgetState {
@@ -207,6 +328,8 @@ impl WidgetState {
             inner,
             rect: [0; 4],
             visible,
+            cache: None,
+            stale: true,
         }
     }
 }
@@ -216,8 +339,25 @@ pub struct Control {
     pub display: HWND,
     capture_mouse: Option<usize>,
     last: Option<usize>,
+    // gets `KeyDown` and `Char` wherever the mouse is
+    key_focus: Option<usize>,
     widgets: Vec<WidgetState>,
+    // widget indices from bottom to top
+    order: Vec<usize>,
     events: Vec<WidgetEvent>,
+    // from `Config::ui_scale` times the window DPI scale, widgets only see
+    // unscaled coordinates
+    ui_scale: f32,
+    dpi: u32,
+    // virtual key from `Config::toggle_key` that acts like the `MODS` button
+    toggle_key: Option<u16>,
+    // last theme from the list, kept for the fallback images after the
+    // device is recreated
+    theme: theme::Theme,
+    // window size the widgets were laid out for
+    size: (i32, i32),
+    // the launcher has a modal open, widgets are faded and ignore input
+    covered: bool,
 
     dirty: bool,
 
@@ -227,6 +367,14 @@ pub struct Control {
     dbl_click_height: i32,
     drag_files: Option<Vec<PathBuf>>,
 
+    // last mouse position and the tooltip waiting on `TOOLTIP_TIMER`
+    mouse: (i32, i32),
+    tooltip: Option<(usize, Vec<String>)>,
+    // widgets receiving `EventKind::Tick`
+    ticking: Vec<usize>,
+    // widgets receiving `EventKind::Frame`
+    animating: Vec<usize>,
+
     hooks: Vec<(HWND, unsafe extern "system" fn(
         hwnd: HWND,
         msg: u32,
@@ -240,8 +388,23 @@ unsafe impl Sync for Control {}
 
 impl Control {
     pub const MOD_LIST_WIDGET: usize = 0;
-    //pub const BUTTON_WIDGET: usize = 1;
+    pub const BUTTON_WIDGET: usize = 1;
     pub const DROPDOWN_WIDGET: usize = 2;
+    pub const TOOLTIP_WIDGET: usize = 3;
+    pub const CONFIRM_WIDGET: usize = 4;
+    pub const SETTINGS_WIDGET: usize = 5;
+    pub const DROP_HINT_WIDGET: usize = 6;
+
+    const COVERED_OPACITY: f32 = 0.3;
+    // standard deviation for widgets with `blur_behind`
+    const BLUR_DEVIATION: f32 = 4.0;
+
+    const TOOLTIP_TIMER: usize = 0x3d7;
+    const TOOLTIP_DELAY_MSEC: u32 = 600;
+    const TICK_TIMER: usize = 0x3d8;
+    const TICK_MSEC: u32 = 150;
+    const FRAME_TIMER: usize = 0x3d9;
+    const FRAME_MSEC: u32 = 16;
 
     const WM_PRIV_MOUSE: u32 = WM_APP + 0x333;
     const WM_PRIV_MOUSELEAVE: u32 = WM_APP + 0x334;
@@ -250,10 +413,15 @@ impl Control {
     const WM_PRIV_DRAGDROP: u32 = WM_APP + 0x337;
     const WM_PRIV_CUSTOM: u32 = WM_APP + 0x338;
 
+    #[allow(clippy::too_many_arguments)]
     pub fn hook(
         mod_list: list::ModListWidget,
         button: button::ButtonWidget,
         dropdown: dropdown::DropdownWidget,
+        tooltip: tooltip::TooltipWidget,
+        confirm: confirm::ConfirmWidget,
+        settings: settings::SettingsWidget,
+        drop_hint: drop_hint::DropHintWidget,
         hwnd: HWND,
     ) {
         let mut control = CONTROL.lock().unwrap();
@@ -267,16 +435,33 @@ impl Control {
         let width = u32::try_from(rect.right - rect.left).unwrap();
         let height = u32::try_from(rect.bottom - rect.top).unwrap();
 
+        let show_list = cfg!(debug_assertions) || mod_list.show_on_start();
+        let ui_scale = mod_list.scale();
+        let toggle_key = mod_list.toggle_key();
+        let theme = mod_list.theme();
+        let is_patched = mod_list.is_patched();
+        let dpi = match unsafe { GetDpiForWindow(hwnd) } {
+            0 => USER_DEFAULT_SCREEN_DPI,
+            dpi => dpi,
+        };
         let mut widgets = Vec::new();
-        widgets.push(WidgetState::new(Box::new(mod_list), cfg!(debug_assertions)));
+        widgets.push(WidgetState::new(Box::new(mod_list), show_list));
         widgets.push(WidgetState::new(Box::new(button), true));
         widgets.push(WidgetState::new(Box::new(dropdown), false));
-
+        widgets.push(WidgetState::new(Box::new(tooltip), false));
+        widgets.push(WidgetState::new(Box::new(confirm), false));
+        widgets.push(WidgetState::new(Box::new(settings), false));
+        widgets.push(WidgetState::new(Box::new(drop_hint), false));
         for widget in &mut widgets {
-            widget.rect = widget.inner.rect(width, height);
-            assert!(widget.rect[0] <= widget.rect[2]);
-            assert!(widget.rect[1] <= widget.rect[3]);
+            widget.inner.set_theme(&theme);
         }
+        widgets[Self::BUTTON_WIDGET].inner.receive(Box::new(is_patched));
+
+        let scale = ui_scale * dpi as f32 / USER_DEFAULT_SCREEN_DPI as f32;
+        layout(&mut widgets, width, height, scale);
+
+        let mut order: Vec<usize> = (0..widgets.len()).collect();
+        order.sort_by_key(|i| widgets[*i].config.z_index);
 
         let mut hooks = Vec::new();
         let mut display = None;
@@ -323,8 +508,16 @@ impl Control {
             display,
             capture_mouse: None,
             last: None,
+            key_focus: None,
             widgets,
+            order,
             events: Vec::new(),
+            ui_scale,
+            dpi,
+            toggle_key,
+            theme,
+            size: (rect.right - rect.left, rect.bottom - rect.top),
+            covered: false,
 
             dirty: false,
 
@@ -334,6 +527,11 @@ impl Control {
             dbl_click_height,
             drag_files: None,
 
+            mouse: (-1, -1),
+            tooltip: None,
+            ticking: Vec::new(),
+            animating: Vec::new(),
+
             hooks,
         });
 
@@ -341,17 +539,176 @@ impl Control {
         drop_target::DropTarget::start(hwnd, display);
     }
 
+    fn scale(&self) -> f32 {
+        self.ui_scale * self.dpi as f32 / USER_DEFAULT_SCREEN_DPI as f32
+    }
+
+    // lays out every widget again for a new scale or window size
+    fn relayout(&mut self, width: i32, height: i32) {
+        self.cancel_tooltip();
+        // the render target they were made from is replaced with the size
+        self.drop_caches();
+        self.size = (width, height);
+        let width = u32::try_from(width).unwrap_or(0);
+        let height = u32::try_from(height).unwrap_or(0);
+        let scale = self.scale();
+        layout(&mut self.widgets, width, height, scale);
+    }
+
+    // called from the `UpdateLayeredWindow` hook before every `render`
+    pub fn resize(&mut self, width: i32, height: i32) {
+        if (width, height) != self.size {
+            self.relayout(width, height);
+        }
+    }
+
+    // `suggested` is the window rect from `WM_DPICHANGED`, applied after
+    // the original `wnd_proc` handles the message
+    fn dpi_changed(&mut self, dpi: u32, suggested: &RECT) {
+        if dpi != 0 && dpi != self.dpi {
+            self.dpi = dpi;
+            self.relayout(suggested.right - suggested.left, suggested.bottom - suggested.top);
+            if !self.dirty {
+                self.dirty = true;
+                update_display(&self.display);
+            }
+        }
+    }
+
     fn drag_enter(&mut self, files: &mut Vec<PathBuf>) -> bool {
         self.drag_files = Some(core::mem::take(files));
         true
     }
 
+    fn escape_closes_list(&self) -> bool {
+        self.capture_mouse.is_none()
+            && self.widgets[Self::MOD_LIST_WIDGET].visible
+            && !self.widgets.iter().any(|w| w.visible && w.inner.takes_escape())
+    }
+
+    // `WM_KEYDOWN` and `WM_SYSKEYDOWN` (`F10` is a system key), returns true
+    // when the message is taken
+    //
+    // dialogs capturing the mouse keep the key
+    fn hotkey(&mut self, msg: u32, w_param: WPARAM, l_param: LPARAM) -> bool {
+        if self.toggle_key != Some(w_param.0 as u16)
+            || self.covered
+            || self.capture_mouse.is_some()
+        {
+            return false;
+        }
+        // ignore auto repeat and let key ups through to the launcher except
+        // for `WM_SYSKEYUP` which would open the window menu on `F10`
+        let repeat = l_param.0 & (1 << 30) != 0;
+        if (msg == WM_KEYDOWN || msg == WM_SYSKEYDOWN) && !repeat {
+            self.events.push(WidgetEvent::Toggle(Self::MOD_LIST_WIDGET));
+            self.drain_events();
+        }
+        msg != WM_KEYUP
+    }
+
+    // hint over the whole window while a drag hasn't reached the list
+    fn drop_hint(&mut self, show: bool) {
+        let widget = &mut self.widgets[Self::DROP_HINT_WIDGET];
+        if widget.visible != show {
+            widget.visible = show;
+            widget.stale = true;
+            if !self.dirty {
+                self.dirty = true;
+                update_display(&self.display);
+            }
+        }
+    }
+
+    // returns true if a shown tooltip was hidden
+    fn cancel_tooltip(&mut self) -> bool {
+        unsafe {
+            let _ = KillTimer(Some(self.display), Self::TOOLTIP_TIMER);
+        }
+        self.tooltip = None;
+
+        let widget = &mut self.widgets[Self::TOOLTIP_WIDGET];
+        let visible = widget.visible;
+        widget.visible = false;
+        visible
+    }
+
+    fn show_tooltip(&mut self) {
+        unsafe {
+            let _ = KillTimer(Some(self.display), Self::TOOLTIP_TIMER);
+        }
+        let Some((owner, lines)) = self.tooltip.take() else {
+            return;
+        };
+        // the mouse moved on to another widget while waiting
+        if self.last != Some(owner) || !self.widgets[owner].visible {
+            return;
+        }
+
+        let mut window = RECT::default();
+        unsafe {
+            let _ = GetWindowRect(self.hwnd, &mut window);
+        }
+        let window_width = ((window.right - window.left) as f32 / self.scale()) as i32;
+        let window_height = ((window.bottom - window.top) as f32 / self.scale()) as i32;
+
+        // below and right of the cursor unless that leaves the window
+        let (width, height) = tooltip::TooltipWidget::size(&lines);
+        let (width, height) = (width as i32, height as i32);
+        let (x, y) = self.mouse;
+        let x = (x + 12).min(window_width - width).max(0);
+        let y = if y + 20 + height > window_height {
+            (y - height - 4).max(0)
+        } else {
+            y + 20
+        };
+
+        let widget = &mut self.widgets[Self::TOOLTIP_WIDGET];
+        widget.inner.receive(Box::new(lines));
+        widget.stale = true;
+        widget.rect = [x as u32, y as u32, (x + width) as u32, (y + height) as u32];
+        widget.visible = true;
+        self.scope_widget(Self::TOOLTIP_WIDGET, Event {
+            kind: EventKind::Show,
+            ..Default::default()
+        });
+
+        if !self.dirty {
+            self.dirty = true;
+            update_display(&self.display);
+        }
+    }
+
+    fn tick(&mut self, kind: EventKind) {
+        let widgets = match kind {
+            EventKind::Frame => &self.animating,
+            _ => &self.ticking,
+        };
+        // hidden widgets too so they can stop ticking
+        for i in widgets.clone() {
+            self.scope_widget(i, Event {
+                kind,
+                ..Default::default()
+            });
+        }
+    }
+
+    // to the top or bottom of the widget's `WidgetConfig::z_index` layer
+    fn raise(&mut self, widget: usize, top: bool) {
+        let z = self.widgets[widget].config.z_index;
+        self.order.retain(|i| *i != widget);
+        let at = match top {
+            true => self.order.iter().position(|i| self.widgets[*i].config.z_index > z),
+            false => self.order.iter().position(|i| self.widgets[*i].config.z_index >= z),
+        };
+        self.order.insert(at.unwrap_or(self.order.len()), widget);
+    }
+
     fn test_widgets(&self, x: i32, y: i32) -> Option<usize> {
         let x = u32::try_from(x).ok()?;
         let y = u32::try_from(y).ok()?;
 
-        for i in 0..self.widgets.len() {
-            let i = self.widgets.len() - 1 - i;
+        for &i in self.order.iter().rev() {
             let widget = &self.widgets[i];
             if !widget.visible {
                 continue;
@@ -372,6 +729,37 @@ impl Control {
         None
     }
 
+    pub fn theme(&self) -> theme::Theme {
+        self.theme
+    }
+
+    // after `DxgiContext::recreate` nothing made with the old device can be
+    // drawn, including the cached renders
+    pub fn recreate(&mut self, assets: &Assets) {
+        for widget in &mut self.widgets {
+            widget.inner.recreate(assets);
+        }
+        self.drop_caches();
+        if !self.dirty {
+            self.dirty = true;
+            update_display(&self.display);
+        }
+    }
+
+    // every widget is rendered again, the targets are kept
+    fn invalidate(&mut self) {
+        for widget in &mut self.widgets {
+            widget.stale = true;
+        }
+    }
+
+    fn drop_caches(&mut self) {
+        for widget in &mut self.widgets {
+            widget.cache = None;
+            widget.stale = true;
+        }
+    }
+
     fn scope_widget(&mut self, i: usize, event: Event) {
         let mut scope = ControlScope {
             hwnd: self.display,
@@ -389,6 +777,10 @@ impl Control {
             return;
         };
 
+        if self.tooltip.as_ref().is_some_and(|(owner, _)| *owner == last) {
+            self.cancel_tooltip();
+        }
+
         let widget = &mut self.widgets[last];
         let mut event = event_.scope(widget.rect);
         event.kind = EventKind::MouseLeave;
@@ -396,6 +788,45 @@ impl Control {
         self.last = None;
     }
 
+    fn set_key_focus(&mut self, target: Option<usize>, ring: bool) {
+        if target == self.key_focus && !ring {
+            return;
+        }
+        if let Some(old) = self.key_focus.take() {
+            self.scope_widget(old, Event {
+                kind: EventKind::KeyBlur,
+                ..Default::default()
+            });
+        }
+        self.key_focus = target;
+        if let Some(i) = target {
+            self.scope_widget(i, Event {
+                kind: EventKind::KeyFocus(ring),
+                ..Default::default()
+            });
+        }
+        if !self.dirty {
+            self.dirty = true;
+            update_display(&self.display);
+        }
+    }
+
+    // next shown focusable widget from bottom to top, `SHIFT+TAB` goes back
+    fn cycle_key_focus(&mut self, back: bool) {
+        let mut focusable: Vec<usize> = self.order.iter()
+            .copied()
+            .filter(|i| self.widgets[*i].visible && self.widgets[*i].config.focusable)
+            .collect();
+        if back {
+            focusable.reverse();
+        }
+        let next = match self.key_focus.and_then(|f| focusable.iter().position(|i| *i == f)) {
+            Some(pos) => focusable.get(pos + 1).or(focusable.first()),
+            None => focusable.first(),
+        };
+        self.set_key_focus(next.copied(), true);
+    }
+
     fn lost_focus(&mut self) {
         let Some(i) = self.capture_mouse.take() else {
             return;
@@ -415,9 +846,43 @@ impl Control {
         let y = event_.y;
         let mut target = self.test_widgets(x, y);
 
+        if event_.kind == EventKind::KeyDown(KeyKind::Tab) && self.capture_mouse.is_none() {
+            self.cycle_key_focus(event_.shift);
+            return true;
+        }
+        if event_.kind == EventKind::KeyDown(KeyKind::Escape) && self.escape_closes_list() {
+            self.events.push(WidgetEvent::Hide(Self::MOD_LIST_WIDGET));
+            self.drain_events();
+            return true;
+        }
+        if matches!(event_.kind, EventKind::MouseLeftPress | EventKind::MouseRightPress) {
+            let focus = target.filter(|i| self.widgets[*i].config.focusable);
+            self.set_key_focus(focus, false);
+        }
+        let key_target = match event_.kind {
+            EventKind::KeyDown(_) | EventKind::Char(_) => self.key_focus,
+            _ => None,
+        };
+
+        if let EventKind::MouseMove(_) = event_.kind {
+            self.mouse = (x, y);
+        } else if matches!(
+            event_.kind,
+            EventKind::MouseLeftPress
+                | EventKind::MouseRightPress
+                | EventKind::MouseScroll(_)
+                | EventKind::KeyDown(_),
+        ) && self.cancel_tooltip()
+            && !self.dirty
+        {
+            self.dirty = true;
+            update_display(&self.display);
+        }
+
         if target.is_none()
             && self.capture_mouse.is_none()
             && self.last.is_none()
+            && key_target.is_none()
         {
             return false;
         }
@@ -440,7 +905,7 @@ impl Control {
             self.lost_focus();
         }
 
-        target = self.capture_mouse.or(target);
+        target = self.capture_mouse.or(key_target).or(target);
 
         if let Some(i) = target {
             let widget = &mut self.widgets[i];
@@ -472,13 +937,70 @@ impl Control {
     }
 
     pub fn render(&mut self, draw: &mut DrawScope) {
-        for widget in &mut self.widgets {
-            if widget.visible {
-                draw.set_translation(widget.rect[0] as f32, widget.rect[1] as f32);
+        let covered = is_covered(self.hwnd) || is_covered(self.display);
+        if covered && !self.covered {
+            self.cancel_tooltip();
+            self.lost_focus();
+        }
+        self.covered = covered;
+
+        let scale = self.scale();
+        for &i in &self.order {
+            let widget = &mut self.widgets[i];
+            if !widget.visible {
+                widget.stale = true;
+                continue;
+            }
+
+            // widgets can draw past `rect` like the button's status text, so
+            // the cache covers everything right of and below it
+            let x = (widget.rect[0] as f32 * scale) as i32;
+            let y = (widget.rect[1] as f32 * scale) as i32;
+            let width = u32::try_from(self.size.0 - x).unwrap_or(0);
+            let height = u32::try_from(self.size.1 - y).unwrap_or(0);
+            let resized = widget.cache.as_ref().is_some_and(|cache| cache.size() != (width, height));
+            if (widget.stale || resized) && width > 0 && height > 0 {
+                let res = draw.begin_cached(&mut widget.cache, width, height)
+                    .and_then(|mut target| {
+                        target.set_transform(0.0, 0.0, scale);
+                        widget.inner.render(&mut target);
+                        target.finish()
+                    });
+                // a failed render isn't kept, it's tried again next frame
+                widget.stale = res.is_err();
+            }
+
+            if widget.config.blur_behind {
+                let [x0, y0, x1, y1] = widget.rect.map(|b| (b as f32 * scale) as u32);
+                let (w, h) = (self.size.0.max(0) as u32, self.size.1.max(0) as u32);
+                let rect = [x0.min(w), y0.min(h), x1.min(w), y1.min(h)];
+                draw.set_transform(0.0, 0.0, 1.0);
+                if let Err(err) = draw.blur(rect, Self::BLUR_DEVIATION) {
+                    crate::log::log(&format!("failed to blur behind widget: {err}"));
+                    widget.config.blur_behind = false;
+                }
+            }
+
+            let dest = [x as f32, y as f32, x as f32 + width as f32, y as f32 + height as f32];
+            let mut opacity = widget.inner.opacity();
+            if covered {
+                opacity *= Self::COVERED_OPACITY;
+            }
+            draw.set_transform(0.0, 0.0, 1.0);
+            let faded = opacity < 1.0 && draw.push_layer(opacity, dest).is_ok();
+            if let Some(cache) = &widget.cache
+                && !widget.stale
+            {
+                draw.draw_bitmap(cache.bitmap(), Some(&dest), None);
+            } else {
+                draw.set_transform(widget.rect[0] as f32, widget.rect[1] as f32, scale);
                 widget.inner.render(draw);
             }
+            if faded {
+                draw.pop_layer();
+            }
         }
-        draw.set_translation(0.0, 0.0);
+        draw.set_transform(0.0, 0.0, 1.0);
 
         self.dirty = false;
     }
@@ -489,21 +1011,32 @@ impl Control {
         let mut redraw = false;
         let mut post_events = Vec::new();
         for event in events.drain(..) {
+            // hidden widgets have to lose key focus and get `Hide` like any
+            let event = match event {
+                WidgetEvent::Toggle(target) if self.widgets[target].visible => WidgetEvent::Hide(target),
+                WidgetEvent::Toggle(target) => WidgetEvent::Show(target),
+                event => event,
+            };
             match event {
-                WidgetEvent::Toggle(widget) => {
-                    let widget = &mut self.widgets[widget];
-                    widget.visible = !widget.visible;
-                    redraw = true;
-                }
+                WidgetEvent::Toggle(_) => unreachable!(),
                 WidgetEvent::Hide(target) => {
+                    redraw |= target != Self::TOOLTIP_WIDGET && self.cancel_tooltip();
                     let widget = &mut self.widgets[target];
                     if widget.visible {
                         widget.visible = false;
                         redraw = true;
                         post_events.push((target, EventKind::Hide));
                     }
+                    if self.key_focus == Some(target) {
+                        self.key_focus = None;
+                        post_events.push((target, EventKind::KeyBlur));
+                    }
                 }
                 WidgetEvent::Show(target) => {
+                    // shown widgets open on top, already shown ones stay put
+                    if !self.widgets[target].visible {
+                        self.raise(target, true);
+                    }
                     let widget = &mut self.widgets[target];
                     if !widget.visible {
                         widget.visible = true;
@@ -532,10 +1065,68 @@ impl Control {
                     let widget = &mut self.widgets[widget];
                     widget.rect[2] = widget.rect[0] + width;
                     widget.rect[3] = widget.rect[1] + height;
+                    widget.stale = true;
+                }
+                WidgetEvent::Raise(widget, top) => {
+                    self.raise(widget, top);
+                    redraw = true;
                 }
                 WidgetEvent::CaptureMouse(capture_) => capture = Some(capture_),
                 WidgetEvent::SendEvent(target, event) => post_events.push((target, EventKind::Custom(event))),
-                WidgetEvent::Redraw => redraw = true,
+                // before any `Show` so it's there when the widget is shown
+                WidgetEvent::Deliver(target, data) => {
+                    let widget = &mut self.widgets[target];
+                    widget.inner.receive(data);
+                    widget.stale = true;
+                    redraw = true;
+                }
+                WidgetEvent::Tooltip(owner, lines) => {
+                    redraw |= self.cancel_tooltip();
+                    if !lines.is_empty() {
+                        self.tooltip = Some((owner, lines));
+                        unsafe {
+                            SetTimer(Some(self.display), Self::TOOLTIP_TIMER, Self::TOOLTIP_DELAY_MSEC, None);
+                        }
+                    }
+                }
+                WidgetEvent::Tick(widget, enable) => set_ticking(
+                    self.display,
+                    &mut self.ticking,
+                    widget,
+                    enable,
+                    (Self::TICK_TIMER, Self::TICK_MSEC),
+                ),
+                WidgetEvent::Frame(widget, enable) => set_ticking(
+                    self.display,
+                    &mut self.animating,
+                    widget,
+                    enable,
+                    (Self::FRAME_TIMER, Self::FRAME_MSEC),
+                ),
+                WidgetEvent::Scale(scale) => {
+                    if scale != self.ui_scale {
+                        self.ui_scale = scale;
+                        let mut window = RECT::default();
+                        unsafe {
+                            let _ = GetWindowRect(self.hwnd, &mut window);
+                        }
+                        self.relayout(window.right - window.left, window.bottom - window.top);
+                        redraw = true;
+                    }
+                }
+                WidgetEvent::Theme(theme) => {
+                    self.theme = *theme;
+                    for widget in &mut self.widgets {
+                        widget.inner.set_theme(&theme);
+                    }
+                    self.invalidate();
+                    redraw = true;
+                }
+                WidgetEvent::ToggleKey(key) => self.toggle_key = key,
+                WidgetEvent::Redraw(target) => {
+                    self.widgets[target].stale = true;
+                    redraw = true;
+                }
             }
         }
         self.events = events;
@@ -597,6 +1188,16 @@ impl<'a> ControlScope<'a> {
         self.events.push(WidgetEvent::Resize(widget, width, height));
     }
 
+    // within the widget's `WidgetConfig::z_index` layer, widgets are raised
+    // when they're shown
+    pub fn raise(&mut self, widget: usize) {
+        self.events.push(WidgetEvent::Raise(widget, true));
+    }
+
+    pub fn lower(&mut self, widget: usize) {
+        self.events.push(WidgetEvent::Raise(widget, false));
+    }
+
     pub fn toggle_widget(&mut self, widget: usize) {
         self.events.push(WidgetEvent::Toggle(widget));
     }
@@ -613,6 +1214,27 @@ impl<'a> ControlScope<'a> {
         self.events.push(WidgetEvent::SendEvent(target, event));
     }
 
+    // hands `data` to the `Widget::receive` of `target`
+    pub fn deliver(&mut self, target: usize, data: impl Any) {
+        self.events.push(WidgetEvent::Deliver(target, Box::new(data)));
+    }
+
+    // starts or stops `EventKind::Tick` for animations
+    pub fn tick(&mut self, enable: bool) {
+        self.events.push(WidgetEvent::Tick(self.widget, enable));
+    }
+
+    // like `tick` but every frame for smooth movement and fades, widgets
+    // stop it themselves once they're done animating
+    pub fn request_animation(&mut self, enable: bool) {
+        self.events.push(WidgetEvent::Frame(self.widget, enable));
+    }
+
+    // shown near the mouse after a short hover, an empty `lines` cancels it
+    pub fn tooltip(&mut self, lines: Vec<String>) {
+        self.events.push(WidgetEvent::Tooltip(self.widget, lines));
+    }
+
     pub fn dispatcher(&self) -> Box<dyn Fn(u32) + Send + Sync + 'static> {
         let hwnd_ = self.hwnd.0 as usize;
         let widget = self.widget;
@@ -631,7 +1253,66 @@ impl<'a> ControlScope<'a> {
     }
 
     pub fn redraw(&mut self) {
-        self.events.push(WidgetEvent::Redraw);
+        self.events.push(WidgetEvent::Redraw(self.widget));
+    }
+
+    // `Config::ui_scale` as a factor, the window DPI scale is applied on top
+    //
+    // every widget is laid out again, moved widgets go back to their `rect`
+    pub fn set_scale(&mut self, scale: f32) {
+        self.events.push(WidgetEvent::Scale(scale));
+    }
+
+    // hands the colors to every widget and redraws
+    pub fn set_theme(&mut self, theme: theme::Theme) {
+        self.events.push(WidgetEvent::Theme(Box::new(theme)));
+    }
+
+    // `None` turns the hotkey off
+    pub fn set_toggle_key(&mut self, key: Option<u16>) {
+        self.events.push(WidgetEvent::ToggleKey(key));
+    }
+}
+
+// a disabled window or an enabled popup means the launcher opened its own
+// settings or a dialog on top
+fn is_covered(hwnd: HWND) -> bool {
+    unsafe {
+        !IsWindowEnabled(hwnd).as_bool()
+            || GetWindow(hwnd, GW_ENABLEDPOPUP).is_ok_and(|popup| popup != hwnd)
+    }
+}
+
+// the timer runs while any widget is in `widgets`
+fn set_ticking(
+    display: HWND,
+    widgets: &mut Vec<usize>,
+    widget: usize,
+    enable: bool,
+    (timer, msec): (usize, u32),
+) {
+    let was_ticking = !widgets.is_empty();
+    widgets.retain(|w| *w != widget);
+    if enable {
+        widgets.push(widget);
+    }
+    unsafe {
+        if !was_ticking && enable {
+            SetTimer(Some(display), timer, msec, None);
+        } else if was_ticking && widgets.is_empty() {
+            let _ = KillTimer(Some(display), timer);
+        }
+    }
+}
+
+// `rect` gets the window size in unscaled coordinates
+fn layout(widgets: &mut [WidgetState], width: u32, height: u32, scale: f32) {
+    let width = (width as f32 / scale) as u32;
+    let height = (height as f32 / scale) as u32;
+    for widget in widgets {
+        widget.rect = widget.inner.rect(width, height);
+        assert!(widget.rect[0] <= widget.rect[2]);
+        assert!(widget.rect[1] <= widget.rect[3]);
     }
 }
 
@@ -650,6 +1331,13 @@ unsafe extern "system" fn wnd_proc(
             .find_map(|(check, hook)| (*check == hwnd).then_some(hook))
             .unwrap();
 
+        if matches!(msg, WM_KEYDOWN | WM_SYSKEYDOWN | WM_KEYUP | WM_SYSKEYUP)
+            && control.hotkey(msg, w_param, l_param)
+        {
+            return Ok(0);
+        }
+
+        let scale = control.scale();
         let event = if msg == Control::WM_PRIV_MOUSE {
             Event::from_msg(&control.hwnd, l_param.0 as u32, w_param.0)
         } else if msg == Control::WM_PRIV_DRAGMOVE
@@ -670,8 +1358,8 @@ unsafe extern "system" fn wnd_proc(
                         } else {
                             EventKind::DragDrop
                         },
-                        ctrl: false,
-                        shift: false,
+                        ctrl: w_param.0 & MK_CONTROL.0 as usize != 0,
+                        shift: w_param.0 & MK_SHIFT.0 as usize != 0,
                         x,
                         y,
                     })
@@ -681,9 +1369,14 @@ unsafe extern "system" fn wnd_proc(
             }
         } else {
             Event::from_msg(&control.hwnd, msg, w_param.0)
-        };
+        }.filter(|_| !control.covered).map(|event| event.unscale(scale));
 
         if let Some(event) = event {
+            if msg == Control::WM_PRIV_DRAGMOVE || msg == Control::WM_PRIV_DRAGDROP {
+                let over_list = control.test_widgets(event.x, event.y) == Some(Control::MOD_LIST_WIDGET);
+                control.drop_hint(msg == Control::WM_PRIV_DRAGMOVE && !over_list);
+            }
+
             if control.test_widgets(event.x, event.y).is_some() {
                 if msg != Control::WM_PRIV_MOUSE {
                     control.handle_event(event);
@@ -692,7 +1385,12 @@ unsafe extern "system" fn wnd_proc(
                 if Event::can_capture(msg) {
                     return Ok(0);
                 }
-            } else if msg == Control::WM_PRIV_MOUSE {
+            } else if msg == Control::WM_PRIV_MOUSE
+                // keys go to the focused widget even with the mouse outside
+                || (control.key_focus.is_some()
+                    && matches!(event.kind, EventKind::KeyDown(_) | EventKind::Char(_)))
+                || (event.kind == EventKind::KeyDown(KeyKind::Escape) && control.escape_closes_list())
+            {
                 control.handle_event(event);
                 return Ok(0);
             } else if Event::can_capture(msg) && control.capture_mouse.is_some() {
@@ -712,6 +1410,7 @@ unsafe extern "system" fn wnd_proc(
                 ..Default::default()
             });
             control.drag_files = None;
+            control.drop_hint(false);
         } else if msg == Control::WM_PRIV_CUSTOM {
             let widget = l_param.0 as u32;
             let event = (l_param.0 >> 32) as u32;
@@ -719,6 +1418,27 @@ unsafe extern "system" fn wnd_proc(
                 kind: EventKind::Custom(event),
                 ..Default::default()
             });
+        } else if msg == WM_TIMER && w_param.0 == Control::TOOLTIP_TIMER {
+            control.show_tooltip();
+            return Ok(0);
+        } else if msg == WM_TIMER && w_param.0 == Control::TICK_TIMER {
+            control.tick(EventKind::Tick);
+            return Ok(0);
+        } else if msg == WM_TIMER && w_param.0 == Control::FRAME_TIMER {
+            control.tick(EventKind::Frame);
+            return Ok(0);
+        } else if msg == WM_DPICHANGED && hwnd == control.hwnd {
+            let dpi = w_param.0 as u32 & 0xffff;
+            let suggested = unsafe { &*(l_param.0 as *const RECT) };
+            control.dpi_changed(dpi, suggested);
+        } else if msg == WM_SYSCOLORCHANGE
+            || (msg == WM_SETTINGCHANGE && w_param.0 == SPI_SETHIGHCONTRAST.0 as usize)
+        {
+            // the list reloads the theme with the config
+            control.scope_widget(Control::MOD_LIST_WIDGET, Event {
+                kind: EventKind::Custom(list::ModListEvent::ConfigChanged as u32),
+                ..Default::default()
+            });
         } else if msg == WM_KILLFOCUS {
             control.lost_focus();
         } else if msg == WM_NCDESTROY {
@@ -0,0 +1,303 @@
+use std::cell::Cell;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::dxgi::SolidColorBrush;
+use crate::dxgi::TextFormat;
+
+use super::Control;
+use super::ControlScope;
+use super::Event;
+use super::EventKind;
+use super::KeyKind;
+use super::list::ModListEvent;
+use super::list::ModListWidget;
+use super::WidgetConfig;
+use super::theme::Theme;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Row {
+    AutoEnable,
+    ShowOnStart,
+    ShowLegend,
+    ScrollSpeed,
+    SmoothScroll,
+    UiScale,
+    ScanDepth,
+    Colors,
+}
+
+// modal like `ConfirmWidget`, every change is saved right away
+pub struct SettingsWidget {
+    brush: SolidColorBrush,
+    theme: Theme,
+    text_format: TextFormat,
+    mods_path: PathBuf,
+
+    // window size from the last `rect`
+    size: Cell<(u32, u32)>,
+
+    config: Config,
+    hovered_option: Option<usize>,
+}
+
+impl SettingsWidget {
+    const ROWS: [(Row, &str); 8] = [
+        (Row::AutoEnable, "Enable mods after installing"),
+        (Row::ShowOnStart, "Show mod list on start"),
+        (Row::ShowLegend, "Show color legend"),
+        (Row::ScrollSpeed, "Scroll speed (x Windows lines)"),
+        (Row::SmoothScroll, "Smooth scrolling"),
+        (Row::UiScale, "UI scale % (CTRL + scroll)"),
+        (Row::ScanDepth, "Mod subfolder depth"),
+        (Row::Colors, "Colors"),
+    ];
+
+    const BOX_WIDTH: u32 = 460;
+    const PADDING: u32 = 12;
+    const LINE_HEIGHT: u32 = 26;
+    const VALUE_WIDTH: u32 = 140;
+
+    pub fn new(
+        brush: SolidColorBrush,
+        text_format: TextFormat,
+        mods_path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            brush,
+            theme: Theme::DEFAULT,
+            text_format,
+            mods_path: mods_path.into(),
+
+            size: Cell::new((0, 0)),
+
+            config: Config::default(),
+            hovered_option: None,
+        }
+    }
+
+    // header row then one row per setting
+    fn box_rect(&self) -> [u32; 4] {
+        let (width, height) = self.size.get();
+        let box_height = Self::PADDING * 2 + Self::LINE_HEIGHT * (Self::ROWS.len() as u32 + 1);
+        let left = width.saturating_sub(Self::BOX_WIDTH) / 2;
+        let top = height.saturating_sub(box_height) / 2;
+        [left, top, left + Self::BOX_WIDTH, top + box_height]
+    }
+
+    fn row_rect(&self, i: usize) -> [u32; 4] {
+        let [left, top, right, _] = self.box_rect();
+        let top = top + Self::PADDING + Self::LINE_HEIGHT * (i as u32 + 1);
+        [left + Self::PADDING, top, right - Self::PADDING, top + Self::LINE_HEIGHT]
+    }
+
+    fn row_at(&self, x: i32, y: i32) -> Option<usize> {
+        let (Ok(x), Ok(y)) = (u32::try_from(x), u32::try_from(y)) else {
+            return None;
+        };
+        (0..Self::ROWS.len()).find(|&i| {
+            let [x0, y0, x1, y1] = self.row_rect(i);
+            x >= x0 && x < x1 && y >= y0 && y < y1
+        })
+    }
+
+    fn is_inside(&self, x: i32, y: i32) -> bool {
+        let [x0, y0, x1, y1] = self.box_rect().map(|b| b as i32);
+        x >= x0 && x < x1 && y >= y0 && y < y1
+    }
+
+    fn value(&self, row: Row) -> String {
+        let on_off = |b: bool| if b { "on" } else { "off" }.to_string();
+        match row {
+            Row::AutoEnable => on_off(self.config.auto_enable),
+            Row::ShowOnStart => on_off(self.config.show_on_start),
+            Row::ShowLegend => on_off(self.config.show_legend),
+            Row::ScrollSpeed => format!("- {} +", self.config.scroll_speed),
+            Row::SmoothScroll => on_off(self.config.smooth_scroll),
+            Row::UiScale => format!("- {} +", self.config.ui_scale),
+            Row::ScanDepth => format!("- {} +", self.config.scan_depth),
+            Row::Colors => "edit config.toml".to_string(),
+        }
+    }
+
+    // `up` is false for right clicks and `LEFT`
+    fn change(&mut self, control: &mut ControlScope, i: usize, up: bool) {
+        let Some((row, _)) = Self::ROWS.get(i) else {
+            return;
+        };
+        match row {
+            Row::AutoEnable => self.config.auto_enable = !self.config.auto_enable,
+            Row::ShowOnStart => self.config.show_on_start = !self.config.show_on_start,
+            Row::ShowLegend => self.config.show_legend = !self.config.show_legend,
+            Row::ScrollSpeed => {
+                let speed = match up {
+                    true => self.config.scroll_speed + 1,
+                    false => self.config.scroll_speed.saturating_sub(1),
+                };
+                self.config.scroll_speed = speed.clamp(1, Config::MAX_SCROLL_SPEED);
+            }
+            Row::SmoothScroll => self.config.smooth_scroll = !self.config.smooth_scroll,
+            Row::UiScale => {
+                self.config.step_ui_scale(up);
+            }
+            Row::ScanDepth => {
+                let depth = match up {
+                    true => self.config.scan_depth + 1,
+                    false => self.config.scan_depth.saturating_sub(1),
+                };
+                self.config.scan_depth = depth.clamp(1, Config::MAX_SCAN_DEPTH);
+            }
+            Row::Colors => {
+                // write out every color so there's something to edit
+                if self.config.colors.is_empty() {
+                    self.config.colors = self.theme.colors();
+                }
+                self.save();
+                ModListWidget::open(&crate::config::path(&self.mods_path));
+                return;
+            }
+        }
+        self.save();
+        ModListWidget::send(control, ModListEvent::ConfigChanged);
+        control.redraw();
+    }
+
+    fn save(&self) {
+        if let Err(err) = self.config.save(&self.mods_path) {
+            crate::log::log(&format!("failed to save settings: {err}"));
+        }
+    }
+
+    fn close(control: &mut ControlScope) {
+        control.hide_widget(Control::SETTINGS_WIDGET);
+    }
+}
+
+impl super::Widget for SettingsWidget {
+    fn config(&self) -> WidgetConfig {
+        WidgetConfig {
+            z_index: WidgetConfig::DIALOG_LAYER,
+            ..Default::default()
+        }
+    }
+
+    fn rect(&self, width: u32, height: u32) -> [u32; 4] {
+        self.size.set((width, height));
+        [0, 0, width, height]
+    }
+
+    fn handle_event(
+        &mut self,
+        control: &mut ControlScope,
+        event: Event,
+    ) {
+        match event.kind {
+            EventKind::Show => {
+                self.config = Config::load(&self.mods_path).unwrap_or_else(|err| {
+                    crate::log::log(&format!("failed to load settings: {err}"));
+                    Config::default()
+                });
+                self.hovered_option = None;
+                control.capture_mouse();
+            }
+            EventKind::Hide => {
+                control.release_mouse();
+                // picks up colors edited while the panel was open
+                ModListWidget::send(control, ModListEvent::ConfigChanged);
+            }
+            // `ESC` breaks the capture
+            EventKind::LostFocus => Self::close(control),
+            EventKind::MouseMove(_) => {
+                let hovered = self.row_at(event.x, event.y);
+                if hovered != self.hovered_option {
+                    self.hovered_option = hovered;
+                    control.redraw();
+                }
+            }
+            EventKind::MouseLeftRelease
+            | EventKind::MouseRightRelease => match self.row_at(event.x, event.y) {
+                Some(i) => self.change(control, i, event.kind == EventKind::MouseLeftRelease),
+                None if !self.is_inside(event.x, event.y) => Self::close(control),
+                None => (),
+            },
+            EventKind::KeyDown(key) => {
+                let len = Self::ROWS.len();
+                match (key, self.hovered_option) {
+                    (KeyKind::Up, Some(i)) => self.hovered_option = Some((i + len - 1) % len),
+                    (KeyKind::Down, Some(i)) => self.hovered_option = Some((i + 1) % len),
+                    (KeyKind::Up | KeyKind::Down, None) => self.hovered_option = Some(0),
+                    (KeyKind::Enter | KeyKind::Space | KeyKind::Right, Some(i)) => self.change(control, i, true),
+                    (KeyKind::Left, Some(i)) => self.change(control, i, false),
+                    _ => return,
+                }
+                control.redraw();
+            }
+            _ => (),
+        }
+    }
+
+    fn set_theme(&mut self, theme: &Theme) {
+        self.theme = *theme;
+    }
+
+    fn recreate(&mut self, assets: &super::Assets) {
+        self.brush = assets.brush.clone();
+    }
+
+    fn render(&mut self, context: &mut super::DrawScope) {
+        let theme = self.theme;
+        let (width, height) = self.size.get();
+        self.brush.set_color(&theme.dim);
+        context.fill_rounded_rect(
+            &self.brush,
+            [0.0, 0.0, width as f32, height as f32],
+            0.0,
+        );
+
+        let rect = self.box_rect().map(|b| b as f32);
+        self.brush.set_color(&theme.background);
+        context.fill_rounded_rect(&self.brush, rect, 2.0);
+        self.brush.set_color(&theme.border);
+        context.draw_rounded_rect(&self.brush, rect, 2.0, 2.0);
+
+        let [left, top, right, _] = self.box_rect();
+        self.brush.set_color(&theme.text);
+        context.draw_text(
+            "Settings (ESC to close)".as_ref(),
+            &self.text_format,
+            &self.brush,
+            &[
+                left + Self::PADDING,
+                top + Self::PADDING,
+                right - Self::PADDING,
+                top + Self::PADDING + Self::LINE_HEIGHT,
+            ].map(|b| b as f32),
+        );
+
+        for (i, (row, text)) in Self::ROWS.iter().enumerate() {
+            let [x0, y0, x1, y1] = self.row_rect(i);
+            if Some(i) == self.hovered_option {
+                self.brush.set_color(&theme.highlight);
+                context.fill_rounded_rect(&self.brush, [x0, y0, x1, y1].map(|b| b as f32), 2.0);
+            }
+
+            self.brush.set_color(&theme.text);
+            context.draw_text(
+                text.as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[x0 + 4, y0, x1 - Self::VALUE_WIDTH, y1].map(|b| b as f32),
+            );
+
+            self.brush.set_color(&theme.secondary_text);
+            self.text_format.set_text_alignment(crate::dxgi::Alignment::Max).unwrap();
+            context.draw_text(
+                self.value(*row).as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[x1 - Self::VALUE_WIDTH, y0, x1 - 4, y1].map(|b| b as f32),
+            );
+            self.text_format.set_text_alignment(crate::dxgi::Alignment::Min).unwrap();
+        }
+    }
+}
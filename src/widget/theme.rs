@@ -0,0 +1,140 @@
+use windows::Win32::Graphics::Gdi::*;
+use windows::Win32::UI::Accessibility::*;
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+use crate::config::Config;
+
+// every widget color, each can be overridden in the `[colors]` table of the config
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub builtin: [f32; 4],
+    pub enabled: [f32; 4],
+    pub disabled: [f32; 4],
+    pub missing_entry: [f32; 4],
+    pub not_installed: [f32; 4],
+    // hovered mod row
+    pub hover: [f32; 4],
+    pub error: [f32; 4],
+    pub text: [f32; 4],
+    pub secondary_text: [f32; 4],
+    // behind modals and busy lists
+    pub dim: [f32; 4],
+    // menus, tooltips and modals
+    pub background: [f32; 4],
+    pub highlight: [f32; 4],
+    pub border: [f32; 4],
+    // fallbacks when the launcher images aren't found
+    pub panel: [f32; 4],
+    pub button_active: [f32; 4],
+    // Windows high contrast is on, the translucent launcher images are
+    // replaced by solid fallbacks
+    pub high_contrast: bool,
+}
+
+impl Theme {
+    pub const DEFAULT: Self = Self {
+        builtin: [220.0 / 255.0, 190.0 / 255.0, 60.0 / 255.0, 1.0],
+        enabled: [71.0 / 255.0, 196.0 / 255.0, 208.0 / 255.0, 1.0],
+        disabled: [102.0 / 255.0, 102.0 / 255.0, 102.0 / 255.0, 1.0],
+        missing_entry: [0.8, 0.5, 0.0, 1.0],
+        not_installed: [0.6, 0.2, 0.2, 1.0],
+        hover: [0.2, 0.2, 0.2, 0.5],
+        error: [0.8, 0.2, 0.2, 1.0],
+        text: [1.0, 1.0, 1.0, 1.0],
+        secondary_text: [0.7, 0.7, 0.7, 1.0],
+        dim: [0.0, 0.0, 0.0, 0.5],
+        background: [0.05, 0.05, 0.05, 1.0],
+        highlight: [0.15, 0.15, 0.15, 1.0],
+        border: [0.6, 0.6, 0.6, 1.0],
+        panel: [0.0, 0.0, 0.0, 0.8],
+        button_active: [0.2, 0.2, 0.2, 0.8],
+        high_contrast: false,
+    };
+
+    // solid colors from the system high contrast scheme, mod states keep
+    // their default colors
+    fn high_contrast() -> Self {
+        let color = |index, alpha| {
+            let rgb = unsafe { GetSysColor(index) };
+            [
+                (rgb & 0xff) as f32 / 255.0,
+                ((rgb >> 8) & 0xff) as f32 / 255.0,
+                ((rgb >> 16) & 0xff) as f32 / 255.0,
+                alpha,
+            ]
+        };
+        Self {
+            disabled: color(COLOR_GRAYTEXT, 1.0),
+            hover: color(COLOR_HIGHLIGHT, 0.4),
+            text: color(COLOR_WINDOWTEXT, 1.0),
+            secondary_text: color(COLOR_GRAYTEXT, 1.0),
+            dim: color(COLOR_WINDOW, 0.85),
+            background: color(COLOR_WINDOW, 1.0),
+            highlight: color(COLOR_HIGHLIGHT, 0.4),
+            border: color(COLOR_WINDOWTEXT, 1.0),
+            panel: color(COLOR_WINDOW, 1.0),
+            button_active: color(COLOR_HIGHLIGHT, 1.0),
+            high_contrast: true,
+            ..Self::DEFAULT
+        }
+    }
+
+    pub fn is_high_contrast() -> bool {
+        let mut hc = HIGHCONTRASTW {
+            cbSize: core::mem::size_of::<HIGHCONTRASTW>() as u32,
+            ..Default::default()
+        };
+        unsafe {
+            SystemParametersInfoW(
+                SPI_GETHIGHCONTRAST,
+                hc.cbSize,
+                Some(&mut hc as *mut HIGHCONTRASTW as *mut _),
+                Default::default(),
+            ).is_ok() && hc.dwFlags.contains(HCF_HIGHCONTRASTON)
+        }
+    }
+
+    // names used in the config
+    fn fields(&mut self) -> [(&'static str, &mut [f32; 4]); 15] {
+        [
+            ("builtin", &mut self.builtin),
+            ("enabled", &mut self.enabled),
+            ("disabled", &mut self.disabled),
+            ("missing_entry", &mut self.missing_entry),
+            ("not_installed", &mut self.not_installed),
+            ("hover", &mut self.hover),
+            ("error", &mut self.error),
+            ("text", &mut self.text),
+            ("secondary_text", &mut self.secondary_text),
+            ("dim", &mut self.dim),
+            ("background", &mut self.background),
+            ("highlight", &mut self.highlight),
+            ("border", &mut self.border),
+            ("panel", &mut self.panel),
+            ("button_active", &mut self.button_active),
+        ]
+    }
+
+    // `[colors]` is ignored under high contrast since `Colors` in the
+    // settings writes out every default
+    pub fn from_config(config: &Config) -> Self {
+        if Self::is_high_contrast() {
+            return Self::high_contrast();
+        }
+        let mut theme = Self::DEFAULT;
+        for (name, color) in theme.fields() {
+            if let Some(c) = config.color(name) {
+                *color = c;
+            }
+        }
+        theme
+    }
+
+    // every color by name, written out so users can see what to change
+    pub fn colors(mut self) -> Vec<(String, [f32; 4])> {
+        self.fields()
+            .into_iter()
+            .map(|(name, color)| (name.to_string(), *color))
+            .collect()
+    }
+}
@@ -0,0 +1,121 @@
+use std::any::Any;
+
+use crate::dxgi::SolidColorBrush;
+use crate::dxgi::TextFormat;
+
+use super::ControlScope;
+use super::Event;
+use super::WidgetConfig;
+use super::theme::Theme;
+
+pub struct TooltipWidget {
+    brush: SolidColorBrush,
+    theme: Theme,
+    text_format: TextFormat,
+
+    lines: Vec<String>,
+}
+
+impl TooltipWidget {
+    const PADDING: u32 = 6;
+    const LINE_HEIGHT: u32 = 20;
+    // lines aren't measured so the width is a guess from the longest line
+    const CHAR_WIDTH: u32 = 8;
+    const MAX_WIDTH: u32 = 600;
+
+    pub fn new(
+        brush: SolidColorBrush,
+        text_format: TextFormat,
+    ) -> Self {
+        Self {
+            brush,
+            theme: Theme::DEFAULT,
+            text_format,
+
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn size(lines: &[String]) -> (u32, u32) {
+        let chars = lines.iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0) as u32;
+        let width = (chars * Self::CHAR_WIDTH + Self::PADDING * 2).min(Self::MAX_WIDTH);
+        let height = lines.len() as u32 * Self::LINE_HEIGHT + Self::PADDING * 2;
+        (width, height)
+    }
+}
+
+impl super::Widget for TooltipWidget {
+    fn config(&self) -> WidgetConfig {
+        WidgetConfig {
+            z_index: WidgetConfig::TOOLTIP_LAYER,
+            ..Default::default()
+        }
+    }
+
+    fn rect(&self, _width: u32, _height: u32) -> [u32; 4] {
+        [0, 0, 0, 0]
+    }
+
+    // never takes the mouse from the widget below
+    fn hit_test(&self, _x: u32, _y: u32) -> bool {
+        false
+    }
+
+    fn handle_event(
+        &mut self,
+        _control: &mut ControlScope,
+        _event: Event,
+    ) {
+    }
+
+    // lines for the next shown tooltip, from `Control::show_tooltip`
+    fn receive(&mut self, data: Box<dyn Any>) {
+        if let Ok(lines) = data.downcast() {
+            self.lines = *lines;
+        }
+    }
+
+    fn set_theme(&mut self, theme: &Theme) {
+        self.theme = *theme;
+    }
+
+    fn recreate(&mut self, assets: &super::Assets) {
+        self.brush = assets.brush.clone();
+    }
+
+    fn render(&mut self, context: &mut super::DrawScope) {
+        let theme = self.theme;
+        let (width, height) = Self::size(&self.lines);
+        let rect = [
+            1.0,
+            1.0,
+            width as f32 - 1.0,
+            height as f32 - 1.0,
+        ];
+
+        self.brush.set_color(&theme.background);
+        context.fill_rounded_rect(&self.brush, rect, 2.0);
+        self.brush.set_color(&theme.border);
+        context.draw_rounded_rect(&self.brush, rect, 2.0, 1.0);
+
+        self.brush.set_color(&theme.text);
+        let mut o = Self::PADDING;
+        for line in &self.lines {
+            context.draw_text(
+                line.as_ref(),
+                &self.text_format,
+                &self.brush,
+                &[
+                    Self::PADDING as f32,
+                    o as f32,
+                    (width - Self::PADDING) as f32,
+                    (o + Self::LINE_HEIGHT) as f32,
+                ],
+            );
+            o += Self::LINE_HEIGHT;
+        }
+    }
+}